@@ -58,14 +58,6 @@ const CHINESE: Language = Language::new_fostered(&["zh"]);
 const CHINESE_TRADITIONAL: Language = Language::new_fostered(&["zh", "tw"]);
 const CHINESE_SIMPLIFIED: Language = Language::new_fostered(&["zh", "cn"]);
 
-// Already-compressed media types
-const COMPRESSED_MEDIA_TYPES: &[MediaType] = &[
-    MediaType::new_fostered("image", "png"),
-    MediaType::new_fostered("image", "jpeg"),
-    MediaType::new_fostered("audio", "mpeg"),
-    MediaType::new_fostered("video", "mpeg"),
-];
-
 #[main]
 async fn main() {
     utils::init_tracing();
@@ -110,6 +102,7 @@ async fn main() {
             get(([("XX-Cache-Duration", "1 ms")], "This response has a custom cache duration of 1 ms\n")),
         )
         .route("/quickie2", get(("This response also has a custom cache duration of 1 ms\n",)))
+        // (Already-compressed media types, such as PNG, are never encoded by default)
         .route(
             "/png",
             get(([("Content-Type", "image/png"), ("Content-Length", utils::TINY_PNG_SIZE)], utils::TINY_PNG)),
@@ -138,6 +131,7 @@ async fn main() {
             CachingLayer::default()
                 .cache(cache.clone())
                 .max_cacheable_body_size(MAX_BODY_SIZE)
+                .cache_status("kutil")
                 .cache_key(|context| {
                     // HTTP content negotiation for "/language"
                     if context.request.uri().path() == "/language" {
@@ -157,13 +151,6 @@ async fn main() {
                     // This is an alternative to using the `XX-Encode` header
                     context.uri.path() != "/neverencode2"
                 })
-                .encodable_by_response(|context| {
-                    // This is where we can disable encoding for already-compressed media types
-                    match context.headers.content_type() {
-                        Some(content_type) => !COMPRESSED_MEDIA_TYPES.contains(&content_type),
-                        None => true,
-                    }
-                })
                 .keep_identity_encoding(false),
         )
         .layer(TraceLayer::new_for_http());
@@ -0,0 +1,66 @@
+use super::{
+    super::std::{future::*, immutable::*},
+    headers::*,
+};
+
+use std::fmt;
+
+//
+// Authenticated
+//
+
+/// Authenticated identity.
+///
+/// Intended to be stored as a request extension, e.g. via
+/// [AuthenticationLayer](crate::http::tower::authentication::AuthenticationLayer), where it will
+/// be consumed by the handler and by the cache key
+/// ([CacheKey::apply_authenticated](crate::http::cache::CacheKey::apply_authenticated)).
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Authenticated {
+    /// Scheme (e.g. "Basic" or "Bearer").
+    pub scheme: ByteString,
+
+    /// Identity (e.g. a user ID).
+    ///
+    /// Must be unique per user, because cached responses are partitioned by it.
+    pub identity: ByteString,
+}
+
+impl Authenticated {
+    /// Constructor.
+    pub fn new(scheme: ByteString, identity: ByteString) -> Self {
+        Self { scheme, identity }
+    }
+}
+
+// Only the scheme, so that identities don't end up in logs
+impl fmt::Display for Authenticated {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "authenticated ({})", self.scheme)
+    }
+}
+
+//
+// CredentialsVerifier
+//
+
+/// Credentials verifier.
+///
+/// Implemented for functions (and closures) with the same signature as
+/// [verify](Self::verify).
+pub trait CredentialsVerifier {
+    /// Verify credentials.
+    ///
+    /// Returns the identity (see [Authenticated::identity]) or [None] if the credentials are
+    /// rejected.
+    fn verify(&self, credentials: Credentials) -> CapturedFuture<Option<ByteString>>;
+}
+
+impl<FunctionT> CredentialsVerifier for FunctionT
+where
+    FunctionT: Fn(Credentials) -> CapturedFuture<Option<ByteString>>,
+{
+    fn verify(&self, credentials: Credentials) -> CapturedFuture<Option<ByteString>> {
+        self(credentials)
+    }
+}
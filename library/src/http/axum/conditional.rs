@@ -0,0 +1,27 @@
+use super::super::headers::*;
+
+use ::axum::{
+    http::{HeaderMap, Method},
+    response::*,
+};
+
+/// Evaluate the request's preconditions in an axum handler.
+///
+/// Returns a 304 (Not Modified) or 412 (Precondition Failed) [Response] if the handler should *not*
+/// proceed. See [evaluate_preconditions].
+pub fn precondition_response(
+    method: &Method,
+    request_headers: &HeaderMap,
+    representation_headers: Option<&HeaderMap>,
+) -> Option<Response> {
+    let precondition = evaluate_preconditions(method, request_headers, representation_headers);
+    let status_code = precondition.status_code()?;
+
+    Some(match (precondition, representation_headers) {
+        (Precondition::NotModified, Some(representation_headers)) => {
+            (status_code, not_modified_headers(representation_headers)).into_response()
+        }
+
+        _ => status_code.into_response(),
+    })
+}
@@ -1,4 +1,7 @@
-use super::super::super::std::{collections::*, immutable::*};
+use super::super::{
+    super::std::{collections::*, immutable::*},
+    origin::*,
+};
 
 use {
     ::axum::{extract::*, http::StatusCode, response::*, *},
@@ -73,13 +76,20 @@ impl HostRouter {
 ///
 /// Expects the [HostRouter] to be available as state. See
 /// [Router::with_state](::axum::Router::with_state).
+///
+/// If the request has an [EffectiveOrigin] extension then its host will be used. See
+/// [EffectiveOriginLayer](crate::http::tower::origin::EffectiveOriginLayer). Note that otherwise
+/// the host might be taken from `Forwarded` and `X-Forwarded-Host` headers without validation.
 pub async fn host_router_handler(
     State(mut host_router): State<HostRouter>,
     Host(host_and_optional_port): Host,
     request: Request,
 ) -> Response {
-    host_router
-        .handle(host_and_optional_port.into(), request)
-        .await
-        .unwrap_or_else(|| StatusCode::NOT_FOUND.into_response())
+    let host_and_optional_port = request
+        .extensions()
+        .get::<EffectiveOrigin>()
+        .and_then(|origin| origin.host_and_optional_port())
+        .unwrap_or_else(|| host_and_optional_port.into());
+
+    host_router.handle(host_and_optional_port, request).await.unwrap_or_else(|| StatusCode::NOT_FOUND.into_response())
 }
@@ -1,5 +1,6 @@
+mod conditional;
 mod host_router;
 mod shutdown;
 
 #[allow(unused_imports)]
-pub use {host_router::*, shutdown::*};
+pub use {conditional::*, host_router::*, shutdown::*};
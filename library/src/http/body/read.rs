@@ -37,7 +37,8 @@ where
     /// If we read less than `min_size` *or* we did not read all the way to EOF will return a
     /// [ReadBodyError] with [FileTooLarge](io::ErrorKind::FileTooLarge) and [BodyPieces], the
     /// latter of which can be used by the caller to reconstruct the original body, e.g. with
-    /// [BodyReader::new_with_first_bytes](super::reader::BodyReader::new_with_first_bytes).
+    /// [BodyReader::new_with_first_bytes](super::reader::BodyReader::new_with_first_bytes). Note
+    /// that the pieces include any trailers that have already been read.
     async fn read_into_bytes_or_pieces(
         self,
         declared_size: Option<usize>,
@@ -111,7 +112,7 @@ where
         match reader.read_u8().await {
             Ok(byte) => {
                 println!("!!!!!!!!!!!!!!!!! {:?} {}", read_size, bytes.len());
-                let (body, remainder, _trailers) = reader.into_inner();
+                let (body, remainder, trailers) = reader.into_inner();
 
                 // Push back the byte we read and the remainder
                 bytes.put_u8(byte);
@@ -119,16 +120,19 @@ where
 
                 return Err(ErrorWithBodyPieces::new(
                     io::Error::new(io::ErrorKind::FileTooLarge, format!("body is bigger than {}", read_size)).into(),
-                    Some(BodyPieces::new(body, bytes.into())),
+                    Some(BodyPieces::new(body, bytes.into(), trailers)),
                 ));
             }
 
             Err(error) => {
                 // Actually, we *do* expect EOF :)
                 if error.kind() != io::ErrorKind::UnexpectedEof {
-                    let (body, remainder, _trailers) = reader.into_inner();
+                    let (body, remainder, trailers) = reader.into_inner();
                     bytes.put(remainder); // remainder *should* be empty
-                    return Err(ErrorWithBodyPieces::new(error.into(), Some(BodyPieces::new(body, bytes.into()))));
+                    return Err(ErrorWithBodyPieces::new(
+                        error.into(),
+                        Some(BodyPieces::new(body, bytes.into(), trailers)),
+                    ));
                 }
             }
         }
@@ -143,7 +147,7 @@ where
         }
 
         if fulfilled_size < min_size {
-            let (body, remainder, _trailers) = reader.into_inner();
+            let (body, remainder, trailers) = reader.into_inner();
             bytes.put(remainder); // remainder *should* be empty
             return Err(ErrorWithBodyPieces::new(
                 io::Error::new(
@@ -151,7 +155,7 @@ where
                     format!("body is too big: {} > {}", fulfilled_size, min_size),
                 )
                 .into(),
-                Some(BodyPieces::new(body, bytes.into())),
+                Some(BodyPieces::new(body, bytes.into(), trailers)),
             ));
         }
 
@@ -22,6 +22,13 @@ pub struct BodyReader<BodyT> {
 
     /// Trailers
     pub trailers: Vec<HeaderMap>,
+
+    /// Whether to yield after every data frame.
+    ///
+    /// See [with_yield_per_frame](Self::with_yield_per_frame).
+    pub yield_per_frame: bool,
+
+    yielding: bool,
 }
 
 impl<BodyT> BodyReader<BodyT> {
@@ -37,7 +44,18 @@ impl<BodyT> BodyReader<BodyT> {
             None => BytesMut::with_capacity(0),
         };
 
-        Self { body: Box::pin(body), remainder, trailers: Default::default() }
+        Self { body: Box::pin(body), remainder, trailers: Default::default(), yield_per_frame: false, yielding: false }
+    }
+
+    /// With yielding after every data frame.
+    ///
+    /// We will return [Poll::Pending] (after waking) once after every data frame has been fully
+    /// read. For wrappers that flush when their input is pending (e.g. the encoders in
+    /// [TranscodingReader](crate::transcoding::reader::TranscodingReader)) this means flushing
+    /// after every frame, which is important for streaming.
+    pub fn with_yield_per_frame(mut self) -> Self {
+        self.yield_per_frame = true;
+        self
     }
 
     /// Back to the inner [Body].
@@ -69,6 +87,12 @@ where
         context: &mut Context<'_>,
         buffer: &mut ReadBuf<'_>,
     ) -> Poll<io::Result<()>> {
+        if self.yielding {
+            self.yielding = false;
+            context.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
         // Copy as much as we can from the remainder
         if self.remainder.has_remaining() {
             let size = min(buffer.remaining_mut(), self.remainder.remaining());
@@ -77,10 +101,10 @@ where
                 let bytes = self.remainder.copy_to_bytes(size);
                 buffer.put(bytes);
 
-                if !buffer.has_remaining_mut() {
-                    // Buffer is full
-                    return Poll::Ready(Ok(()));
-                }
+                // Note that we must not poll the body now, because if it is pending we would have
+                // to return Poll::Pending, thus losing what we put in the buffer
+                self.yielding = self.yield_per_frame && !self.remainder.has_remaining();
+                return Poll::Ready(Ok(()));
             }
         }
 
@@ -101,6 +125,8 @@ where
                         if data.has_remaining() {
                             self.validate_remainder_capacity();
                             self.remainder.put(data);
+                        } else {
+                            self.yielding = self.yield_per_frame && (size != 0);
                         }
 
                         Ok(())
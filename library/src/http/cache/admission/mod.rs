@@ -0,0 +1,6 @@
+mod policy;
+mod sketch;
+mod window;
+
+#[allow(unused_imports)]
+pub use {policy::*, sketch::*, window::*};
@@ -0,0 +1,21 @@
+use super::super::key::*;
+
+//
+// AdmissionPolicy
+//
+
+/// Cache admission policy.
+///
+/// Decides whether a cacheable response should actually be stored, for example in order to keep
+/// "one-hit wonders" (URLs that are requested only once, e.g. by crawlers) from evicting hot
+/// entries.
+///
+/// Implementations should be cheap, because they are called for every cacheable miss.
+pub trait AdmissionPolicy<CacheKeyT = CommonCacheKey>
+where
+    Self: Send + Sync,
+    CacheKeyT: CacheKey,
+{
+    /// Record a request for the key and return whether its response should be stored.
+    fn admit(&self, key: &CacheKeyT) -> bool;
+}
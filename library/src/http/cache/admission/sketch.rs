@@ -0,0 +1,92 @@
+use super::{super::key::*, policy::*};
+
+use std::{hash::*, sync::atomic::*};
+
+const DEPTH: usize = 4;
+const MAX_COUNTER: u8 = 15;
+
+// Odd constants for deriving the row indexes from a single hash
+const SEEDS: [u64; DEPTH] =
+    [0x9e37_79b9_7f4a_7c15, 0xc2b2_ae3d_27d4_eb4f, 0x1656_67b1_9e37_79f9, 0x85eb_ca77_c2b2_ae63];
+
+//
+// FrequencySketchAdmission
+//
+
+/// TinyLFU-style [AdmissionPolicy] that admits a key once its estimated request frequency
+/// reaches a minimum.
+///
+/// Frequencies are estimated with a count-min sketch of small saturating counters (up to 15),
+/// so memory use is fixed (4 bytes per unit of capacity) regardless of how many unique keys are
+/// requested. Estimates may be too high (due to hash collisions) but are never too low.
+///
+/// In order to favor recent popularity, all counters are halved after every
+/// 10 × capacity requests.
+pub struct FrequencySketchAdmission {
+    /// Minimum estimated frequency.
+    pub min_frequency: u8,
+
+    counters: Box<[AtomicU8]>,
+    mask: usize,
+    sample_size: usize,
+    additions: AtomicUsize,
+    hasher: rapidhash::fast::RandomState,
+}
+
+impl FrequencySketchAdmission {
+    /// Constructor.
+    ///
+    /// The capacity should be about the number of entries the cache can hold.
+    pub fn new(min_frequency: u8, capacity: usize) -> Self {
+        let width = capacity.max(64).next_power_of_two();
+        Self {
+            min_frequency: min_frequency.min(MAX_COUNTER),
+            counters: (0..width * DEPTH).map(|_| AtomicU8::new(0)).collect(),
+            mask: width - 1,
+            sample_size: capacity.max(1).saturating_mul(10),
+            additions: Default::default(),
+            hasher: Default::default(),
+        }
+    }
+
+    /// Increment the frequency for a hash and return the new estimate.
+    fn increment(&self, hash: u64) -> u8 {
+        let width = self.mask + 1;
+        let mut estimate = MAX_COUNTER;
+
+        for (row, seed) in SEEDS.iter().enumerate() {
+            let index = row * width + ((hash.wrapping_mul(*seed) >> 32) as usize & self.mask);
+            let counter = &self.counters[index];
+            let value = match counter.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |value| {
+                if value < MAX_COUNTER { Some(value + 1) } else { None }
+            }) {
+                Ok(value) => value + 1,
+                Err(value) => value,
+            };
+            estimate = estimate.min(value);
+        }
+
+        if self.additions.fetch_add(1, Ordering::Relaxed) + 1 >= self.sample_size {
+            self.age();
+        }
+
+        estimate
+    }
+
+    /// Halve all counters.
+    fn age(&self) {
+        self.additions.store(0, Ordering::Relaxed);
+        for counter in &self.counters {
+            _ = counter.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |value| Some(value >> 1));
+        }
+    }
+}
+
+impl<CacheKeyT> AdmissionPolicy<CacheKeyT> for FrequencySketchAdmission
+where
+    CacheKeyT: CacheKey,
+{
+    fn admit(&self, key: &CacheKeyT) -> bool {
+        self.increment(self.hasher.hash_one(key)) >= self.min_frequency
+    }
+}
@@ -0,0 +1,71 @@
+use super::{
+    super::{super::super::std::collections::*, key::*},
+    policy::*,
+};
+
+use std::{hash::*, time::*};
+
+//
+// WindowAdmission
+//
+
+/// [AdmissionPolicy] that admits a key after a minimum number of requests within a time window.
+///
+/// Requests are counted per key hash in a [FastConcurrentHashMap]. The count is removed once
+/// the key is admitted, so if the entry is later evicted from the cache it will have to be
+/// requested again the minimum number of times.
+///
+/// If the number of tracked keys exceeds the capacity then expired windows are removed. If it
+/// is *still* exceeded then all counts are reset.
+pub struct WindowAdmission {
+    /// Minimum number of requests.
+    pub min_requests: usize,
+
+    /// Window.
+    pub window: Duration,
+
+    /// Capacity.
+    pub capacity: usize,
+
+    requests: FastConcurrentHashMap<u64, (Instant, usize)>,
+    hasher: rapidhash::fast::RandomState,
+}
+
+impl WindowAdmission {
+    /// Constructor.
+    pub fn new(min_requests: usize, window: Duration, capacity: usize) -> Self {
+        Self { min_requests, window, capacity, requests: Default::default(), hasher: Default::default() }
+    }
+}
+
+impl<CacheKeyT> AdmissionPolicy<CacheKeyT> for WindowAdmission
+where
+    CacheKeyT: CacheKey,
+{
+    fn admit(&self, key: &CacheKeyT) -> bool {
+        let hash = self.hasher.hash_one(key);
+        let now = Instant::now();
+        let mut requests = self.requests.pin();
+
+        let (_start, count) = *requests.update_or_insert(
+            hash,
+            |(start, count)| if now.duration_since(*start) > self.window { (now, 1) } else { (*start, count + 1) },
+            (now, 1),
+        );
+
+        if count >= self.min_requests {
+            requests.remove(&hash);
+            return true;
+        }
+
+        if requests.len() > self.capacity {
+            requests.retain(|_hash, (start, _count)| now.duration_since(*start) <= self.window);
+            if requests.len() > self.capacity {
+                tracing::debug!("admission capacity exceeded");
+                requests.clear();
+            }
+        }
+
+        false
+    }
+}
@@ -32,6 +32,17 @@ where
     /// Set `XX-Cache-Duration` header.
     fn with_duration(self, duration: Duration) -> Response;
 
+    /// Set `Cache-Control` header.
+    fn with_cache_control(self, cache_control: CacheControl) -> Response;
+
+    /// Set `Cache-Control` header to "no-store".
+    fn with_no_store(self) -> Response {
+        self.with_cache_control(CacheControl::default().with_no_store())
+    }
+
+    /// Set `XX-Cache-Tags` header.
+    fn with_tags(self, tags: &[&str]) -> Result<Response, InvalidHeaderValue>;
+
     /// Set `XX-Cache-Duration` header.
     fn with_duration_str(self, duration: &str) -> Result<Response, InvalidHeaderValue>;
 
@@ -59,6 +70,19 @@ where
         Ok(response)
     }
 
+    fn with_cache_control(self, cache_control: CacheControl) -> Response {
+        let mut response = self.into_response();
+        response.headers_mut().set_cache_control(cache_control);
+        response
+    }
+
+    fn with_tags(self, tags: &[&str]) -> Result<Response, InvalidHeaderValue> {
+        let mut response = self.into_response();
+        let headers = response.headers_mut();
+        headers.set_string_value(XX_CACHE_TAGS, &tags.join(","))?;
+        Ok(response)
+    }
+
     fn set_header_bool(self, name: HeaderName, value: bool) -> Response {
         let mut response = self.into_response();
         response.headers_mut().set_bool_value(name, value);
@@ -4,20 +4,29 @@ use super::{
         transcoding::{transcode::*, *},
     },
     configuration::*,
+    pressure::*,
     weight::*,
 };
 
-use std::io;
+use std::{io, sync::*};
 
 //
 // CachedBody
 //
 
 /// Cached HTTP response body.
+///
+/// Encodings with a dictionary are stored per dictionary, because the [DictionaryHash] is part of
+/// the [Encoding].
 #[derive(Clone, Debug, Default)]
 pub struct CachedBody {
     /// Representations.
     pub representations: FastHashMap<Encoding, Bytes>,
+
+    /// Access counts per representation.
+    ///
+    /// Shared between clones.
+    pub accesses: RepresentationAccesses,
 }
 
 impl CachedBody {
@@ -26,13 +35,15 @@ impl CachedBody {
     /// If the `preferred_encoding` is different from the `encoding` then we will reencode.
     ///
     /// If an [Identity](Encoding::Identity) is created during this reencoding then it will also be
-    /// stored if `keep_identity_encoding` is true.
+    /// stored if `keep_identity_encoding` is true. It will always be stored for encodings with a
+    /// dictionary, because those are only useful for some clients.
     pub async fn new_with(
         bytes: Bytes,
         encoding: Encoding,
         preferred_encoding: Encoding,
         configuration: &EncodingConfiguration,
     ) -> io::Result<Self> {
+        let dictionaries = configuration.dictionaries.as_ref();
         let mut representations = FastHashMap::default();
 
         if preferred_encoding == encoding {
@@ -41,31 +52,31 @@ impl CachedBody {
         } else if encoding == Encoding::Identity {
             tracing::debug!("encoding to {}", preferred_encoding);
 
-            let encoded_bytes = bytes.encode(&preferred_encoding).await?;
+            let encoded_bytes = bytes.encode_with_dictionaries(&preferred_encoding, dictionaries).await?;
 
             representations.insert(preferred_encoding, encoded_bytes);
-            if configuration.keep_identity_encoding {
+            if configuration.keep_identity_encoding || preferred_encoding.dictionary().is_some() {
                 representations.insert(Encoding::Identity, bytes);
             }
         } else if preferred_encoding == Encoding::Identity {
             tracing::debug!("decoding from {}", encoding);
 
-            let identity_bytes = bytes.decode(&encoding).await?;
+            let identity_bytes = bytes.decode_with_dictionaries(&encoding, dictionaries).await?;
 
             representations.insert(Encoding::Identity, identity_bytes);
         } else {
             tracing::debug!("reencoding from {} to {}", encoding, preferred_encoding);
 
-            let identity_bytes = bytes.decode(&encoding).await?;
-            let encoded_bytes = identity_bytes.encode(&preferred_encoding).await?;
+            let identity_bytes = bytes.decode_with_dictionaries(&encoding, dictionaries).await?;
+            let encoded_bytes = identity_bytes.encode_with_dictionaries(&preferred_encoding, dictionaries).await?;
 
             representations.insert(preferred_encoding, encoded_bytes);
-            if configuration.keep_identity_encoding {
+            if configuration.keep_identity_encoding || preferred_encoding.dictionary().is_some() {
                 representations.insert(Encoding::Identity, identity_bytes);
             }
         }
 
-        Ok(Self { representations })
+        Ok(Self { representations, accesses: Default::default() })
     }
 
     /// Returns the body [Bytes] in the specified encoding.
@@ -74,31 +85,35 @@ impl CachedBody {
     /// storing the result so that we won't have to encode it again.
     ///
     /// If an [Identity](Encoding::Identity) is created during this reencoding then it will also be
-    /// stored if `keep_identity_encoding` is true.
+    /// stored if `keep_identity_encoding` is true (always for encodings with a dictionary).
     ///
     /// Returns a modified clone if reencoding caused a new encoding to be stored. Note that
     /// cloning should be cheap due to our use of [Bytes].
+    ///
+    /// Counts as an access for the specified encoding.
     pub async fn get(
         &self,
         encoding: &Encoding,
         configuration: &EncodingConfiguration,
     ) -> io::Result<(Bytes, Option<Self>)> {
+        self.accesses.increment(encoding);
+
+        let dictionaries = configuration.dictionaries.as_ref();
+
         match (self.representations.get(encoding), encoding) {
             (Some(bytes), _) => Ok((bytes.clone(), None)),
 
             (None, Encoding::Identity) => {
                 // Decode
-                for from_encoding in ENCODINGS_BY_DECODING_COST {
-                    if let Some(bytes) = self.representations.get(from_encoding) {
-                        tracing::debug!("decoding from {}", from_encoding);
+                if let Some((from_encoding, bytes)) = self.cheapest_to_decode() {
+                    tracing::debug!("decoding from {}", from_encoding);
 
-                        let identity_bytes = bytes.decode(from_encoding).await?;
+                    let identity_bytes = bytes.decode_with_dictionaries(from_encoding, dictionaries).await?;
 
-                        let mut modified = self.clone();
-                        modified.representations.insert(Encoding::Identity, identity_bytes.clone());
+                    let mut modified = self.clone();
+                    modified.representations.insert(Encoding::Identity, identity_bytes.clone());
 
-                        return Ok((identity_bytes, Some(modified)));
-                    }
+                    return Ok((identity_bytes, Some(modified)));
                 }
 
                 // This should never happen (but we don't want to panic here!)
@@ -111,30 +126,26 @@ impl CachedBody {
                 if let Some(identity_bytes) = self.representations.get(&Encoding::Identity) {
                     tracing::debug!("encoding to {}", to_encoding);
 
-                    let bytes = identity_bytes.encode(to_encoding).await?;
+                    let bytes = identity_bytes.encode_with_dictionaries(to_encoding, dictionaries).await?;
 
                     let mut modified = self.clone();
                     modified.representations.insert(to_encoding.clone(), bytes.clone());
 
                     Ok((bytes, Some(modified)))
-                } else {
-                    for from_encoding in ENCODINGS_BY_DECODING_COST {
-                        if let Some(bytes) = self.representations.get(from_encoding) {
-                            tracing::debug!("reencoding from {} to {}", from_encoding, to_encoding);
-
-                            let identity_bytes = bytes.decode(from_encoding).await?;
-                            let bytes = identity_bytes.encode(to_encoding).await?;
+                } else if let Some((from_encoding, bytes)) = self.cheapest_to_decode() {
+                    tracing::debug!("reencoding from {} to {}", from_encoding, to_encoding);
 
-                            let mut modified = self.clone();
-                            if configuration.keep_identity_encoding {
-                                modified.representations.insert(Encoding::Identity, identity_bytes);
-                            }
-                            modified.representations.insert(to_encoding.clone(), bytes.clone());
+                    let identity_bytes = bytes.decode_with_dictionaries(from_encoding, dictionaries).await?;
+                    let bytes = identity_bytes.encode_with_dictionaries(to_encoding, dictionaries).await?;
 
-                            return Ok((bytes, Some(modified)));
-                        }
+                    let mut modified = self.clone();
+                    if configuration.keep_identity_encoding || to_encoding.dictionary().is_some() {
+                        modified.representations.insert(Encoding::Identity, identity_bytes);
                     }
+                    modified.representations.insert(to_encoding.clone(), bytes.clone());
 
+                    Ok((bytes, Some(modified)))
+                } else {
                     // This should never happen (but we don't want to panic here!)
                     tracing::error!("no encodings");
                     Ok((Default::default(), None))
@@ -142,6 +153,52 @@ impl CachedBody {
             }
         }
     }
+
+    // Encodings with a dictionary are last because decoding them requires the dictionary
+    fn cheapest_to_decode(&self) -> Option<(&Encoding, &Bytes)> {
+        ENCODINGS_BY_DECODING_COST
+            .iter()
+            .find_map(|encoding| self.representations.get_key_value(encoding))
+            .or_else(|| self.representations.iter().find(|(encoding, _bytes)| encoding.dictionary().is_some()))
+    }
+
+    /// Returns a clone with only one representation, chosen according to the retention.
+    ///
+    /// Returns [None] if we have one representation or fewer.
+    pub fn retain_one(&self, retention: RepresentationRetention) -> Option<Self> {
+        if self.representations.len() <= 1 {
+            return None;
+        }
+
+        // Encodings with a dictionary are only useful for some clients, so we prefer not to retain them
+        let has_without_dictionary = self.representations.keys().any(|encoding| encoding.dictionary().is_none());
+        let candidates = self
+            .representations
+            .iter()
+            .filter(|(encoding, _bytes)| !has_without_dictionary || encoding.dictionary().is_none());
+
+        let (encoding, bytes) = match retention {
+            RepresentationRetention::Smallest => candidates.min_by(|(encoding1, bytes1), (encoding2, bytes2)| {
+                bytes1
+                    .len()
+                    .cmp(&bytes2.len())
+                    .then_with(|| self.accesses.get(encoding2).cmp(&self.accesses.get(encoding1)))
+            }),
+
+            RepresentationRetention::MostRequested => candidates.max_by(|(encoding1, bytes1), (encoding2, bytes2)| {
+                self.accesses
+                    .get(encoding1)
+                    .cmp(&self.accesses.get(encoding2))
+                    .then_with(|| bytes2.len().cmp(&bytes1.len()))
+            }),
+        }?;
+
+        tracing::debug!("retaining only {} ({:?})", encoding, retention);
+
+        let mut representations = FastHashMap::default();
+        representations.insert(*encoding, bytes.clone());
+        Some(Self { representations, accesses: self.accesses.clone() })
+    }
 }
 
 impl CacheWeight for CachedBody {
@@ -158,3 +215,30 @@ impl CacheWeight for CachedBody {
         size
     }
 }
+
+//
+// RepresentationAccesses
+//
+
+/// Access counts per representation of a [CachedBody].
+///
+/// Cloning is cheap and clones share the same counts.
+#[derive(Clone, Debug, Default)]
+pub struct RepresentationAccesses {
+    counts: Arc<Mutex<FastHashMap<Encoding, u64>>>,
+}
+
+impl RepresentationAccesses {
+    /// Access count for an encoding.
+    pub fn get(&self, encoding: &Encoding) -> u64 {
+        self.counts.lock().map(|counts| counts.get(encoding).copied().unwrap_or_default()).unwrap_or_default()
+    }
+
+    /// Increment the access count for an encoding.
+    pub fn increment(&self, encoding: &Encoding) {
+        if let Ok(mut counts) = self.counts.lock() {
+            let count = counts.entry(*encoding).or_default();
+            *count = count.saturating_add(1);
+        }
+    }
+}
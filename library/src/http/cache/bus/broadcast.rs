@@ -0,0 +1,116 @@
+use super::{
+    super::{cache::*, enumerable::*, response::*, snapshot::*},
+    bus::*,
+    message::*,
+};
+
+use std::{io, sync::*};
+
+//
+// BroadcastCache
+//
+
+/// [Cache] wrapper that broadcasts invalidations to peers via an [InvalidationBus].
+///
+/// Call [listen](BroadcastCache::listen) (e.g. in a spawned task) in order to apply invalidations
+/// received from peers.
+#[derive(Clone, Debug)]
+pub struct BroadcastCache<CacheT> {
+    /// Inner cache.
+    pub cache: CacheT,
+
+    /// Bus.
+    pub bus: Arc<InvalidationBus>,
+}
+
+impl<CacheT> BroadcastCache<CacheT> {
+    /// Constructor.
+    pub fn new(cache: CacheT, bus: InvalidationBus) -> Self {
+        Self { cache, bus: bus.into() }
+    }
+
+    /// Receive invalidations from peers and apply them to the inner cache.
+    ///
+    /// Loops until the bus errors.
+    pub async fn listen<CacheKeyT>(&self) -> io::Result<()>
+    where
+        CacheKeyT: SnapshotCacheKey,
+        CacheT: EnumerableCache<CacheKeyT>,
+    {
+        let mut buffer = Vec::default();
+
+        loop {
+            match self.bus.receive(&mut buffer).await? {
+                InvalidationMessage::Invalidate(key) => {
+                    tracing::debug!("received invalidate: {}", key);
+                    self.cache.invalidate(&key).await;
+                }
+
+                InvalidationMessage::InvalidateAll => {
+                    tracing::debug!("received invalidate all");
+                    self.cache.invalidate_all().await;
+                }
+
+                InvalidationMessage::InvalidateTag(tag) => {
+                    tracing::debug!("received invalidate tag: {}", tag);
+                    self.cache.invalidate_tag(&tag).await;
+                }
+            }
+        }
+    }
+
+    async fn publish<CacheKeyT>(&self, message: InvalidationMessage<CacheKeyT>)
+    where
+        CacheKeyT: SnapshotCacheKey,
+    {
+        if let Err(error) = self.bus.publish(&message).await {
+            tracing::error!("could not publish invalidation: {}", error);
+        }
+    }
+}
+
+impl<CacheKeyT, CacheT> Cache<CacheKeyT> for BroadcastCache<CacheT>
+where
+    CacheKeyT: SnapshotCacheKey,
+    CacheT: Cache<CacheKeyT>,
+{
+    async fn get(&self, key: &CacheKeyT) -> Option<CachedResponseRef> {
+        self.cache.get(key).await
+    }
+
+    async fn put(&self, key: CacheKeyT, cached_response: CachedResponseRef) {
+        self.cache.put(key, cached_response).await
+    }
+
+    async fn invalidate(&self, key: &CacheKeyT) {
+        self.cache.invalidate(key).await;
+        self.publish(InvalidationMessage::Invalidate(key.clone())).await
+    }
+
+    async fn invalidate_local(&self, key: &CacheKeyT) {
+        // Peers expire their own entries
+        self.cache.invalidate_local(key).await
+    }
+
+    async fn invalidate_all(&self) {
+        self.cache.invalidate_all().await;
+        self.publish(InvalidationMessage::<CacheKeyT>::InvalidateAll).await
+    }
+}
+
+impl<CacheKeyT, CacheT> EnumerableCache<CacheKeyT> for BroadcastCache<CacheT>
+where
+    CacheKeyT: SnapshotCacheKey,
+    CacheT: EnumerableCache<CacheKeyT>,
+{
+    async fn entries(&self) -> Vec<(CacheKeyT, CachedResponseRef)> {
+        self.cache.entries().await
+    }
+
+    async fn invalidate_tag(&self, tag: &str) -> usize {
+        // Peers will invalidate their own entries, so we don't want to broadcast each key
+        let count = self.cache.invalidate_tag(tag).await;
+        self.publish(InvalidationMessage::<CacheKeyT>::InvalidateTag(tag.into())).await;
+        count
+    }
+}
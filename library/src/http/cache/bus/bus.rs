@@ -0,0 +1,247 @@
+use super::{super::snapshot::*, message::*};
+
+use {
+    socket2::*,
+    std::{hash::*, io, net::*, path::*, process},
+    tokio::net::UdpSocket,
+};
+
+#[cfg(unix)]
+use {
+    std::fs::{create_dir_all, remove_file},
+    tokio::{fs, net::UnixDatagram},
+};
+
+/// Maximum size of an invalidation message.
+pub const MAX_INVALIDATION_MESSAGE_SIZE: usize = 64 * 1_024; // 64 KiB
+
+/// Default multicast group for [InvalidationTransport::Multicast].
+pub const DEFAULT_INVALIDATION_MULTICAST_GROUP: Ipv4Addr = Ipv4Addr::new(239, 255, 77, 75);
+
+/// Default port for [InvalidationTransport::Multicast].
+pub const DEFAULT_INVALIDATION_MULTICAST_PORT: u16 = 7775;
+
+//
+// InvalidationTransport
+//
+
+/// Transport for [InvalidationBus].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InvalidationTransport {
+    /// UDP multicast.
+    ///
+    /// The multicast TTL is 0, so messages will not leave the host.
+    ///
+    /// Note that on Linux the loopback interface does not have multicast enabled by default. You
+    /// can enable it with `ip link set lo multicast on`, or use a different interface.
+    Multicast {
+        /// Multicast group.
+        group: Ipv4Addr,
+
+        /// Port.
+        port: u16,
+
+        /// Interface address.
+        interface: Ipv4Addr,
+    },
+
+    /// Unix domain datagram sockets in a shared directory.
+    ///
+    /// Each peer binds its own socket in the directory and sends messages to all the other
+    /// sockets in it. Sockets that are no longer bound (e.g. if a process crashed) are removed.
+    #[cfg(unix)]
+    UnixDirectory(PathBuf),
+}
+
+impl Default for InvalidationTransport {
+    /// Multicast on the loopback interface.
+    fn default() -> Self {
+        Self::Multicast {
+            group: DEFAULT_INVALIDATION_MULTICAST_GROUP,
+            port: DEFAULT_INVALIDATION_MULTICAST_PORT,
+            interface: Ipv4Addr::LOCALHOST,
+        }
+    }
+}
+
+//
+// InvalidationBus
+//
+
+/// Cache invalidation bus.
+///
+/// Publishes [InvalidationMessage] to and receives them from peers, which are usually other
+/// processes on the same host.
+///
+/// Our own messages are never received.
+#[derive(Debug)]
+pub struct InvalidationBus {
+    socket: InvalidationSocket,
+    id: u64,
+}
+
+impl InvalidationBus {
+    /// Constructor.
+    ///
+    /// Must be called within a Tokio runtime.
+    pub fn new(transport: &InvalidationTransport) -> io::Result<Self> {
+        let socket = match transport {
+            InvalidationTransport::Multicast { group, port, interface } => {
+                let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+                socket.set_reuse_address(true)?;
+                #[cfg(unix)]
+                socket.set_reuse_port(true)?;
+                socket.bind(&SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, *port).into())?;
+                socket.join_multicast_v4(group, interface)?;
+                socket.set_multicast_if_v4(interface)?;
+                socket.set_multicast_loop_v4(true)?;
+                socket.set_multicast_ttl_v4(0)?;
+                socket.set_nonblocking(true)?;
+
+                InvalidationSocket::Multicast(
+                    UdpSocket::from_std(socket.into())?,
+                    SocketAddrV4::new(*group, *port).into(),
+                )
+            }
+
+            #[cfg(unix)]
+            InvalidationTransport::UnixDirectory(directory) => {
+                create_dir_all(directory)?;
+
+                let path = directory.join(format!("{}-{:x}.sock", process::id(), random_u64()));
+                let socket = UnixDatagram::bind(&path)?;
+
+                InvalidationSocket::UnixDirectory(socket, directory.clone(), path)
+            }
+        };
+
+        Ok(Self { socket, id: random_u64() })
+    }
+
+    /// Publish a message to all peers.
+    ///
+    /// Sending is non-blocking, so that a slow peer cannot stall us (this is called while handling
+    /// requests). If a peer's receive queue is full then the message is dropped for that peer.
+    /// Per-peer errors are logged and do not stop us from publishing to the other peers.
+    pub async fn publish<CacheKeyT>(&self, message: &InvalidationMessage<CacheKeyT>) -> io::Result<()>
+    where
+        CacheKeyT: SnapshotCacheKey,
+    {
+        let bytes = message.encode(self.id)?;
+        if bytes.len() > MAX_INVALIDATION_MESSAGE_SIZE {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "invalidation message too big"));
+        }
+
+        match &self.socket {
+            InvalidationSocket::Multicast(socket, address) => match socket.try_send_to(&bytes, *address) {
+                Ok(_) => {}
+
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => {
+                    tracing::warn!("could not publish (send buffer is full)");
+                }
+
+                Err(error) => return Err(error),
+            },
+
+            #[cfg(unix)]
+            InvalidationSocket::UnixDirectory(socket, directory, path) => {
+                let mut entries = fs::read_dir(directory).await?;
+                loop {
+                    let peer_path = match entries.next_entry().await {
+                        Ok(Some(entry)) => entry.path(),
+                        Ok(None) => break,
+
+                        Err(error) => {
+                            tracing::warn!("could not read {}: {}", directory.display(), error);
+                            continue;
+                        }
+                    };
+
+                    if (peer_path == *path) || (peer_path.extension().is_none_or(|extension| extension != "sock")) {
+                        continue;
+                    }
+
+                    if let Err(error) = socket.try_send_to(&bytes, &peer_path) {
+                        match error.kind() {
+                            io::ErrorKind::ConnectionRefused | io::ErrorKind::NotFound => {
+                                // Nobody is bound to it anymore
+                                tracing::debug!("removing stale socket: {}", peer_path.display());
+                                _ = fs::remove_file(&peer_path).await;
+                            }
+
+                            io::ErrorKind::WouldBlock => {
+                                tracing::warn!("could not publish to {} (queue is full)", peer_path.display())
+                            }
+
+                            _ => tracing::warn!("could not publish to {}: {}", peer_path.display(), error),
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Receive the next message from a peer.
+    ///
+    /// The buffer is reused between calls in order to avoid allocating for each message. It will
+    /// be grown to [MAX_INVALIDATION_MESSAGE_SIZE] if it is smaller.
+    ///
+    /// Malformed messages are skipped.
+    pub async fn receive<CacheKeyT>(&self, buffer: &mut Vec<u8>) -> io::Result<InvalidationMessage<CacheKeyT>>
+    where
+        CacheKeyT: SnapshotCacheKey,
+    {
+        if buffer.len() < MAX_INVALIDATION_MESSAGE_SIZE {
+            buffer.resize(MAX_INVALIDATION_MESSAGE_SIZE, 0);
+        }
+
+        loop {
+            let size = match &self.socket {
+                InvalidationSocket::Multicast(socket, _address) => socket.recv(buffer).await?,
+
+                #[cfg(unix)]
+                InvalidationSocket::UnixDirectory(socket, _directory, _path) => socket.recv(buffer).await?,
+            };
+
+            match InvalidationMessage::decode(&buffer[..size]) {
+                Ok((sender, message)) => {
+                    if sender != self.id {
+                        return Ok(message);
+                    }
+                }
+
+                Err(error) => tracing::warn!("malformed invalidation message: {}", error),
+            }
+        }
+    }
+}
+
+//
+// InvalidationSocket
+//
+
+#[derive(Debug)]
+enum InvalidationSocket {
+    Multicast(UdpSocket, SocketAddr),
+
+    #[cfg(unix)]
+    UnixDirectory(UnixDatagram, PathBuf, PathBuf),
+}
+
+impl Drop for InvalidationSocket {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Self::UnixDirectory(_socket, _directory, path) = self {
+            _ = remove_file(path);
+        }
+    }
+}
+
+// Random enough for identifying peers.
+fn random_u64() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u32(process::id());
+    hasher.finish()
+}
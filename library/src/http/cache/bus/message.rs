@@ -0,0 +1,83 @@
+use super::super::{super::super::std::immutable::*, key::*, snapshot::*};
+
+use std::io;
+
+const INVALIDATION_MESSAGE_SIGNATURE: &[u8] = b"KUTILINVALIDATION";
+
+const INVALIDATION_MESSAGE_VERSION: u16 = 1;
+
+//
+// InvalidationMessage
+//
+
+/// Cache invalidation message.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InvalidationMessage<CacheKeyT = CommonCacheKey> {
+    /// Invalidate a cache entry.
+    Invalidate(CacheKeyT),
+
+    /// Invalidate all cache entries.
+    InvalidateAll,
+
+    /// Invalidate all cache entries that have a tag.
+    InvalidateTag(ByteString),
+}
+
+impl<CacheKeyT> InvalidationMessage<CacheKeyT>
+where
+    CacheKeyT: SnapshotCacheKey,
+{
+    /// Encode.
+    ///
+    /// The sender is used to identify (and ignore) our own messages.
+    pub fn encode(&self, sender: u64) -> io::Result<Vec<u8>> {
+        let mut writer = SnapshotWriter::new(Vec::default());
+
+        writer.write_bytes(INVALIDATION_MESSAGE_SIGNATURE)?;
+        writer.write_u16(INVALIDATION_MESSAGE_VERSION)?;
+        writer.write_u64(sender)?;
+
+        match self {
+            Self::Invalidate(key) => {
+                writer.write_u8(0)?;
+                key.write_snapshot(&mut writer)?;
+            }
+
+            Self::InvalidateAll => writer.write_u8(1)?,
+
+            Self::InvalidateTag(tag) => {
+                writer.write_u8(2)?;
+                writer.write_str(tag)?;
+            }
+        }
+
+        Ok(writer.into_inner())
+    }
+
+    /// Decode.
+    ///
+    /// Returns the sender and the message.
+    pub fn decode(bytes: &[u8]) -> io::Result<(u64, Self)> {
+        let mut reader = SnapshotReader::new(bytes);
+
+        if reader.read_bytes()? != INVALIDATION_MESSAGE_SIGNATURE {
+            return Err(invalid_data("not an invalidation message"));
+        }
+
+        let version = reader.read_u16()?;
+        if version != INVALIDATION_MESSAGE_VERSION {
+            return Err(invalid_data(format!("unsupported invalidation message version: {}", version)));
+        }
+
+        let sender = reader.read_u64()?;
+
+        let message = match reader.read_u8()? {
+            0 => Self::Invalidate(CacheKeyT::read_snapshot(&mut reader)?),
+            1 => Self::InvalidateAll,
+            2 => Self::InvalidateTag(reader.read_string()?),
+            kind => return Err(invalid_data(format!("unsupported invalidation message: {}", kind))),
+        };
+
+        Ok((sender, message))
+    }
+}
@@ -0,0 +1,6 @@
+mod broadcast;
+mod bus;
+mod message;
+
+#[allow(unused_imports)]
+pub use {broadcast::*, bus::*, message::*};
@@ -34,6 +34,15 @@ where
     /// constraint. Implementations can simply use `async fn invalidate`.
     fn invalidate(&self, key: &CacheKeyT) -> impl Future<Output = ()> + Send;
 
+    /// Invalidate a cache entry only in this cache, without propagating the invalidation elsewhere
+    /// (e.g. to peers).
+    ///
+    /// Used for entries that expired locally. The default implementation calls
+    /// [invalidate](Self::invalidate).
+    fn invalidate_local(&self, key: &CacheKeyT) -> impl Future<Output = ()> + Send {
+        self.invalidate(key)
+    }
+
     /// Invalidate all cache entries.
     ///
     /// Note that this is an `async` function written in longer form in order to include the `Send`
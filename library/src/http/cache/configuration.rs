@@ -1,4 +1,4 @@
-use super::hooks::*;
+use super::{super::super::transcoding::*, hooks::*, pressure::*};
 
 //
 // CachingConfiguration
@@ -35,4 +35,24 @@ pub struct EncodingConfiguration {
 
     /// Keep identity encoding.
     pub keep_identity_encoding: bool,
+
+    /// Memory pressure.
+    ///
+    /// [None] means we are never under pressure.
+    pub memory_pressure: Option<MemoryPressureRef>,
+
+    /// Which representation to keep under memory pressure.
+    pub retention: RepresentationRetention,
+
+    /// Compression dictionaries.
+    ///
+    /// [None] means that encodings with a dictionary are disabled.
+    pub dictionaries: Option<Dictionaries>,
+}
+
+impl EncodingConfiguration {
+    /// Whether we are under memory pressure.
+    pub fn is_under_memory_pressure(&self) -> bool {
+        self.memory_pressure.as_ref().is_some_and(|memory_pressure| memory_pressure.is_under_pressure())
+    }
 }
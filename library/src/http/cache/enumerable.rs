@@ -0,0 +1,42 @@
+use super::{cache::*, key::*, response::*};
+
+//
+// EnumerableCache
+//
+
+/// [Cache] that can enumerate its entries.
+#[allow(async_fn_in_trait)]
+pub trait EnumerableCache<CacheKeyT = CommonCacheKey>
+where
+    Self: Cache<CacheKeyT>,
+    CacheKeyT: CacheKey,
+{
+    /// All cache entries.
+    ///
+    /// This is a snapshot in time. Entries may be added or removed concurrently.
+    ///
+    /// Note that this is an `async` function written in longer form in order to include the `Send`
+    /// constraint. Implementations can simply use `async fn entries`.
+    fn entries(&self) -> impl Future<Output = Vec<(CacheKeyT, CachedResponseRef)>> + Send;
+
+    /// Invalidate all cache entries that have a tag.
+    ///
+    /// Tags are set via the `XX-Cache-Tags` header. See [CachedResponse::has_tag].
+    ///
+    /// Returns the number of invalidated entries.
+    ///
+    /// Note that this is an `async` function written in longer form in order to include the `Send`
+    /// constraint. Implementations can simply use `async fn invalidate_tag`.
+    fn invalidate_tag(&self, tag: &str) -> impl Future<Output = usize> + Send {
+        async move {
+            let mut count = 0;
+            for (key, cached_response) in self.entries().await {
+                if cached_response.has_tag(tag) {
+                    self.invalidate(&key).await;
+                    count += 1;
+                }
+            }
+            count
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use super::super::super::{cache::*, key::*, response::*};
+use super::super::super::{cache::*, enumerable::*, key::*, response::*};
 
 use std::{ops::*, sync::*};
 
@@ -34,3 +34,12 @@ where
         self.deref().invalidate_all()
     }
 }
+
+impl<CacheKeyT> EnumerableCache<CacheKeyT> for MokaCacheImplementation<CacheKeyT>
+where
+    CacheKeyT: CacheKey,
+{
+    async fn entries(&self) -> Vec<(CacheKeyT, CachedResponseRef)> {
+        self.deref().iter().map(|(key, cached_response)| (key.as_ref().clone(), cached_response)).collect()
+    }
+}
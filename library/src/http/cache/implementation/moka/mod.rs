@@ -1,7 +1,9 @@
 mod builder;
 mod cache;
 mod expiry;
+mod partitioned;
+mod pressure;
 mod weigher;
 
 #[allow(unused_imports)]
-pub use {builder::*, cache::*, expiry::*, weigher::*};
+pub use {builder::*, cache::*, expiry::*, partitioned::*, pressure::*, weigher::*};
@@ -0,0 +1,74 @@
+use super::{
+    super::super::{
+        super::super::std::{collections::*, immutable::*},
+        key::*,
+        partitioned::*,
+    },
+    builder::*,
+    cache::*,
+};
+
+use std::sync::*;
+
+//
+// PartitionQuotas
+//
+
+/// Per-partition weight quotas for [PartitionedCache::new_moka].
+///
+/// Weights are as calculated by our Moka weigher (see
+/// [ForHttpResponse](super::ForHttpResponse)), so roughly in bytes.
+#[derive(Clone, Debug)]
+pub struct PartitionQuotas {
+    /// Quota for partitions not in [quotas](Self::quotas).
+    pub default_quota: u64,
+
+    /// Quotas by partition name.
+    pub quotas: FastHashMap<ByteString, u64>,
+}
+
+impl PartitionQuotas {
+    /// Constructor.
+    pub fn new(default_quota: u64) -> Self {
+        Self { default_quota, quotas: Default::default() }
+    }
+
+    /// With a quota for a partition.
+    pub fn with_quota(mut self, partition: ByteString, quota: u64) -> Self {
+        self.quotas.insert(partition, quota);
+        self
+    }
+
+    /// Quota for a partition.
+    pub fn quota_for(&self, partition: &str) -> u64 {
+        self.quotas.get(partition).copied().unwrap_or(self.default_quota)
+    }
+}
+
+impl<CacheKeyT> PartitionedCache<MokaCacheImplementation<CacheKeyT>, CacheKeyT>
+where
+    CacheKeyT: CacheKey,
+{
+    /// Constructor with a Moka cache per partition.
+    ///
+    /// Each partition's max capacity is its quota.
+    pub fn new_moka(
+        partition: impl Fn(&CacheKeyT) -> ByteString + 'static + Send + Sync,
+        quotas: PartitionQuotas,
+    ) -> Self {
+        Self::new(partition, move |name| {
+            let quota = quotas.quota_for(name);
+            tracing::debug!("new partition: {} ({})", name, quota);
+            Arc::new(moka::future::Cache::builder().for_http_response().max_capacity(quota).build())
+        })
+    }
+}
+
+impl PartitionedCache<MokaCacheImplementation<CommonCacheKey>, CommonCacheKey> {
+    /// Constructor with a Moka cache per partition, partitioned by [CommonCacheKey::host].
+    ///
+    /// See [new_by_host](PartitionedCache::new_by_host).
+    pub fn new_moka_by_host(quotas: PartitionQuotas) -> Self {
+        Self::new_moka(|key: &CommonCacheKey| key.host.clone().unwrap_or_default(), quotas)
+    }
+}
@@ -0,0 +1,49 @@
+use super::{
+    super::super::{key::*, pressure::*},
+    cache::*,
+};
+
+//
+// MokaMemoryPressure
+//
+
+/// [MemoryPressure] for [MokaCacheImplementation].
+///
+/// We are under pressure when the cache's weighted size reaches a threshold. Note that Moka
+/// updates its weighted size lazily, so this is an approximation.
+#[derive(Clone)]
+pub struct MokaMemoryPressure<CacheKeyT = CommonCacheKey> {
+    /// Cache.
+    pub cache: MokaCacheImplementation<CacheKeyT>,
+
+    /// Threshold as a weighted size.
+    pub threshold: u64,
+}
+
+impl<CacheKeyT> MokaMemoryPressure<CacheKeyT>
+where
+    CacheKeyT: CacheKey,
+{
+    /// Constructor.
+    ///
+    /// The threshold is a fraction of the cache's max capacity, e.g. 0.8 means that we are under
+    /// pressure when the cache is 80% full. If the cache has no max capacity then we will never be
+    /// under pressure.
+    pub fn new(cache: MokaCacheImplementation<CacheKeyT>, threshold: f64) -> Self {
+        let threshold = match cache.policy().max_capacity() {
+            Some(max_capacity) => (max_capacity as f64 * threshold) as u64,
+            None => u64::MAX,
+        };
+
+        Self { cache, threshold }
+    }
+}
+
+impl<CacheKeyT> MemoryPressure for MokaMemoryPressure<CacheKeyT>
+where
+    CacheKeyT: CacheKey,
+{
+    fn is_under_pressure(&self) -> bool {
+        self.cache.weighted_size() >= self.threshold
+    }
+}
@@ -1,9 +1,12 @@
 use super::{
     super::{
-        super::{super::std::immutable::*, headers::*, uri::*},
+        super::{super::std::immutable::*, authentication::*, headers::*, origin::*, uri::*},
+        middleware::*,
         weight::*,
     },
+    digest::*,
     key::*,
+    normalization::*,
 };
 
 use {
@@ -11,6 +14,12 @@ use {
     std::{collections::*, fmt, hash::*},
 };
 
+/// [CommonCacheKey] extension key for the [Authenticated] identity.
+pub const AUTHENTICATED_EXTENSION: &[u8] = b"authenticated";
+
+/// [CommonCacheKey] extension key prefix for cookies (followed by the cookie name).
+pub const COOKIE_EXTENSION_PREFIX: &[u8] = b"cookie:";
+
 //
 // CommonCacheKey
 //
@@ -29,32 +38,39 @@ pub struct CommonCacheKey {
 
     /// Optional scheme.
     ///
-    /// Not set by default but reserved for custom use.
+    /// Not set by default but reserved for custom use. Set from the [EffectiveOrigin] if
+    /// available.
     pub scheme: Option<Scheme>,
 
     /// Optional host.
     ///
-    /// Not set by default but reserved for custom use.
+    /// Not set by default but reserved for custom use. Set from the [EffectiveOrigin] if
+    /// available.
     pub host: Option<ByteString>,
 
     /// Optional port.
     ///
-    /// Not set by default but reserved for custom use.
+    /// Not set by default but reserved for custom use. Set from the [EffectiveOrigin] if
+    /// available.
     pub port: Option<u16>,
 
     /// Optional media type.
     ///
-    /// Not set by default but reserved for custom use.
+    /// Not set by default but reserved for custom use. Set from the [NegotiatedVariant] if
+    /// available.
     pub media_type: Option<MediaType>,
 
     /// Optional languages (sorted).
     ///
-    /// Not set by default but reserved for custom use.
+    /// Not set by default but reserved for custom use. Set from the [NegotiatedVariant] if
+    /// available.
     pub languages: Option<BTreeSet<Language>>,
 
     /// Optional extensions (sorted by key).
     ///
-    /// Not set by default but reserved for custom use.
+    /// Not set by default but reserved for custom use. The [Authenticated] identity is stored
+    /// under [AUTHENTICATED_EXTENSION]. Cookies can be folded in via
+    /// [apply_cookies](Self::apply_cookies).
     pub extensions: Option<BTreeMap<Bytes, Bytes>>,
 }
 
@@ -73,17 +89,94 @@ impl CommonCacheKey {
     ) -> Self {
         Self { method, scheme, host, port, path, query, media_type, languages, extensions }
     }
+
+    /// Fold the values of selected cookies into the extensions.
+    ///
+    /// Each value is stored under [COOKIE_EXTENSION_PREFIX] followed by the cookie name. Missing
+    /// cookies are not stored, so all requests without them will share the key.
+    ///
+    /// Intended to be called from a [CacheKeyHook], e.g. to cache per session tier.
+    pub fn apply_cookies(&mut self, headers: &HeaderMap, names: &[&str]) {
+        let cookies = headers.cookies();
+        for name in names {
+            if let Some(value) = cookies.get(name) {
+                let mut key = Vec::with_capacity(COOKIE_EXTENSION_PREFIX.len() + name.len());
+                key.extend_from_slice(COOKIE_EXTENSION_PREFIX);
+                key.extend_from_slice(name.as_bytes());
+
+                self.extensions.get_or_insert_default().insert(key.into(), Bytes::copy_from_slice(value.as_bytes()));
+            }
+        }
+    }
 }
 
 impl CacheKey for CommonCacheKey {
-    fn for_request(method: &Method, uri: &Uri, _headers: &HeaderMap) -> Self {
+    fn for_request(method: &Method, uri: &Uri, headers: &HeaderMap) -> Self {
+        Self::for_normalized_request(method, uri, headers, &CacheKeyNormalization::NONE)
+    }
+
+    /// Normalizes the path and query directly, without reconstructing the [Uri].
+    fn for_normalized_request(
+        method: &Method,
+        uri: &Uri,
+        _headers: &HeaderMap,
+        normalization: &CacheKeyNormalization,
+    ) -> Self {
         let (path, query) = uri
             .path_and_query()
-            .map(|path_and_query| (Some(path_and_query.path().into()), path_and_query.decoded_query_map()))
+            .map(|path_and_query| {
+                (
+                    Some(normalization.normalize_path(path_and_query.path()).as_ref().into()),
+                    normalization.normalize_query(path_and_query.decoded_query_map()),
+                )
+            })
             .unwrap_or_default();
 
         Self::new(method.clone(), path, query, None, None, None, None, None, None)
     }
+
+    /// Adds the [body_digest] to the extensions under [BODY_DIGEST_EXTENSION].
+    fn for_request_with_body(
+        method: &Method,
+        uri: &Uri,
+        headers: &HeaderMap,
+        body: &[u8],
+        normalization: &CacheKeyNormalization,
+    ) -> Option<Self> {
+        let mut cache_key = Self::for_normalized_request(method, uri, headers, normalization);
+        cache_key
+            .extensions
+            .get_or_insert_default()
+            .insert(Bytes::from_static(BODY_DIGEST_EXTENSION), Bytes::copy_from_slice(&body_digest(body)));
+        Some(cache_key)
+    }
+
+    /// Sets the scheme, host, and port.
+    fn apply_origin(&mut self, origin: &EffectiveOrigin) {
+        self.scheme = origin.scheme.clone();
+        self.host = origin.host.clone();
+        self.port = origin.port;
+    }
+
+    /// Sets the media type and languages.
+    fn apply_variant(&mut self, variant: &NegotiatedVariant) {
+        if let Some(media_type) = &variant.media_type {
+            self.media_type = Some(media_type.clone());
+        }
+
+        if let Some(language) = &variant.language {
+            self.languages = Some(BTreeSet::from([language.clone()]));
+        }
+    }
+
+    /// Adds the identity to the extensions under [AUTHENTICATED_EXTENSION].
+    fn apply_authenticated(&mut self, authenticated: &Authenticated) -> bool {
+        self.extensions.get_or_insert_default().insert(
+            Bytes::from_static(AUTHENTICATED_EXTENSION),
+            Bytes::copy_from_slice(authenticated.identity.as_bytes()),
+        );
+        true
+    }
 }
 
 impl CacheWeight for CommonCacheKey {
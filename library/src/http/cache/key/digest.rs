@@ -0,0 +1,15 @@
+use ring::digest::*;
+
+/// [CommonCacheKey](super::common::CommonCacheKey) extension key for the [body_digest].
+pub const BODY_DIGEST_EXTENSION: &[u8] = b"body-digest";
+
+/// SHA-256 digest of a request body.
+///
+/// Stable across processes, so it can be used in cache keys that are shared or persisted. Being
+/// cryptographic, clients cannot construct a body that collides with another client's body in
+/// order to poison its entries.
+pub fn body_digest(body: &[u8]) -> [u8; 32] {
+    let mut body_digest = [0; 32];
+    body_digest.copy_from_slice(digest(&SHA256, body).as_ref());
+    body_digest
+}
@@ -1,4 +1,11 @@
-use super::super::weight::*;
+use super::{
+    super::{
+        super::{authentication::*, origin::*},
+        middleware::*,
+        weight::*,
+    },
+    normalization::*,
+};
 
 use {
     http::{header::*, uri::*, *},
@@ -16,6 +23,63 @@ where
 {
     /// Create a cache key for a request.
     fn for_request(method: &Method, uri: &Uri, headers: &HeaderMap) -> Self;
+
+    /// Create a cache key for a request with normalization.
+    ///
+    /// The default implementation normalizes the [Uri] and then calls
+    /// [for_request](Self::for_request).
+    fn for_normalized_request(
+        method: &Method,
+        uri: &Uri,
+        headers: &HeaderMap,
+        normalization: &CacheKeyNormalization,
+    ) -> Self {
+        Self::for_request(method, &normalization.normalize_uri(uri), headers)
+    }
+
+    /// Create a cache key for a request with a body.
+    ///
+    /// Used for non-idempotent requests (e.g. `POST`) that have been opted in for caching, for
+    /// which the response depends on the body. The key *must* thus depend on the body.
+    ///
+    /// The default implementation returns [None], meaning that such requests will not be cached.
+    fn for_request_with_body(
+        _method: &Method,
+        _uri: &Uri,
+        _headers: &HeaderMap,
+        _body: &[u8],
+        _normalization: &CacheKeyNormalization,
+    ) -> Option<Self> {
+        None
+    }
+
+    /// Apply the effective request origin.
+    ///
+    /// Called by the caching middleware when the request has an [EffectiveOrigin] extension.
+    ///
+    /// The default implementation does nothing.
+    fn apply_origin(&mut self, _origin: &EffectiveOrigin) {}
+
+    /// Apply the negotiated content variant.
+    ///
+    /// Called by the caching middleware when the request has a [NegotiatedVariant] extension,
+    /// i.e. when the route has registered [Variants].
+    ///
+    /// The default implementation does nothing.
+    fn apply_variant(&mut self, _variant: &NegotiatedVariant) {}
+
+    /// Apply the authenticated identity.
+    ///
+    /// Called by the caching middleware when the request has an [Authenticated] extension. The
+    /// key *must* then depend on the identity, otherwise cached responses would leak across
+    /// users.
+    ///
+    /// Returns false if that is not supported, in which case the request will skip the cache.
+    ///
+    /// The default implementation returns false.
+    fn apply_authenticated(&mut self, _authenticated: &Authenticated) -> bool {
+        false
+    }
 }
 
 //
@@ -1,5 +1,7 @@
 mod common;
+mod digest;
 mod key;
+mod normalization;
 
 #[allow(unused_imports)]
-pub use {common::*, key::*};
+pub use {common::*, digest::*, key::*, normalization::*};
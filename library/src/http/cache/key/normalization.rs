@@ -0,0 +1,330 @@
+use super::super::super::uri::*;
+
+use {http::uri::*, std::borrow::*, url::form_urlencoded};
+
+/// Query parameters commonly used for tracking.
+///
+/// They do not affect the response, so it's a good idea to deny them. See
+/// [QueryNormalization::Deny].
+pub const TRACKING_QUERY_PARAMETERS: &[&str] =
+    &["utm_*", "fbclid", "gclid", "dclid", "msclkid", "mc_cid", "mc_eid", "_ga", "_gl", "yclid"];
+
+//
+// CacheKeyNormalization
+//
+
+/// Cache key normalization.
+///
+/// Allows URIs that differ in insignificant ways to map to the same cache key.
+///
+/// Note that query parameters are always sorted and decoded.
+///
+/// The path normalizations are also applied to the request forwarded upstream (see
+/// [normalize_uri_path](Self::normalize_uri_path)), otherwise a request for, say, `/x/../admin`
+/// could store the response for `/x/../admin` under the key for `/admin`. Query normalization is
+/// *not* forwarded, so make sure that removed parameters really do not affect the response.
+#[derive(Clone, Debug, Default)]
+pub struct CacheKeyNormalization {
+    /// Query normalization.
+    pub query: QueryNormalization,
+
+    /// Whether to fold the path to lowercase.
+    ///
+    /// Only use this if your paths are indeed case-insensitive. The upstream will see the folded
+    /// path.
+    pub fold_path_case: bool,
+
+    /// Whether to normalize percent-encoding in the path.
+    ///
+    /// Hex digits are uppercased and unreserved characters are decoded, as per
+    /// [RFC 3986](https://datatracker.ietf.org/doc/html/rfc3986#section-6.2.2.2). The upstream
+    /// will see the normalized path.
+    pub normalize_percent_encoding: bool,
+
+    /// Whether to remove `.` and `..` segments from the path, as per
+    /// [RFC 3986](https://datatracker.ietf.org/doc/html/rfc3986#section-5.2.4). The upstream will
+    /// see the resolved path.
+    pub remove_dot_segments: bool,
+
+    /// Trailing slash handling. The upstream will see the normalized path.
+    pub trailing_slash: TrailingSlash,
+}
+
+impl CacheKeyNormalization {
+    /// No normalization.
+    pub const NONE: Self = Self {
+        query: QueryNormalization::Keep,
+        fold_path_case: false,
+        normalize_percent_encoding: false,
+        remove_dot_segments: false,
+        trailing_slash: TrailingSlash::Keep,
+    };
+
+    /// Whether we do not normalize anything.
+    pub fn is_none(&self) -> bool {
+        matches!(self.query, QueryNormalization::Keep)
+            && !self.fold_path_case
+            && !self.normalize_percent_encoding
+            && !self.remove_dot_segments
+            && (self.trailing_slash == TrailingSlash::Keep)
+    }
+
+    /// Normalize a path.
+    pub fn normalize_path<'own>(&self, path: &'own str) -> Cow<'own, str> {
+        let mut path = Cow::Borrowed(path);
+
+        if self.normalize_percent_encoding && path.contains('%') {
+            path = normalize_percent_encoding(&path).into();
+        }
+
+        // After percent-encoding normalization so that decoded characters will be folded, too
+        if self.fold_path_case && path.chars().any(char::is_uppercase) {
+            path = fold_path_case(&path).into();
+        }
+
+        if self.remove_dot_segments && path.split('/').any(|segment| (segment == ".") || (segment == "..")) {
+            path = remove_dot_segments(&path).into();
+        }
+
+        match self.trailing_slash {
+            TrailingSlash::Keep => {}
+
+            TrailingSlash::Remove => {
+                if (path.len() > 1) && path.ends_with('/') {
+                    let trimmed = path.trim_end_matches('/');
+                    path = if trimmed.is_empty() { "/" } else { trimmed }.to_string().into();
+                }
+            }
+
+            TrailingSlash::Add => {
+                if !path.ends_with('/') {
+                    path = (path.into_owned() + "/").into();
+                }
+            }
+        }
+
+        path
+    }
+
+    /// Normalize just the path of a [Uri], keeping the query as is.
+    ///
+    /// This is what should be forwarded upstream. If normalization fails (which should not happen)
+    /// the original is returned.
+    pub fn normalize_uri_path<'own>(&self, uri: &'own Uri) -> Cow<'own, Uri> {
+        let Some(path_and_query) = uri.path_and_query() else {
+            return Cow::Borrowed(uri);
+        };
+
+        let Cow::Owned(mut normalized) = self.normalize_path(path_and_query.path()) else {
+            return Cow::Borrowed(uri);
+        };
+
+        if let Some(query) = path_and_query.query() {
+            normalized = normalized + "?" + query;
+        }
+
+        let mut parts = uri.clone().into_parts();
+        parts.path_and_query = match normalized.parse() {
+            Ok(path_and_query) => Some(path_and_query),
+            Err(_) => return Cow::Borrowed(uri),
+        };
+
+        match Uri::from_parts(parts) {
+            Ok(uri) => Cow::Owned(uri),
+            Err(_) => Cow::Borrowed(uri),
+        }
+    }
+
+    /// Normalize a query.
+    ///
+    /// Returns [None] if no parameters remain.
+    pub fn normalize_query(&self, query: Option<QueryMap>) -> Option<QueryMap> {
+        let mut query = query?;
+
+        match &self.query {
+            QueryNormalization::Keep => {}
+
+            QueryNormalization::Allow(patterns) => {
+                query.retain(|name, _values| matches_any_query_parameter(patterns, name.as_ref()))
+            }
+
+            QueryNormalization::Deny(patterns) => {
+                query.retain(|name, _values| !matches_any_query_parameter(patterns, name.as_ref()))
+            }
+        }
+
+        if query.is_empty() { None } else { Some(query) }
+    }
+
+    /// Normalize a [Uri].
+    ///
+    /// The query will be sorted and reencoded. If normalization fails (which should not happen)
+    /// the original is returned.
+    pub fn normalize_uri<'own>(&self, uri: &'own Uri) -> Cow<'own, Uri> {
+        if self.is_none() {
+            return Cow::Borrowed(uri);
+        }
+
+        let Some(path_and_query) = uri.path_and_query() else {
+            return Cow::Borrowed(uri);
+        };
+
+        let mut normalized = self.normalize_path(path_and_query.path()).into_owned();
+
+        if let Some(query) = self.normalize_query(path_and_query.decoded_query_map()) {
+            let mut serializer = form_urlencoded::Serializer::new(String::default());
+            for (name, values) in &query {
+                for value in values {
+                    serializer.append_pair(name, value);
+                }
+            }
+            normalized = normalized + "?" + &serializer.finish();
+        }
+
+        let mut parts = uri.clone().into_parts();
+        parts.path_and_query = match normalized.parse() {
+            Ok(path_and_query) => Some(path_and_query),
+            Err(_) => return Cow::Borrowed(uri),
+        };
+
+        match Uri::from_parts(parts) {
+            Ok(uri) => Cow::Owned(uri),
+            Err(_) => Cow::Borrowed(uri),
+        }
+    }
+}
+
+//
+// QueryNormalization
+//
+
+/// Query normalization for [CacheKeyNormalization].
+///
+/// Patterns are parameter names, optionally ending with a `*` wildcard to match a prefix, e.g.
+/// "utm_*".
+#[derive(Clone, Debug, Default)]
+pub enum QueryNormalization {
+    /// Keep all parameters.
+    #[default]
+    Keep,
+
+    /// Keep only parameters that match the patterns.
+    Allow(Vec<String>),
+
+    /// Remove parameters that match the patterns. See [TRACKING_QUERY_PARAMETERS].
+    Deny(Vec<String>),
+}
+
+//
+// TrailingSlash
+//
+
+/// Trailing slash handling for [CacheKeyNormalization].
+///
+/// The root path ("/") is never modified.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TrailingSlash {
+    /// Keep as is.
+    #[default]
+    Keep,
+
+    /// Remove trailing slashes.
+    Remove,
+
+    /// Add a trailing slash if there isn't one.
+    Add,
+}
+
+fn matches_any_query_parameter(patterns: &[String], name: &str) -> bool {
+    patterns.iter().any(|pattern| match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => name == pattern,
+    })
+}
+
+// https://datatracker.ietf.org/doc/html/rfc3986#section-6.2.2.2
+fn normalize_percent_encoding(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut normalized = String::with_capacity(path.len());
+
+    let mut index = 0;
+    while index < bytes.len() {
+        if (bytes[index] == b'%')
+            && (index + 2 < bytes.len())
+            && let (Some(high), Some(low)) = (hex_value(bytes[index + 1]), hex_value(bytes[index + 2]))
+        {
+            let byte = (high << 4) | low;
+            if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+                normalized.push(byte as char);
+            } else {
+                normalized.push('%');
+                normalized.push(bytes[index + 1].to_ascii_uppercase() as char);
+                normalized.push(bytes[index + 2].to_ascii_uppercase() as char);
+            }
+            index += 3;
+        } else {
+            // We always advance by whole chars, so we are at a char boundary here
+            let character = path[index..].chars().next().expect("char");
+            normalized.push(character);
+            index += character.len_utf8();
+        }
+    }
+
+    normalized
+}
+
+// Keeps percent-encoded hex digits as they are
+fn fold_path_case(path: &str) -> String {
+    let mut folded = String::with_capacity(path.len());
+
+    let mut escape = 0;
+    for character in path.chars() {
+        if escape > 0 {
+            folded.push(character);
+            escape -= 1;
+        } else {
+            if character == '%' {
+                escape = 2;
+            }
+            folded.extend(character.to_lowercase());
+        }
+    }
+
+    folded
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|digit| digit as u8)
+}
+
+// https://datatracker.ietf.org/doc/html/rfc3986#section-5.2.4
+fn remove_dot_segments(path: &str) -> String {
+    let absolute = path.starts_with('/');
+    let segments: Vec<_> = path.split('/').skip(if absolute { 1 } else { 0 }).collect();
+    let last = segments.len().saturating_sub(1);
+
+    let mut output: Vec<&str> = Vec::with_capacity(segments.len());
+    let mut trailing_slash = false;
+    for (index, segment) in segments.into_iter().enumerate() {
+        match segment {
+            "." => trailing_slash = index == last,
+
+            ".." => {
+                output.pop();
+                trailing_slash = index == last;
+            }
+
+            _ => {
+                output.push(segment);
+                trailing_slash = false;
+            }
+        }
+    }
+
+    let mut normalized = if absolute { String::from("/") } else { String::default() };
+    normalized += &output.join("/");
+    if trailing_slash && !normalized.ends_with('/') {
+        normalized.push('/');
+    }
+    normalized
+}
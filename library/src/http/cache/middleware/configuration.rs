@@ -1,8 +1,12 @@
 use super::{
-    super::super::{cache::*, headers::*},
+    super::super::{super::std::immutable::*, cache::*, headers::*},
     hooks::*,
+    media_types::*,
+    variants::*,
 };
 
+use std::sync::*;
+
 /// Encodings in order from most preferred to least.
 ///
 /// We are generally preferring to optimize for compute rather than bandwidth.
@@ -16,6 +20,12 @@ pub const ENCODINGS_BY_PREFERENCE: &[EncodingHeaderValue] = &[
     EncodingHeaderValue::Zstandard,
 ];
 
+/// Encodings with a dictionary in order from most preferred to least.
+///
+/// See [Compression Dictionary Transport](https://datatracker.ietf.org/doc/rfc9842/).
+pub const DICTIONARY_ENCODINGS_BY_PREFERENCE: &[EncodingHeaderValue] =
+    &[EncodingHeaderValue::DictionaryBrotli, EncodingHeaderValue::DictionaryZstandard];
+
 //
 // MiddlewareCachingConfiguration
 //
@@ -31,9 +41,43 @@ pub struct MiddlewareCachingConfiguration<RequestBodyT, CacheT, CacheKeyT> {
     /// Cacheable by response (hook).
     pub cacheable_by_response: Option<CacheableHook>,
 
+    /// Cacheable `POST` by request (hook).
+    pub cacheable_post: Option<CacheableHook>,
+
+    /// Maximum size in bytes of `POST` request bodies to buffer for the cache key.
+    pub max_post_body_size: usize,
+
+    /// Cache key normalization.
+    pub key_normalization: CacheKeyNormalization,
+
     /// Cache key (hook).
     pub cache_key: Option<CacheKeyHook<CacheKeyT, RequestBodyT>>,
 
+    /// Admission policy.
+    ///
+    /// [None] means all cacheable responses are stored.
+    pub admission: Option<Arc<dyn AdmissionPolicy<CacheKeyT>>>,
+
+    /// Cache name for the `Cache-Status` header.
+    ///
+    /// [None] means the header is disabled.
+    pub cache_status_name: Option<ByteString>,
+
+    /// Whether to set the `X-Cache` header.
+    pub x_cache: bool,
+
+    /// Media types of streaming responses.
+    ///
+    /// Such responses (as well as those with `X-Accel-Buffering: no`) are passed through without
+    /// buffering and are never cached. Other long-lived responses (e.g. long polling) must send
+    /// `X-Accel-Buffering: no`, otherwise they will be buffered.
+    pub streaming_media_types: Vec<MediaTypeSelector>,
+
+    /// Content variants by route.
+    ///
+    /// Used for server-side negotiation of `Accept` and `Accept-Language`.
+    pub variants: VariantRegistry,
+
     /// Inner configuration.
     pub inner: CachingConfiguration,
 }
@@ -44,7 +88,15 @@ impl<RequestBodyT, CacheT, CacheKeyT> Default for MiddlewareCachingConfiguration
             cache: None,
             cacheable_by_request: None,
             cacheable_by_response: None,
+            cacheable_post: None,
+            max_post_body_size: 64 * 1024, // 64 KiB
+            key_normalization: Default::default(),
             cache_key: None,
+            admission: None,
+            cache_status_name: None,
+            x_cache: false,
+            streaming_media_types: STREAMING_MEDIA_TYPES.into(),
+            variants: Default::default(),
             inner: CachingConfiguration {
                 min_body_size: 0,
                 max_body_size: 1024 * 1024, // 1 MiB
@@ -66,7 +118,15 @@ where
             cache: self.cache.clone(),
             cacheable_by_request: self.cacheable_by_request.clone(),
             cacheable_by_response: self.cacheable_by_response.clone(),
+            cacheable_post: self.cacheable_post.clone(),
+            max_post_body_size: self.max_post_body_size,
+            key_normalization: self.key_normalization.clone(),
             cache_key: self.cache_key.clone(),
+            admission: self.admission.clone(),
+            cache_status_name: self.cache_status_name.clone(),
+            x_cache: self.x_cache,
+            streaming_media_types: self.streaming_media_types.clone(),
+            variants: self.variants.clone(),
             inner: self.inner.clone(),
         }
     }
@@ -82,23 +142,59 @@ pub struct MiddlewareEncodingConfiguration {
     /// Enabled encodings in order of preference.
     pub enabled_encodings_by_preference: Option<Vec<EncodingHeaderValue>>,
 
+    /// Encodings in order of preference for specific media types.
+    ///
+    /// These override [enabled_encodings_by_preference](Self::enabled_encodings_by_preference)
+    /// (but only if it's not [None]). The first match is used.
+    pub encodings_by_media_type: Vec<MediaTypeEncodings>,
+
     /// Encodable by request (hook).
     pub encodable_by_request: Option<EncodableHook>,
 
     /// Encodable by response (hook).
     pub encodable_by_response: Option<EncodableHook>,
 
+    /// Encodable by the response's media type.
+    pub encodable_by_media_type: MediaTypeEncodingPolicy,
+
     /// Inner configuration.
     pub inner: EncodingConfiguration,
 }
 
+impl MiddlewareEncodingConfiguration {
+    /// Enabled encodings in order of preference for a media type.
+    ///
+    /// [None] means that encoding is disabled.
+    pub fn enabled_encodings_for(&self, media_type: Option<&MediaType>) -> Option<&[EncodingHeaderValue]> {
+        let enabled_encodings = self.enabled_encodings_by_preference.as_ref()?;
+
+        if let Some(media_type) = media_type
+            && let Some(media_type_encodings) =
+                self.encodings_by_media_type.iter().find(|encodings| encodings.media_type.matches(media_type))
+        {
+            return Some(&media_type_encodings.encodings);
+        }
+
+        Some(enabled_encodings)
+    }
+}
+
 impl Default for MiddlewareEncodingConfiguration {
     fn default() -> Self {
         Self {
             enabled_encodings_by_preference: Some(ENCODINGS_BY_PREFERENCE.into()),
+            encodings_by_media_type: Default::default(),
             encodable_by_request: None,
             encodable_by_response: None,
-            inner: EncodingConfiguration { min_body_size: 0, encodable_by_default: true, keep_identity_encoding: true },
+            encodable_by_media_type: Default::default(),
+            inner: EncodingConfiguration {
+                min_body_size: 0,
+                encodable_by_default: true,
+                keep_identity_encoding: true,
+                memory_pressure: None,
+                retention: Default::default(),
+                dictionaries: None,
+            },
         }
     }
 }
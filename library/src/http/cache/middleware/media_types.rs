@@ -0,0 +1,131 @@
+use super::super::super::headers::*;
+
+/// Media types that are encodable by default.
+///
+/// These are textual formats that compress well.
+pub const ENCODABLE_MEDIA_TYPES: &[MediaTypeSelector] = &[
+    MediaTypeSelector::new(Selector::Specific(MediaTypeSegment::new_fostered("text")), Selector::Any),
+    MediaTypeSelector::new_fostered("image", "svg+xml"),
+    MediaTypeSelector::new_fostered("application", "json"),
+    MediaTypeSelector::new_fostered("application", "ld+json"),
+    MediaTypeSelector::new_fostered("application", "manifest+json"),
+    MediaTypeSelector::new_fostered("application", "javascript"),
+    MediaTypeSelector::new_fostered("application", "x-javascript"),
+    MediaTypeSelector::new_fostered("application", "ecmascript"),
+    MediaTypeSelector::new_fostered("application", "xml"),
+    MediaTypeSelector::new_fostered("application", "xhtml+xml"),
+    MediaTypeSelector::new_fostered("application", "atom+xml"),
+    MediaTypeSelector::new_fostered("application", "rss+xml"),
+    MediaTypeSelector::new_fostered("application", "wasm"),
+];
+
+/// Media types that are unencodable by default.
+///
+/// These are formats that are already compressed, so encoding them would be a waste of compute.
+pub const UNENCODABLE_MEDIA_TYPES: &[MediaTypeSelector] = &[
+    MediaTypeSelector::new(Selector::Specific(MediaTypeSegment::new_fostered("image")), Selector::Any),
+    MediaTypeSelector::new(Selector::Specific(MediaTypeSegment::new_fostered("video")), Selector::Any),
+    MediaTypeSelector::new(Selector::Specific(MediaTypeSegment::new_fostered("audio")), Selector::Any),
+    MediaTypeSelector::new_fostered("font", "woff"),
+    MediaTypeSelector::new_fostered("font", "woff2"),
+    MediaTypeSelector::new_fostered("application", "zip"),
+    MediaTypeSelector::new_fostered("application", "gzip"),
+    MediaTypeSelector::new_fostered("application", "x-gzip"),
+    MediaTypeSelector::new_fostered("application", "x-bzip2"),
+    MediaTypeSelector::new_fostered("application", "x-xz"),
+    MediaTypeSelector::new_fostered("application", "zstd"),
+    MediaTypeSelector::new_fostered("application", "x-7z-compressed"),
+    MediaTypeSelector::new_fostered("application", "vnd.rar"),
+    MediaTypeSelector::new_fostered("application", "x-rar-compressed"),
+];
+
+/// Media types of streaming responses.
+///
+/// Such responses are passed through without buffering and without caching.
+pub const STREAMING_MEDIA_TYPES: &[MediaTypeSelector] = &[
+    MediaTypeSelector::new_fostered("text", "event-stream"),
+    MediaTypeSelector::new_fostered("multipart", "x-mixed-replace"),
+];
+
+//
+// MediaTypeEncodingPolicy
+//
+
+/// Encoding policy by media type (from the response's `Content-Type`).
+///
+/// The encodable media types are checked first, so they can be used for exceptions to the
+/// unencodable media types (e.g. `image/svg+xml` vs. `image/*`). Media types with a `+json` or
+/// `+xml` structured syntax suffix are encodable unless listed as unencodable.
+#[derive(Clone, Debug)]
+pub struct MediaTypeEncodingPolicy {
+    /// Encodable media types.
+    pub encodable: Vec<MediaTypeSelector>,
+
+    /// Unencodable media types.
+    pub unencodable: Vec<MediaTypeSelector>,
+
+    /// Whether media types that match neither list are encodable.
+    ///
+    /// Also used for responses without a `Content-Type`.
+    pub encodable_by_default: bool,
+}
+
+impl MediaTypeEncodingPolicy {
+    /// Constructor.
+    pub fn new(
+        encodable: Vec<MediaTypeSelector>,
+        unencodable: Vec<MediaTypeSelector>,
+        encodable_by_default: bool,
+    ) -> Self {
+        Self { encodable, unencodable, encodable_by_default }
+    }
+
+    /// Policy that does not consider media types at all.
+    pub fn disabled() -> Self {
+        Self::new(Default::default(), Default::default(), true)
+    }
+
+    /// Whether a media type is encodable.
+    pub fn is_encodable(&self, media_type: Option<&MediaType>) -> bool {
+        let Some(media_type) = media_type else {
+            return self.encodable_by_default;
+        };
+
+        if self.encodable.iter().any(|selector| selector.matches(media_type)) {
+            true
+        } else if self.unencodable.iter().any(|selector| selector.matches(media_type)) {
+            false
+        } else {
+            let subtype = media_type.subtype.as_ref();
+            if subtype.ends_with("+json") || subtype.ends_with("+xml") { true } else { self.encodable_by_default }
+        }
+    }
+}
+
+impl Default for MediaTypeEncodingPolicy {
+    /// [ENCODABLE_MEDIA_TYPES] and [UNENCODABLE_MEDIA_TYPES], with other media types encodable.
+    fn default() -> Self {
+        Self::new(ENCODABLE_MEDIA_TYPES.into(), UNENCODABLE_MEDIA_TYPES.into(), true)
+    }
+}
+
+//
+// MediaTypeEncodings
+//
+
+/// Encodings in order from most preferred to least for media types.
+#[derive(Clone, Debug)]
+pub struct MediaTypeEncodings {
+    /// Media type.
+    pub media_type: MediaTypeSelector,
+
+    /// Encodings in order from most preferred to least.
+    pub encodings: Vec<EncodingHeaderValue>,
+}
+
+impl MediaTypeEncodings {
+    /// Constructor.
+    pub fn new(media_type: MediaTypeSelector, encodings: Vec<EncodingHeaderValue>) -> Self {
+        Self { media_type, encodings }
+    }
+}
@@ -1,7 +1,10 @@
 mod configuration;
 mod hooks;
+mod media_types;
 mod request;
 mod responses;
+mod status;
+mod variants;
 
 #[allow(unused_imports)]
-pub use {configuration::*, hooks::*, request::*, responses::*};
+pub use {configuration::*, hooks::*, media_types::*, request::*, responses::*, status::*, variants::*};
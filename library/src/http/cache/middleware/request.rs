@@ -1,13 +1,14 @@
 use super::{
     super::{
-        super::{super::transcoding::*, headers::*},
+        super::{super::transcoding::*, authentication::*, headers::*, origin::*},
         key::*,
     },
     configuration::*,
     hooks::*,
+    variants::*,
 };
 
-use http::*;
+use http::{header::*, *};
 
 //
 // CacheableEncodableRequest
@@ -15,33 +16,103 @@ use http::*;
 
 /// Cacheable and/or encodable request.
 pub trait CacheableEncodableRequest<RequestBodyT> {
+    /// Whether this is a protocol upgrade request (e.g. WebSocket).
+    ///
+    /// That's either a `Connection: upgrade` header or a `CONNECT` method.
+    fn is_upgrade(&self) -> bool;
+
     /// May call `cacheable_by_request` hook.
+    ///
+    /// Upgrade requests (see [is_upgrade](Self::is_upgrade)) always skip the cache. So do requests
+    /// with an `Authorization` header that have not been [Authenticated], because we cannot know
+    /// whether the response is specific to the user.
     fn should_skip_cache<CacheT, CacheKeyT>(
         &self,
         configuration: &MiddlewareCachingConfiguration<RequestBodyT, CacheT, CacheKeyT>,
     ) -> bool;
 
     /// May call `cache_key` hook.
+    ///
+    /// The key is normalized according to `key_normalization`.
+    ///
+    /// Note that for `HEAD` requests the key is created as if for `GET`, because a `HEAD`
+    /// response is the `GET` response without the body.
+    ///
+    /// Returns [None] if the request is [Authenticated] but the cache key implementation does not
+    /// support partitioning by identity.
     fn cache_key_with_hook<CacheT, CacheKeyT>(
         &self,
         configuration: &MiddlewareCachingConfiguration<RequestBodyT, CacheT, CacheKeyT>,
-    ) -> CacheKeyT
+    ) -> Option<CacheKeyT>
+    where
+        CacheKeyT: CacheKey;
+
+    /// May call `cache_key` hook.
+    ///
+    /// For requests with a buffered body (opted-in `POST`). Returns [None] if the cache key
+    /// implementation does not support bodies, or if the request is [Authenticated] but it does
+    /// not support partitioning by identity.
+    fn cache_key_with_body_and_hook<CacheT, CacheKeyT>(
+        &self,
+        body: &[u8],
+        configuration: &MiddlewareCachingConfiguration<RequestBodyT, CacheT, CacheKeyT>,
+    ) -> Option<CacheKeyT>
     where
         CacheKeyT: CacheKey;
 
     /// May call `encodable_by_request` hook.
-    fn select_encoding(&self, configuration: &MiddlewareEncodingConfiguration) -> Encoding;
+    fn select_encoding(&self, configuration: &MiddlewareEncodingConfiguration) -> Encoding {
+        self.select_encoding_for(None, configuration)
+    }
+
+    /// May call `encodable_by_request` hook.
+    ///
+    /// Uses the encoding preferences for the media type, if known and configured.
+    fn select_encoding_for(
+        &self,
+        media_type: Option<&MediaType>,
+        configuration: &MiddlewareEncodingConfiguration,
+    ) -> Encoding;
+
+    /// The encoding with a dictionary (see
+    /// [Compression Dictionary Transport](https://datatracker.ietf.org/doc/rfc9842/)) to use
+    /// instead of the selected encoding.
+    ///
+    /// Requires the `Available-Dictionary` header to refer to one of our registered dictionaries
+    /// and the `Accept-Encoding` header to allow `dcb` or `dcz`.
+    fn dictionary_encoding(&self, configuration: &MiddlewareEncodingConfiguration) -> Option<Encoding>;
+
+    /// The `Accept-Encoding` preferences for [validate_encoding](super::UpstreamResponse::validate_encoding), but only if
+    /// there are encoding preferences for specific media types (otherwise [None]).
+    ///
+    /// We need these to reselect the encoding once the upstream response's media type is known.
+    fn accept_encoding_for_upstream(
+        &self,
+        configuration: &MiddlewareEncodingConfiguration,
+    ) -> Option<Preferences<EncodingHeaderValue>>;
 }
 
 impl<RequestBodyT> CacheableEncodableRequest<RequestBodyT> for Request<RequestBodyT> {
+    fn is_upgrade(&self) -> bool {
+        (self.method() == Method::CONNECT) || self.headers().connection_upgrade()
+    }
+
     fn should_skip_cache<CacheT, CacheKeyT>(
         &self,
         configuration: &MiddlewareCachingConfiguration<RequestBodyT, CacheT, CacheKeyT>,
     ) -> bool {
-        let mut skip_cache = if !configuration.cache.is_none() {
+        let mut skip_cache = if self.is_upgrade() {
+            tracing::debug!("skip (upgrade)");
+            true
+        } else if !configuration.cache.is_none() {
             let method = self.method();
             if method.is_idempotent() {
                 false
+            } else if (method == Method::POST)
+                && let Some(cacheable_post) = &configuration.cacheable_post
+                && cacheable_post(CacheableHookContext::new(self.uri(), self.headers()))
+            {
+                false
             } else {
                 tracing::debug!("skip (non-idempotent {})", method);
                 true
@@ -59,30 +130,99 @@ impl<RequestBodyT> CacheableEncodableRequest<RequestBodyT> for Request<RequestBo
             skip_cache = true;
         }
 
+        if !skip_cache
+            && self.headers().contains_key(AUTHORIZATION)
+            && self.extensions().get::<Authenticated>().is_none()
+        {
+            tracing::debug!("skip (unauthenticated {})", AUTHORIZATION);
+            skip_cache = true;
+        }
+
         skip_cache
     }
 
     fn cache_key_with_hook<CacheT, CacheKeyT>(
         &self,
         configuration: &MiddlewareCachingConfiguration<RequestBodyT, CacheT, CacheKeyT>,
-    ) -> CacheKeyT
+    ) -> Option<CacheKeyT>
     where
         CacheKeyT: CacheKey,
     {
-        let mut cache_key = self.cache_key();
+        let method = if self.method() == Method::HEAD { &Method::GET } else { self.method() };
+        let mut cache_key =
+            CacheKeyT::for_normalized_request(method, self.uri(), self.headers(), &configuration.key_normalization);
+
+        if let Some(origin) = self.extensions().get::<EffectiveOrigin>() {
+            cache_key.apply_origin(origin);
+        }
+
+        if let Some(variant) = self.extensions().get::<NegotiatedVariant>() {
+            cache_key.apply_variant(variant);
+        }
+
+        if let Some(authenticated) = self.extensions().get::<Authenticated>()
+            && !cache_key.apply_authenticated(authenticated)
+        {
+            return None;
+        }
 
         if let Some(cache_key_hook) = &configuration.cache_key {
             cache_key_hook(CacheKeyHookContext::new(&mut cache_key, self));
         }
 
-        cache_key
+        Some(cache_key)
     }
 
-    fn select_encoding(&self, configuration: &MiddlewareEncodingConfiguration) -> Encoding {
-        let encoding = match &configuration.enabled_encodings_by_preference {
+    fn cache_key_with_body_and_hook<CacheT, CacheKeyT>(
+        &self,
+        body: &[u8],
+        configuration: &MiddlewareCachingConfiguration<RequestBodyT, CacheT, CacheKeyT>,
+    ) -> Option<CacheKeyT>
+    where
+        CacheKeyT: CacheKey,
+    {
+        let mut cache_key = CacheKeyT::for_request_with_body(
+            self.method(),
+            self.uri(),
+            self.headers(),
+            body,
+            &configuration.key_normalization,
+        )?;
+
+        if let Some(origin) = self.extensions().get::<EffectiveOrigin>() {
+            cache_key.apply_origin(origin);
+        }
+
+        if let Some(variant) = self.extensions().get::<NegotiatedVariant>() {
+            cache_key.apply_variant(variant);
+        }
+
+        if let Some(authenticated) = self.extensions().get::<Authenticated>()
+            && !cache_key.apply_authenticated(authenticated)
+        {
+            return None;
+        }
+
+        if let Some(cache_key_hook) = &configuration.cache_key {
+            cache_key_hook(CacheKeyHookContext::new(&mut cache_key, self));
+        }
+
+        Some(cache_key)
+    }
+
+    fn select_encoding_for(
+        &self,
+        media_type: Option<&MediaType>,
+        configuration: &MiddlewareEncodingConfiguration,
+    ) -> Encoding {
+        let encoding = match configuration.enabled_encodings_for(media_type) {
             Some(enabled_encodings) => {
                 if !enabled_encodings.is_empty() {
-                    self.headers().accept_encoding().best(enabled_encodings).cloned().unwrap_or_default().into()
+                    match self.headers().accept_encoding().best(enabled_encodings) {
+                        // Encodings with a dictionary are selected separately
+                        Some(encoding) if !encoding.is_dictionary() => (*encoding).into(),
+                        _ => Encoding::Identity,
+                    }
                 } else {
                     return Encoding::Identity;
                 }
@@ -101,4 +241,28 @@ impl<RequestBodyT> CacheableEncodableRequest<RequestBodyT> for Request<RequestBo
 
         encoding
     }
+
+    fn dictionary_encoding(&self, configuration: &MiddlewareEncodingConfiguration) -> Option<Encoding> {
+        let dictionaries = configuration.inner.dictionaries.as_ref()?;
+
+        let headers = self.headers();
+        let hash = headers.available_dictionary()?;
+        if !dictionaries.contains(&hash) {
+            tracing::debug!("unknown dictionary: {}", hash);
+            return None;
+        }
+
+        match headers.accept_encoding().best(DICTIONARY_ENCODINGS_BY_PREFERENCE)? {
+            EncodingHeaderValue::DictionaryBrotli => Some(Encoding::DictionaryBrotli(hash)),
+            EncodingHeaderValue::DictionaryZstandard => Some(Encoding::DictionaryZstandard(hash)),
+            _ => None,
+        }
+    }
+
+    fn accept_encoding_for_upstream(
+        &self,
+        configuration: &MiddlewareEncodingConfiguration,
+    ) -> Option<Preferences<EncodingHeaderValue>> {
+        if configuration.encodings_by_media_type.is_empty() { None } else { Some(self.headers().accept_encoding()) }
+    }
 }
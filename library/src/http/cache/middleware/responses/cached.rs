@@ -20,6 +20,8 @@ pub trait ToTranscodingResponse {
     ///
     /// Will update the cache if we are modified.
     ///
+    /// The stored trailers will be replayed after the body.
+    ///
     /// If we encounter an error will return a response with [StatusCode::INTERNAL_SERVER_ERROR].
     async fn to_transcoding_response<ResponseBodyT, CacheT, CacheKeyT>(
         self,
@@ -60,6 +62,9 @@ impl ToTranscodingResponse for CachedResponseRef {
     {
         match self.to_response(&encoding, configuration).await {
             Ok((response, modified)) => {
+                let response =
+                    response.map(|body: TranscodingBody<ResponseBodyT>| body.with_trailers(self.trailers.clone()));
+
                 if is_new {
                     cache.put(key, self).await;
                 } else if let Some(modified) = modified {
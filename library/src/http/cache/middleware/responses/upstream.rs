@@ -12,6 +12,23 @@ use http::{header::*, *};
 
 /// Upstream response.
 pub trait UpstreamResponse<ResponseBodyT> {
+    /// Whether this is a protocol upgrade response (`101 Switching Protocols`).
+    ///
+    /// Such responses must be passed through as is.
+    fn is_upgrade(&self) -> bool;
+
+    /// Whether this is a streaming response.
+    ///
+    /// That's either a media type in `streaming_media_types` or a `X-Accel-Buffering: no` header.
+    /// Such responses must not be buffered.
+    ///
+    /// Other long-lived responses (e.g. long polling) cannot be detected, so they must send
+    /// `X-Accel-Buffering: no`.
+    fn is_streaming<RequestBodyT, CacheT, CacheKeyT>(
+        &self,
+        configuration: &MiddlewareCachingConfiguration<CacheT, CacheKeyT, RequestBodyT>,
+    ) -> bool;
+
     /// Check if we should skip the cache.
     ///
     /// Also returns the value of `Content-Length` if available.
@@ -26,6 +43,11 @@ pub trait UpstreamResponse<ResponseBodyT> {
 
     /// Validate encoding.
     ///
+    /// If `accept_encoding` is provided (see
+    /// [accept_encoding_for_upstream](super::super::CacheableEncodableRequest::accept_encoding_for_upstream))
+    /// then the encoding is reselected according to the preferences for the response's media
+    /// type. Note that the `encodable_by_request` hook is *not* called again.
+    ///
     /// Checks `content_length`, if provided, against `min_body_size`. And gives the hook one last
     /// chance to skip encoding.
     ///
@@ -34,12 +56,35 @@ pub trait UpstreamResponse<ResponseBodyT> {
         &self,
         uri: &Uri,
         encoding: Encoding,
+        accept_encoding: Option<&Preferences<EncodingHeaderValue>>,
         content_length: Option<usize>,
         configuration: &MiddlewareEncodingConfiguration,
     ) -> (Encoding, bool);
 }
 
 impl<ResponseBodyT> UpstreamResponse<ResponseBodyT> for Response<ResponseBodyT> {
+    fn is_upgrade(&self) -> bool {
+        self.status() == StatusCode::SWITCHING_PROTOCOLS
+    }
+
+    fn is_streaming<RequestBodyT, CacheT, CacheKeyT>(
+        &self,
+        configuration: &MiddlewareCachingConfiguration<CacheT, CacheKeyT, RequestBodyT>,
+    ) -> bool {
+        let headers = self.headers();
+
+        if !headers.x_accel_buffering(true) {
+            return true;
+        }
+
+        match headers.content_type() {
+            Some(media_type) => {
+                configuration.streaming_media_types.iter().any(|selector| selector.matches(&media_type))
+            }
+            None => false,
+        }
+    }
+
     fn should_skip_cache<RequestBodyT, CacheT, CacheKeyT>(
         &self,
         uri: &Uri,
@@ -89,10 +134,23 @@ impl<ResponseBodyT> UpstreamResponse<ResponseBodyT> for Response<ResponseBodyT>
     fn validate_encoding(
         &self,
         uri: &Uri,
-        encoding: Encoding,
+        mut encoding: Encoding,
+        accept_encoding: Option<&Preferences<EncodingHeaderValue>>,
         content_length: Option<usize>,
         configuration: &MiddlewareEncodingConfiguration,
     ) -> (Encoding, bool) {
+        // Identity means that the client or the request hook doesn't want encoding
+        if encoding != Encoding::Identity
+            && let Some(accept_encoding) = accept_encoding
+            && let Some(enabled_encodings) = configuration.enabled_encodings_for(self.headers().content_type().as_ref())
+        {
+            encoding = match accept_encoding.best(enabled_encodings) {
+                // Encodings with a dictionary are selected separately
+                Some(encoding) if !encoding.is_dictionary() => (*encoding).into(),
+                _ => Encoding::Identity,
+            };
+        }
+
         if encoding == Encoding::Identity {
             (encoding, false)
         } else {
@@ -106,6 +164,11 @@ impl<ResponseBodyT> UpstreamResponse<ResponseBodyT> for Response<ResponseBodyT>
                 }
             }
 
+            if !configuration.encodable_by_media_type.is_encodable(self.headers().content_type().as_ref()) {
+                tracing::debug!("not encoding to {} (media type)", encoding);
+                return (Encoding::Identity, true);
+            }
+
             match &configuration.encodable_by_response {
                 Some(encodable) => {
                     if encodable(EncodableHookContext::new(&encoding, uri, self.headers())) {
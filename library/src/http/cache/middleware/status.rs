@@ -0,0 +1,147 @@
+use super::{
+    super::super::{super::std::string::*, headers::*},
+    configuration::*,
+};
+
+use {
+    http::header::*,
+    std::{fmt, result::Result, time::*},
+};
+
+//
+// CacheStatus
+//
+
+/// Cache status of a response.
+///
+/// Used for the `Cache-Status` header (see
+/// [IETF RFC 9211](https://datatracker.ietf.org/doc/html/rfc9211)) and the legacy `X-Cache`
+/// header.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CacheStatus {
+    /// Why the request was forwarded to the upstream.
+    ///
+    /// [None] means it was a hit.
+    pub forward: Option<CacheForward>,
+
+    /// Whether the upstream response was stored in the cache.
+    pub stored: bool,
+
+    /// Remaining time to live.
+    pub ttl: Option<Duration>,
+}
+
+impl CacheStatus {
+    /// Constructor.
+    pub fn new(forward: Option<CacheForward>, stored: bool, ttl: Option<Duration>) -> Self {
+        Self { forward, stored, ttl }
+    }
+
+    /// Constructor for a hit.
+    pub fn hit(ttl: Option<Duration>) -> Self {
+        Self::new(None, false, ttl)
+    }
+
+    /// Constructor for a forward.
+    pub fn forward(forward: CacheForward, stored: bool) -> Self {
+        Self::new(Some(forward), stored, None)
+    }
+
+    /// Whether we are a hit.
+    pub fn is_hit(&self) -> bool {
+        self.forward.is_none()
+    }
+
+    /// The `Cache-Status` entry for a cache.
+    ///
+    /// Fails if the cache name cannot be represented (it must be printable ASCII).
+    pub fn to_cache_status(&self, cache_name: &str) -> Result<String, ParseError> {
+        let mut entry = StructuredItem::new(if is_structured_token(cache_name) {
+            BareItem::Token(cache_name.into())
+        } else {
+            BareItem::String(cache_name.into())
+        });
+
+        match self.forward {
+            None => entry = entry.with_parameter("hit".into(), true.into()),
+            Some(forward) => entry = entry.with_parameter("fwd".into(), BareItem::Token(forward.to_string())),
+        }
+
+        if self.stored {
+            entry = entry.with_parameter("stored".into(), true.into());
+        }
+
+        if let Some(ttl) = self.ttl {
+            entry = entry.with_parameter(
+                "ttl".into(),
+                BareItem::Integer(ttl.as_secs().min(MAX_STRUCTURED_INTEGER as u64) as i64),
+            );
+        }
+
+        entry.serialize()
+    }
+
+    /// Set the `Cache-Status` and/or `X-Cache` headers according to the configuration.
+    ///
+    /// Our `Cache-Status` entry is appended to existing entries, if there are any.
+    pub fn set_headers<RequestBodyT, CacheT, CacheKeyT>(
+        &self,
+        headers: &mut HeaderMap,
+        configuration: &MiddlewareCachingConfiguration<RequestBodyT, CacheT, CacheKeyT>,
+    ) {
+        if let Some(cache_name) = &configuration.cache_status_name {
+            match self
+                .to_cache_status(cache_name)
+                .and_then(|entry| HeaderValue::try_from(entry).map_err(|error| ParseError::from(error.to_string())))
+            {
+                Ok(value) => {
+                    headers.append(CACHE_STATUS, value);
+                }
+
+                Err(error) => tracing::warn!("invalid {}: {}", CACHE_STATUS, error),
+            }
+        }
+
+        if configuration.x_cache {
+            headers.set_value(X_CACHE, if self.is_hit() { HIT_HEADER_VALUE } else { MISS_HEADER_VALUE });
+        }
+    }
+}
+
+//
+// CacheForward
+//
+
+/// Why a request was forwarded to the upstream.
+///
+/// See [IETF RFC 9211 section 2.2](https://datatracker.ietf.org/doc/html/rfc9211#section-2.2).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CacheForward {
+    /// The cache was configured to not handle this request.
+    Bypass,
+
+    /// The cache did not contain a response.
+    Miss,
+
+    /// The cache was able to select a response, but it was stale.
+    Stale,
+}
+
+impl fmt::Display for CacheForward {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(
+            match self {
+                Self::Bypass => "bypass",
+                Self::Miss => "miss",
+                Self::Stale => "stale",
+            },
+            formatter,
+        )
+    }
+}
+
+/// `X-Cache` HTTP header value of "HIT".
+pub const HIT_HEADER_VALUE: HeaderValue = HeaderValue::from_static("HIT");
+
+/// `X-Cache` HTTP header value of "MISS".
+pub const MISS_HEADER_VALUE: HeaderValue = HeaderValue::from_static("MISS");
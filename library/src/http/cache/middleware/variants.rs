@@ -0,0 +1,152 @@
+use super::super::super::{super::std::immutable::*, headers::*};
+
+use http::header::*;
+
+//
+// Variants
+//
+
+/// Content variants that a route can produce.
+///
+/// Used for server-side content negotiation of the `Accept` and `Accept-Language` request
+/// headers. See [negotiate](Self::negotiate).
+#[derive(Clone, Debug, Default)]
+pub struct Variants {
+    /// Media types in order of preference.
+    ///
+    /// Empty means no negotiation of `Accept`.
+    pub media_types: Vec<MediaType>,
+
+    /// Languages in order of preference.
+    ///
+    /// Empty means no negotiation of `Accept-Language`.
+    pub languages: Vec<Language>,
+}
+
+impl Variants {
+    /// Constructor.
+    pub fn new(media_types: Vec<MediaType>, languages: Vec<Language>) -> Self {
+        Self { media_types, languages }
+    }
+
+    /// With media type.
+    pub fn with_media_type(mut self, media_type: MediaType) -> Self {
+        self.media_types.push(media_type);
+        self
+    }
+
+    /// With language.
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.languages.push(language);
+        self
+    }
+
+    /// Negotiate with the request's `Accept` and `Accept-Language` headers.
+    ///
+    /// If a header is missing then the first (most preferred) variant is selected. If there is a
+    /// tie, we will go by the order of variants. Languages are matched with
+    /// [best_language](Preferences::best_language).
+    ///
+    /// [None] means that nothing fits, in which case we should respond with 406 (Not Acceptable).
+    pub fn negotiate(&self, headers: &HeaderMap) -> Option<NegotiatedVariant> {
+        let mut variant = NegotiatedVariant::default();
+
+        if !self.media_types.is_empty() {
+            let accept = headers.accept();
+            variant.media_type = Some(if accept.0.is_empty() {
+                self.media_types[0].clone()
+            } else {
+                accept.best_media_type(&self.media_types)?.clone()
+            });
+        }
+
+        if !self.languages.is_empty() {
+            let accept_language = headers.accept_language();
+            variant.language = Some(if accept_language.0.is_empty() {
+                self.languages[0].clone()
+            } else {
+                accept_language.best_language(&self.languages)?.clone()
+            });
+        }
+
+        Some(variant)
+    }
+
+    /// The request headers by which we vary.
+    pub fn vary(&self) -> Vec<HeaderName> {
+        let mut vary = Vec::with_capacity(2);
+        if !self.media_types.is_empty() {
+            vary.push(ACCEPT);
+        }
+        if !self.languages.is_empty() {
+            vary.push(ACCEPT_LANGUAGE);
+        }
+        vary
+    }
+}
+
+//
+// NegotiatedVariant
+//
+
+/// The result of [Variants::negotiate].
+///
+/// Inserted as a request extension by the caching middleware so that handlers know which variant
+/// to produce. Also applied to the cache key via
+/// [CacheKey::apply_variant](super::super::CacheKey::apply_variant).
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct NegotiatedVariant {
+    /// Media type.
+    ///
+    /// [None] if the route does not negotiate `Accept`.
+    pub media_type: Option<MediaType>,
+
+    /// Language.
+    ///
+    /// [None] if the route does not negotiate `Accept-Language`.
+    pub language: Option<Language>,
+}
+
+//
+// VariantRegistry
+//
+
+/// Registry of [Variants] by route.
+///
+/// Routes are path prefixes, matched at path segment boundaries. The longest matching prefix
+/// wins.
+#[derive(Clone, Debug, Default)]
+pub struct VariantRegistry {
+    /// Routes.
+    pub routes: Vec<(ByteString, Variants)>,
+}
+
+impl VariantRegistry {
+    /// Whether there are no routes.
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// With route.
+    pub fn with_route(mut self, path_prefix: ByteString, variants: Variants) -> Self {
+        self.routes.push((path_prefix, variants));
+        self
+    }
+
+    /// The variants for a path.
+    pub fn variants_for(&self, path: &str) -> Option<&Variants> {
+        self.routes
+            .iter()
+            .filter(|(path_prefix, _)| matches_path_prefix(path, path_prefix))
+            .max_by_key(|(path_prefix, _)| path_prefix.len())
+            .map(|(_, variants)| variants)
+    }
+}
+
+fn matches_path_prefix(path: &str, path_prefix: &str) -> bool {
+    let path_prefix = path_prefix.trim_end_matches('/');
+    match path.strip_prefix(path_prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/'),
+        None => false,
+    }
+}
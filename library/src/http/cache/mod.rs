@@ -1,8 +1,12 @@
+mod admission;
 mod body;
 mod cache;
 mod configuration;
+mod enumerable;
 mod hooks;
 mod key;
+mod partitioned;
+mod pressure;
 mod response;
 mod tiered;
 mod weight;
@@ -11,11 +15,21 @@ mod weight;
 #[cfg(feature = "axum")]
 pub mod axum;
 
+/// Cache invalidation bus.
+#[cfg(feature = "bus")]
+pub mod bus;
+
 /// Cache implementations.
 pub mod implementation;
 
 /// Cache middleware utilities.
 pub mod middleware;
 
+/// Cache snapshot utilities.
+pub mod snapshot;
+
 #[allow(unused_imports)]
-pub use {body::*, cache::*, configuration::*, hooks::*, key::*, response::*, tiered::*, weight::*};
+pub use {
+    admission::*, body::*, cache::*, configuration::*, enumerable::*, hooks::*, key::*, partitioned::*, pressure::*,
+    response::*, tiered::*, weight::*,
+};
@@ -0,0 +1,197 @@
+use super::{
+    super::super::std::{collections::*, immutable::*},
+    cache::*,
+    enumerable::*,
+    key::*,
+    response::*,
+};
+
+use std::sync::*;
+
+/// Hook to get the partition for a cache key.
+pub type PartitionHook<CacheKeyT> = Arc<Box<dyn Fn(&CacheKeyT) -> ByteString + Send + Sync>>;
+
+/// Hook to create a new partition.
+pub type NewPartitionHook<CacheT> = Arc<Box<dyn Fn(&str) -> CacheT + Send + Sync>>;
+
+/// Default for [PartitionedCache::with_max_partitions].
+pub const DEFAULT_MAX_PARTITIONS: usize = 256;
+
+/// Name of the shared partition used when there are too many partitions.
+pub const SHARED_PARTITION: &str = "";
+
+//
+// PartitionedCache
+//
+
+/// Partitioned [Cache].
+///
+/// Each partition is a separate cache, created on demand by a hook. Thus entries in one partition
+/// can never evict entries in another, e.g. to keep one noisy tenant from evicting everyone else's
+/// entries. Partitions can also be invalidated separately.
+///
+/// Partition names often come from the client (e.g. the `Host` header), so the number of
+/// partitions is limited (see [with_max_partitions](Self::with_max_partitions)). Beyond the limit
+/// new names are mapped to the [SHARED_PARTITION].
+///
+/// For Moka caches with per-partition quotas see
+/// [new_moka](PartitionedCache::new_moka).
+pub struct PartitionedCache<CacheT, CacheKeyT = CommonCacheKey> {
+    partitions: Arc<FastConcurrentHashMap<ByteString, CacheT>>,
+    partition: PartitionHook<CacheKeyT>,
+    new_partition: NewPartitionHook<CacheT>,
+    max_partitions: usize,
+}
+
+impl<CacheT, CacheKeyT> PartitionedCache<CacheT, CacheKeyT>
+where
+    CacheT: Clone,
+{
+    /// Constructor.
+    pub fn new(
+        partition: impl Fn(&CacheKeyT) -> ByteString + 'static + Send + Sync,
+        new_partition: impl Fn(&str) -> CacheT + 'static + Send + Sync,
+    ) -> Self {
+        Self {
+            partitions: Default::default(),
+            partition: Arc::new(Box::new(partition)),
+            new_partition: Arc::new(Box::new(new_partition)),
+            max_partitions: DEFAULT_MAX_PARTITIONS,
+        }
+    }
+
+    /// With max partitions, not including the [SHARED_PARTITION].
+    ///
+    /// The default is [DEFAULT_MAX_PARTITIONS].
+    pub fn with_max_partitions(mut self, max_partitions: usize) -> Self {
+        self.max_partitions = max_partitions;
+        self
+    }
+
+    /// Partition name for a cache key.
+    ///
+    /// Returns the [SHARED_PARTITION] if the key's partition doesn't exist and we are at the max.
+    pub fn partition_for(&self, key: &CacheKeyT) -> ByteString {
+        let name = (self.partition)(key);
+
+        let partitions = self.partitions.pin();
+        if partitions.contains_key(&name) {
+            return name;
+        }
+
+        let count = partitions.len() - if partitions.contains_key(SHARED_PARTITION) { 1 } else { 0 };
+        if count >= self.max_partitions {
+            tracing::debug!("too many partitions, using shared partition for: {}", name);
+            SHARED_PARTITION.into()
+        } else {
+            name
+        }
+    }
+
+    /// Partition, if it exists.
+    pub fn partition(&self, name: &str) -> Option<CacheT> {
+        self.partitions.pin().get(name).cloned()
+    }
+
+    /// Partition, creating it if it doesn't exist.
+    pub fn partition_or_new(&self, name: &str) -> CacheT {
+        self.partitions.pin().get_or_insert_with(name.into(), || (self.new_partition)(name)).clone()
+    }
+
+    /// Names of existing partitions.
+    pub fn partition_names(&self) -> Vec<ByteString> {
+        self.partitions.pin().keys().cloned().collect()
+    }
+
+    fn all_partitions(&self) -> Vec<CacheT> {
+        self.partitions.pin().values().cloned().collect()
+    }
+}
+
+impl<CacheT> PartitionedCache<CacheT, CommonCacheKey>
+where
+    CacheT: Clone,
+{
+    /// Constructor that partitions by [CommonCacheKey::host].
+    ///
+    /// Note that the host is not set by default, so you would need to set it via the `cache_key`
+    /// hook or an [EffectiveOrigin](crate::http::EffectiveOrigin) request extension. Keys without
+    /// a host go to the "" partition.
+    pub fn new_by_host(new_partition: impl Fn(&str) -> CacheT + 'static + Send + Sync) -> Self {
+        Self::new(|key: &CommonCacheKey| key.host.clone().unwrap_or_default(), new_partition)
+    }
+}
+
+impl<CacheT, CacheKeyT> PartitionedCache<CacheT, CacheKeyT>
+where
+    CacheT: Cache<CacheKeyT>,
+    CacheKeyT: CacheKey,
+{
+    /// Invalidate all entries in a partition.
+    pub async fn invalidate_partition(&self, name: &str) {
+        if let Some(partition) = self.partition(name) {
+            partition.invalidate_all().await;
+        }
+    }
+}
+
+impl<CacheT, CacheKeyT> Clone for PartitionedCache<CacheT, CacheKeyT> {
+    fn clone(&self) -> Self {
+        // #[derive(Clone)] would require CacheKeyT: Clone
+        Self {
+            partitions: self.partitions.clone(),
+            partition: self.partition.clone(),
+            new_partition: self.new_partition.clone(),
+            max_partitions: self.max_partitions,
+        }
+    }
+}
+
+impl<CacheT, CacheKeyT> Cache<CacheKeyT> for PartitionedCache<CacheT, CacheKeyT>
+where
+    CacheT: Cache<CacheKeyT>,
+    CacheKeyT: CacheKey,
+{
+    async fn get(&self, key: &CacheKeyT) -> Option<CachedResponseRef> {
+        // Don't create a partition just for a miss
+        let partition = self.partition(&self.partition_for(key))?;
+        partition.get(key).await
+    }
+
+    async fn put(&self, key: CacheKeyT, cached_response: CachedResponseRef) {
+        let partition = self.partition_or_new(&self.partition_for(&key));
+        partition.put(key, cached_response).await
+    }
+
+    async fn invalidate(&self, key: &CacheKeyT) {
+        if let Some(partition) = self.partition(&self.partition_for(key)) {
+            partition.invalidate(key).await;
+        }
+    }
+
+    async fn invalidate_local(&self, key: &CacheKeyT) {
+        if let Some(partition) = self.partition(&self.partition_for(key)) {
+            partition.invalidate_local(key).await;
+        }
+    }
+
+    async fn invalidate_all(&self) {
+        for partition in self.all_partitions() {
+            partition.invalidate_all().await;
+        }
+    }
+}
+
+impl<CacheT, CacheKeyT> EnumerableCache<CacheKeyT> for PartitionedCache<CacheT, CacheKeyT>
+where
+    CacheT: EnumerableCache<CacheKeyT>,
+    CacheKeyT: CacheKey,
+{
+    async fn entries(&self) -> Vec<(CacheKeyT, CachedResponseRef)> {
+        let mut entries = Vec::default();
+        for partition in self.all_partitions() {
+            entries.extend(partition.entries().await);
+        }
+        entries
+    }
+}
@@ -0,0 +1,58 @@
+use std::{fmt, sync::*};
+
+//
+// MemoryPressure
+//
+
+/// Memory pressure indicator.
+///
+/// When under memory pressure we drop secondary representations (encodings) of cached bodies,
+/// keeping only one according to a [RepresentationRetention], in the hope of delaying the
+/// eviction of entire entries.
+///
+/// Implementations should be cheap, because they are called whenever we store or serve a cached
+/// response.
+pub trait MemoryPressure
+where
+    Self: Send + Sync,
+{
+    /// Whether we are under memory pressure.
+    fn is_under_pressure(&self) -> bool;
+}
+
+impl<FunctionT> MemoryPressure for FunctionT
+where
+    FunctionT: Fn() -> bool + Send + Sync,
+{
+    fn is_under_pressure(&self) -> bool {
+        self()
+    }
+}
+
+impl fmt::Debug for dyn MemoryPressure {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.debug_struct("MemoryPressure").field("is_under_pressure", &self.is_under_pressure()).finish()
+    }
+}
+
+/// Common reference type for [MemoryPressure].
+pub type MemoryPressureRef = Arc<dyn MemoryPressure>;
+
+//
+// RepresentationRetention
+//
+
+/// Which representation of a cached body to keep under [MemoryPressure].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum RepresentationRetention {
+    /// Keep the smallest representation.
+    ///
+    /// Ties are broken by access count.
+    #[default]
+    Smallest,
+
+    /// Keep the most requested representation.
+    ///
+    /// Ties are broken by size (smallest wins).
+    MostRequested,
+}
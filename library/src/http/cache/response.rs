@@ -2,7 +2,7 @@ use super::{
     super::{
         super::{
             std::{error::*, immutable::*},
-            transcoding::*,
+            transcoding::{transcode::*, *},
         },
         body::*,
         headers::*,
@@ -40,14 +40,20 @@ pub struct CachedResponse {
     /// Response body.
     pub body: CachedBody,
 
+    /// Response trailers.
+    pub trailers: Vec<HeaderMap>,
+
     /// Optional duration.
     pub duration: Option<Duration>,
+
+    /// When the entry was created.
+    pub created: SystemTime,
 }
 
 impl CachedResponse {
     /// Constructor.
     ///
-    /// Reads the response body and stores it as [Bytes].
+    /// Reads the response body and stores it as [Bytes]. Trailers are stored, too.
     ///
     /// If `known_body_size` is not [None] then that's the size we expect. Otherwise
     /// we'll try to read to `max_body_size` and will expect at least `min_body_size`.
@@ -62,10 +68,14 @@ impl CachedResponse {
     /// [Identity](Encoding::Identity).
     ///
     /// If an [Identity](Encoding::Identity) is created during this reencoding then it will also be
-    /// stored if `keep_identity_encoding` is true.
+    /// stored if `keep_identity_encoding` is true. However, if we are under memory pressure then
+    /// only one representation will be stored.
     ///
     /// If the response doesn't already have a `Last-Modified` header, we will set it to the
     /// current time.
+    ///
+    /// If the response has a `Use-As-Dictionary` header and `dictionaries` is not [None] then the
+    /// body is registered as a dictionary.
     pub async fn new_for<BodyT>(
         uri: &Uri,
         response: Response<BodyT>,
@@ -81,7 +91,7 @@ impl CachedResponse {
     {
         let (mut parts, body) = response.into_parts();
 
-        let bytes = match body
+        let (bytes, trailers) = match body
             .read_into_bytes_or_pieces(
                 declared_body_size,
                 caching_configuration.min_body_size,
@@ -89,7 +99,7 @@ impl CachedResponse {
             )
             .await
         {
-            Ok((bytes, _trailers)) => bytes,
+            Ok((bytes, trailers)) => (bytes, trailers),
             Err(error) => {
                 return Err(ErrorWithResponsePieces::new_from_body(error, parts));
             }
@@ -105,15 +115,29 @@ impl CachedResponse {
             }
         }
 
-        let body = CachedBody::new_with(
-            bytes,
-            parts.headers.content_encoding().into(),
-            preferred_encoding,
-            encoding_configuration,
-        )
-        .await
-        // This is not *exactly* a ReadBodyError, but rather an encoding error for the read body
-        .map_err(|error| ErrorWithResponsePieces::from(ReadBodyError::from(error)))?;
+        let encoding: Encoding = parts.headers.content_encoding().into();
+
+        // Register as a dictionary
+        if parts.headers.has_use_as_dictionary()
+            && let Some(dictionaries) = &encoding_configuration.dictionaries
+        {
+            let identity_bytes = bytes
+                .decode_with_dictionaries(&encoding, Some(dictionaries))
+                .await
+                .map_err(|error| ErrorWithResponsePieces::from(ReadBodyError::from(error)))?;
+            dictionaries.register(Dictionary::new(identity_bytes));
+        }
+
+        let mut body = CachedBody::new_with(bytes, encoding, preferred_encoding, encoding_configuration)
+            .await
+            // This is not *exactly* a ReadBodyError, but rather an encoding error for the read body
+            .map_err(|error| ErrorWithResponsePieces::from(ReadBodyError::from(error)))?;
+
+        if encoding_configuration.is_under_memory_pressure()
+            && let Some(retained_body) = body.retain_one(encoding_configuration.retention)
+        {
+            body = retained_body;
+        }
 
         // Extract `XX-Cache-Duration` or call hook
         let duration = match parts.headers.xx_cache_duration() {
@@ -133,14 +157,17 @@ impl CachedResponse {
             parts.headers.set_into_header_value(LAST_MODIFIED, now());
         }
 
+        // Note that we are keeping the upstream `Age` header (if there is one) in the cache
+        // (it will be added to our own age in `to_response`)
+
         parts.headers.remove(XX_CACHE);
         parts.headers.remove(XX_CACHE_DURATION);
         parts.headers.remove(CONTENT_ENCODING);
         parts.headers.remove(CONTENT_LENGTH);
         parts.headers.remove(CONTENT_DIGEST);
 
-        // Note that we are keeping the `XX-Encode` header in the cache
-        // (but will remove it in `to_response`)
+        // Note that we are keeping the `XX-Encode` and `XX-Cache-Tags` headers in the cache
+        // (but will remove them in `to_response`)
 
         if skip_encoding {
             parts.headers.set_bool_value(XX_ENCODE, true);
@@ -150,12 +177,18 @@ impl CachedResponse {
         // https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Headers/Accept-Ranges
         parts.headers.remove(ACCEPT_RANGES);
 
-        Ok(Self { parts, body, duration })
+        Ok(Self { parts, body, trailers, duration, created: SystemTime::now() })
     }
 
     /// Clone with new body.
     pub fn clone_with_body(&self, body: CachedBody) -> Self {
-        Self { parts: self.parts.clone(), body, duration: self.duration.clone() }
+        Self {
+            parts: self.parts.clone(),
+            body,
+            trailers: self.trailers.clone(),
+            duration: self.duration.clone(),
+            created: self.created,
+        }
     }
 
     /// Headers.
@@ -163,6 +196,43 @@ impl CachedResponse {
         &self.parts.headers
     }
 
+    /// Whether we have a tag.
+    ///
+    /// Tags are set via the `XX-Cache-Tags` header.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.headers().xx_cache_tags().contains(&tag)
+    }
+
+    /// How long we have been in the cache.
+    pub fn resident(&self) -> Duration {
+        self.created.elapsed().unwrap_or_default()
+    }
+
+    /// Age.
+    ///
+    /// This is how long we have been in the cache plus the upstream `Age`, if there was one.
+    pub fn age(&self) -> Duration {
+        let resident = self.resident();
+        match self.headers().age() {
+            Some(age) => resident + age,
+            None => resident,
+        }
+    }
+
+    /// Remaining time to live.
+    ///
+    /// [None] if we don't have a duration.
+    pub fn ttl(&self) -> Option<Duration> {
+        self.duration.map(|duration| duration.saturating_sub(self.resident()))
+    }
+
+    /// Whether we have been in the cache longer than our duration.
+    ///
+    /// Cache implementations are expected to evict such entries, but not all of them might.
+    pub fn is_stale(&self) -> bool {
+        self.duration.is_some_and(|duration| self.resident() >= duration)
+    }
+
     /// Create a [Response].
     ///
     /// If we don't have the specified encoding then we will reencode from another encoding,
@@ -174,8 +244,16 @@ impl CachedResponse {
     /// If the stored `XX-Encode` header is "false" then will ignore the specified encoding and
     /// return an [Identity](Encoding::Identity) response.
     ///
+    /// The `Age` header will be set according to [age](Self::age).
+    ///
+    /// Note that the stored trailers are *not* added to the body, because we can't do that for
+    /// any [Body]. See [TranscodingBody::with_trailers](crate::http::transcoding::TranscodingBody::with_trailers).
+    ///
     /// Returns a modified clone if reencoding caused a new encoding to be stored. Note that
     /// cloning should be cheap due to our use of [Bytes] in the body.
+    ///
+    /// If we are under memory pressure then the modified clone will retain only one representation
+    /// (see [CachedBody::retain_one]), and will be returned even if there was no reencoding.
     pub async fn to_response<BodyT>(
         &self,
         mut encoding: &Encoding,
@@ -189,18 +267,31 @@ impl CachedResponse {
             encoding = &Encoding::Identity;
         }
 
-        let (bytes, modified) = self.body.get(encoding, configuration).await?;
+        let (bytes, mut modified) = self.body.get(encoding, configuration).await?;
+
+        if configuration.is_under_memory_pressure()
+            && let Some(retained_body) = modified.as_ref().unwrap_or(&self.body).retain_one(configuration.retention)
+        {
+            modified = Some(retained_body);
+        }
 
         let mut parts = self.parts.clone();
 
         parts.headers.remove(XX_ENCODE);
+        parts.headers.remove(XX_CACHE_TAGS);
 
         if *encoding != Encoding::Identity {
             // No need to specify Identity as it's the default
             parts.headers.set_into_header_value(CONTENT_ENCODING, encoding.clone());
         }
 
+        if encoding.dictionary().is_some() {
+            // Required by downstream caches
+            parts.headers.add_vary(&[ACCEPT_ENCODING, AVAILABLE_DICTIONARY]);
+        }
+
         parts.headers.set_value(CONTENT_LENGTH, bytes.len());
+        parts.headers.set_value(AGE, self.age().as_secs());
 
         Ok((Response::from_parts(parts, bytes.into()), modified.map(|body| self.clone_with_body(body))))
     }
@@ -220,6 +311,12 @@ impl CacheWeight for CachedResponse {
         }
         size += parts.extensions.len() * EXTENSION_ENTRY_SIZE;
 
+        for trailers in &self.trailers {
+            for (name, value) in trailers {
+                size += HEADER_MAP_ENTRY_SIZE + name.as_str().len() + value.len()
+            }
+        }
+
         size += self.body.cache_weight();
 
         size
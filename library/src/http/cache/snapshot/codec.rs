@@ -0,0 +1,211 @@
+use super::super::super::super::std::immutable::*;
+
+use std::{
+    io::{self, Read, Write},
+    time::*,
+};
+
+//
+// SnapshotWriter
+//
+
+/// Writer for the cache snapshot format.
+///
+/// All integers are big-endian. Byte sequences and strings are prefixed with their length.
+pub struct SnapshotWriter<WriteT> {
+    writer: WriteT,
+}
+
+impl<WriteT> SnapshotWriter<WriteT>
+where
+    WriteT: Write,
+{
+    /// Constructor.
+    pub fn new(writer: WriteT) -> Self {
+        Self { writer }
+    }
+
+    /// Back to the inner writer.
+    pub fn into_inner(self) -> WriteT {
+        self.writer
+    }
+
+    /// Write a [u8].
+    pub fn write_u8(&mut self, value: u8) -> io::Result<()> {
+        self.writer.write_all(&[value])
+    }
+
+    /// Write a [u16].
+    pub fn write_u16(&mut self, value: u16) -> io::Result<()> {
+        self.writer.write_all(&value.to_be_bytes())
+    }
+
+    /// Write a [u32].
+    pub fn write_u32(&mut self, value: u32) -> io::Result<()> {
+        self.writer.write_all(&value.to_be_bytes())
+    }
+
+    /// Write a [u64].
+    pub fn write_u64(&mut self, value: u64) -> io::Result<()> {
+        self.writer.write_all(&value.to_be_bytes())
+    }
+
+    /// Write a [bool].
+    pub fn write_bool(&mut self, value: bool) -> io::Result<()> {
+        self.write_u8(if value { 1 } else { 0 })
+    }
+
+    /// Write a length.
+    pub fn write_length(&mut self, length: usize) -> io::Result<()> {
+        self.write_u64(length as u64)
+    }
+
+    /// Write bytes.
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.write_length(bytes.len())?;
+        self.writer.write_all(bytes)
+    }
+
+    /// Write a string.
+    pub fn write_str(&mut self, string: &str) -> io::Result<()> {
+        self.write_bytes(string.as_bytes())
+    }
+
+    /// Write a [Duration] (as milliseconds).
+    pub fn write_duration(&mut self, duration: Duration) -> io::Result<()> {
+        self.write_u64(duration.as_millis().try_into().unwrap_or(u64::MAX))
+    }
+
+    /// Write a [SystemTime] (as milliseconds since the Unix epoch).
+    pub fn write_system_time(&mut self, system_time: SystemTime) -> io::Result<()> {
+        self.write_duration(system_time.duration_since(UNIX_EPOCH).unwrap_or_default())
+    }
+
+    /// Write an optional value.
+    pub fn write_option<ValueT, WriteValueT>(&mut self, value: Option<ValueT>, write: WriteValueT) -> io::Result<()>
+    where
+        WriteValueT: FnOnce(&mut Self, ValueT) -> io::Result<()>,
+    {
+        match value {
+            Some(value) => {
+                self.write_bool(true)?;
+                write(self, value)
+            }
+
+            None => self.write_bool(false),
+        }
+    }
+}
+
+//
+// SnapshotReader
+//
+
+/// Reader for the cache snapshot format.
+///
+/// See [SnapshotWriter].
+pub struct SnapshotReader<ReadT> {
+    reader: ReadT,
+}
+
+impl<ReadT> SnapshotReader<ReadT>
+where
+    ReadT: Read,
+{
+    /// Constructor.
+    pub fn new(reader: ReadT) -> Self {
+        Self { reader }
+    }
+
+    /// Back to the inner reader.
+    pub fn into_inner(self) -> ReadT {
+        self.reader
+    }
+
+    /// Read a [u8].
+    pub fn read_u8(&mut self) -> io::Result<u8> {
+        let mut buffer = [0; 1];
+        self.reader.read_exact(&mut buffer)?;
+        Ok(buffer[0])
+    }
+
+    /// Read a [u16].
+    pub fn read_u16(&mut self) -> io::Result<u16> {
+        let mut buffer = [0; 2];
+        self.reader.read_exact(&mut buffer)?;
+        Ok(u16::from_be_bytes(buffer))
+    }
+
+    /// Read a [u32].
+    pub fn read_u32(&mut self) -> io::Result<u32> {
+        let mut buffer = [0; 4];
+        self.reader.read_exact(&mut buffer)?;
+        Ok(u32::from_be_bytes(buffer))
+    }
+
+    /// Read a [u64].
+    pub fn read_u64(&mut self) -> io::Result<u64> {
+        let mut buffer = [0; 8];
+        self.reader.read_exact(&mut buffer)?;
+        Ok(u64::from_be_bytes(buffer))
+    }
+
+    /// Read a [bool].
+    pub fn read_bool(&mut self) -> io::Result<bool> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            value => Err(invalid_data(format!("not a bool: {}", value))),
+        }
+    }
+
+    /// Read a length.
+    pub fn read_length(&mut self) -> io::Result<usize> {
+        self.read_u64()?.try_into().map_err(|_| invalid_data("length too big"))
+    }
+
+    /// Read bytes.
+    pub fn read_bytes(&mut self) -> io::Result<Bytes> {
+        let length = self.read_length()?;
+
+        // Note: we are not pre-allocating the length, because it might be corrupt
+        let mut bytes = Vec::default();
+        (&mut self.reader).take(length as u64).read_to_end(&mut bytes)?;
+        if bytes.len() != length {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        Ok(bytes.into())
+    }
+
+    /// Read a string.
+    pub fn read_string(&mut self) -> io::Result<ByteString> {
+        ByteString::try_from(self.read_bytes()?).map_err(invalid_data)
+    }
+
+    /// Read a [Duration] (as milliseconds).
+    pub fn read_duration(&mut self) -> io::Result<Duration> {
+        Ok(Duration::from_millis(self.read_u64()?))
+    }
+
+    /// Read a [SystemTime] (as milliseconds since the Unix epoch).
+    pub fn read_system_time(&mut self) -> io::Result<SystemTime> {
+        Ok(UNIX_EPOCH + self.read_duration()?)
+    }
+
+    /// Read an optional value.
+    pub fn read_option<ValueT, ReadValueT>(&mut self, read: ReadValueT) -> io::Result<Option<ValueT>>
+    where
+        ReadValueT: FnOnce(&mut Self) -> io::Result<ValueT>,
+    {
+        if self.read_bool()? { read(self).map(Some) } else { Ok(None) }
+    }
+}
+
+/// [io::Error] with [InvalidData](io::ErrorKind::InvalidData).
+pub fn invalid_data<ErrorT>(error: ErrorT) -> io::Error
+where
+    ErrorT: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
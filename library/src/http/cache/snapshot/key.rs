@@ -0,0 +1,133 @@
+use super::{
+    super::{
+        super::{super::std::immutable::*, headers::*},
+        key::*,
+    },
+    codec::*,
+};
+
+use {
+    http::{uri::*, *},
+    std::{
+        collections::*,
+        io::{self, Read, Write},
+    },
+};
+
+//
+// SnapshotCacheKey
+//
+
+/// [CacheKey] that can be written to and read from a cache snapshot.
+pub trait SnapshotCacheKey
+where
+    Self: CacheKey,
+{
+    /// Write to a snapshot.
+    fn write_snapshot<WriteT>(&self, writer: &mut SnapshotWriter<WriteT>) -> io::Result<()>
+    where
+        WriteT: Write;
+
+    /// Read from a snapshot.
+    fn read_snapshot<ReadT>(reader: &mut SnapshotReader<ReadT>) -> io::Result<Self>
+    where
+        ReadT: Read;
+}
+
+impl SnapshotCacheKey for CommonCacheKey {
+    fn write_snapshot<WriteT>(&self, writer: &mut SnapshotWriter<WriteT>) -> io::Result<()>
+    where
+        WriteT: Write,
+    {
+        writer.write_str(self.method.as_str())?;
+
+        writer.write_option(self.path.as_ref(), |writer, path| writer.write_str(path))?;
+
+        writer.write_option(self.query.as_ref(), |writer, query| {
+            writer.write_length(query.len())?;
+            for (key, values) in query {
+                writer.write_str(key)?;
+                writer.write_length(values.len())?;
+                for value in values {
+                    writer.write_str(value)?;
+                }
+            }
+            Ok(())
+        })?;
+
+        writer.write_option(self.scheme.as_ref(), |writer, scheme| writer.write_str(scheme.as_str()))?;
+        writer.write_option(self.host.as_ref(), |writer, host| writer.write_str(host))?;
+        writer.write_option(self.port, |writer, port| writer.write_u16(port))?;
+
+        writer
+            .write_option(self.media_type.as_ref(), |writer, media_type| writer.write_str(&media_type.to_string()))?;
+
+        writer.write_option(self.languages.as_ref(), |writer, languages| {
+            writer.write_length(languages.len())?;
+            for language in languages {
+                writer.write_str(&language.to_string())?;
+            }
+            Ok(())
+        })?;
+
+        writer.write_option(self.extensions.as_ref(), |writer, extensions| {
+            writer.write_length(extensions.len())?;
+            for (key, value) in extensions {
+                writer.write_bytes(key)?;
+                writer.write_bytes(value)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn read_snapshot<ReadT>(reader: &mut SnapshotReader<ReadT>) -> io::Result<Self>
+    where
+        ReadT: Read,
+    {
+        let method = Method::from_bytes(&reader.read_bytes()?).map_err(invalid_data)?;
+
+        let path = reader.read_option(|reader| reader.read_string())?;
+
+        let query = reader.read_option(|reader| {
+            let mut query = BTreeMap::default();
+            for _ in 0..reader.read_length()? {
+                let key = reader.read_string()?;
+                let mut values = BTreeSet::default();
+                for _ in 0..reader.read_length()? {
+                    values.insert(reader.read_string()?);
+                }
+                query.insert(key, values);
+            }
+            Ok(query)
+        })?;
+
+        let scheme =
+            reader.read_option(|reader| Scheme::try_from(reader.read_bytes()?.as_ref()).map_err(invalid_data))?;
+        let host = reader.read_option(|reader| reader.read_string())?;
+        let port = reader.read_option(|reader| reader.read_u16())?;
+
+        let media_type = reader.read_option(|reader| {
+            let media_type: &str = &reader.read_string()?;
+            media_type.parse::<MediaType>().map_err(|error| invalid_data(error.to_string()))
+        })?;
+
+        let languages = reader.read_option(|reader| {
+            let mut languages = BTreeSet::default();
+            for _ in 0..reader.read_length()? {
+                let language: &str = &reader.read_string()?;
+                languages.insert(Language::from(language));
+            }
+            Ok(languages)
+        })?;
+
+        let extensions = reader.read_option(|reader| {
+            let mut extensions = BTreeMap::<Bytes, Bytes>::default();
+            for _ in 0..reader.read_length()? {
+                extensions.insert(reader.read_bytes()?, reader.read_bytes()?);
+            }
+            Ok(extensions)
+        })?;
+
+        Ok(Self::new(method, path, query, scheme, host, port, media_type, languages, extensions))
+    }
+}
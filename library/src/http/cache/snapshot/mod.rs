@@ -0,0 +1,6 @@
+mod codec;
+mod key;
+mod snapshot;
+
+#[allow(unused_imports)]
+pub use {codec::*, key::*, snapshot::*};
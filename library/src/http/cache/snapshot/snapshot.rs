@@ -0,0 +1,328 @@
+use super::{
+    super::{
+        super::{super::transcoding::*, headers::*},
+        body::*,
+        cache::*,
+        enumerable::*,
+        key::*,
+        response::*,
+    },
+    codec::*,
+    key::*,
+};
+
+use {
+    http::{header::*, response::*, *},
+    std::{
+        fs::*,
+        io::{self, BufReader, BufWriter, Read, Write},
+        path::*,
+        time::*,
+    },
+};
+
+/// Cache snapshot format version.
+///
+/// Snapshots with a different version cannot be read.
+pub const CACHE_SNAPSHOT_VERSION: u16 = 3;
+
+const CACHE_SNAPSHOT_SIGNATURE: &[u8] = b"KUTILCACHESNAPSHOT";
+
+//
+// CacheSnapshot
+//
+
+/// Cache snapshot.
+///
+/// Can be used to keep a cache "warm" between restarts: export the cache to a file on shutdown and
+/// import it on startup.
+///
+/// The snapshot contains the cache keys, the cached responses (including all body
+/// representations and trailers), and their remaining durations. Response extensions are *not* included.
+///
+/// When the snapshot is read, entries will be skipped if their remaining duration has passed since
+/// the snapshot was written. The time that has passed is also added to the `Age` header of the
+/// remaining entries.
+#[derive(Clone, Debug)]
+pub struct CacheSnapshot<CacheKeyT = CommonCacheKey> {
+    /// Entries.
+    pub entries: Vec<(CacheKeyT, CachedResponseRef)>,
+}
+
+impl<CacheKeyT> CacheSnapshot<CacheKeyT>
+where
+    CacheKeyT: SnapshotCacheKey,
+{
+    /// Constructor.
+    pub fn new(entries: Vec<(CacheKeyT, CachedResponseRef)>) -> Self {
+        Self { entries }
+    }
+
+    /// Export a snapshot from a cache.
+    ///
+    /// Stale entries are skipped.
+    pub async fn export<CacheT>(cache: &CacheT) -> Self
+    where
+        CacheT: EnumerableCache<CacheKeyT>,
+    {
+        let mut entries = cache.entries().await;
+        entries.retain(|(_key, cached_response)| !cached_response.is_stale());
+        Self::new(entries)
+    }
+
+    /// Import the snapshot into a cache.
+    ///
+    /// Returns the number of imported entries.
+    pub async fn import<CacheT>(self, cache: &CacheT) -> usize
+    where
+        CacheT: Cache<CacheKeyT>,
+    {
+        let count = self.entries.len();
+        for (key, cached_response) in self.entries {
+            cache.put(key, cached_response).await;
+        }
+        count
+    }
+
+    /// Write the snapshot.
+    pub fn write<WriteT>(&self, writer: WriteT) -> io::Result<()>
+    where
+        WriteT: Write,
+    {
+        let mut writer = SnapshotWriter::new(writer);
+
+        writer.write_bytes(CACHE_SNAPSHOT_SIGNATURE)?;
+        writer.write_u16(CACHE_SNAPSHOT_VERSION)?;
+        writer.write_system_time(SystemTime::now())?;
+
+        writer.write_length(self.entries.len())?;
+        for (key, cached_response) in &self.entries {
+            key.write_snapshot(&mut writer)?;
+            write_cached_response(&mut writer, cached_response)?;
+        }
+
+        writer.into_inner().flush()
+    }
+
+    /// Read a snapshot.
+    pub fn read<ReadT>(reader: ReadT) -> io::Result<Self>
+    where
+        ReadT: Read,
+    {
+        let mut reader = SnapshotReader::new(reader);
+
+        if reader.read_bytes()? != CACHE_SNAPSHOT_SIGNATURE {
+            return Err(invalid_data("not a cache snapshot"));
+        }
+
+        let version = reader.read_u16()?;
+        if version != CACHE_SNAPSHOT_VERSION {
+            return Err(invalid_data(format!("unsupported cache snapshot version: {}", version)));
+        }
+
+        let elapsed = reader.read_system_time()?.elapsed().unwrap_or_default();
+
+        let count = reader.read_length()?;
+        let mut entries = Vec::default();
+        for _ in 0..count {
+            let key = CacheKeyT::read_snapshot(&mut reader)?;
+            if let Some(cached_response) = read_cached_response(&mut reader, elapsed)? {
+                entries.push((key, cached_response.into()));
+            } else {
+                tracing::debug!("skipping expired: {}", key);
+            }
+        }
+
+        Ok(Self::new(entries))
+    }
+
+    /// Write the snapshot to a file.
+    ///
+    /// We first write to a temporary file in the same directory and then rename it, so that an
+    /// existing snapshot file will never be left incomplete.
+    pub fn write_to_file<PathT>(&self, path: PathT) -> io::Result<()>
+    where
+        PathT: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        let mut temporary_path = path.as_os_str().to_owned();
+        temporary_path.push(".tmp");
+
+        self.write(BufWriter::new(File::create(&temporary_path)?))?;
+        rename(temporary_path, path)
+    }
+
+    /// Read a snapshot from a file.
+    pub fn read_from_file<PathT>(path: PathT) -> io::Result<Self>
+    where
+        PathT: AsRef<Path>,
+    {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+}
+
+// Write a cached response.
+//
+// The `Age` header will be the current age and the duration will be the remaining TTL.
+fn write_cached_response<WriteT>(
+    writer: &mut SnapshotWriter<WriteT>,
+    cached_response: &CachedResponse,
+) -> io::Result<()>
+where
+    WriteT: Write,
+{
+    let parts = &cached_response.parts;
+
+    writer.write_u16(parts.status.as_u16())?;
+    writer.write_u8(version_code(parts.version))?;
+
+    // We will write our own `Age`
+    let headers: Vec<_> = parts.headers.iter().filter(|(name, _value)| **name != AGE).collect();
+    writer.write_length(headers.len())?;
+    for (name, value) in headers {
+        writer.write_str(name.as_str())?;
+        writer.write_bytes(value.as_bytes())?;
+    }
+    writer.write_u64(cached_response.age().as_secs())?;
+
+    writer.write_length(cached_response.body.representations.len())?;
+    for (encoding, bytes) in &cached_response.body.representations {
+        write_encoding(writer, encoding)?;
+        writer.write_bytes(bytes)?;
+    }
+
+    writer.write_length(cached_response.trailers.len())?;
+    for trailers in &cached_response.trailers {
+        writer.write_length(trailers.len())?;
+        for (name, value) in trailers {
+            writer.write_str(name.as_str())?;
+            writer.write_bytes(value.as_bytes())?;
+        }
+    }
+
+    writer.write_option(cached_response.ttl(), |writer, ttl| writer.write_duration(ttl))
+}
+
+// Read a cached response.
+//
+// Returns [None] if the remaining TTL is less than `elapsed`.
+fn read_cached_response<ReadT>(
+    reader: &mut SnapshotReader<ReadT>,
+    elapsed: Duration,
+) -> io::Result<Option<CachedResponse>>
+where
+    ReadT: Read,
+{
+    let (mut parts, _) = Response::new(()).into_parts();
+
+    parts.status = StatusCode::from_u16(reader.read_u16()?).map_err(invalid_data)?;
+    parts.version = version_from_code(reader.read_u8()?)?;
+
+    for _ in 0..reader.read_length()? {
+        let name = HeaderName::from_bytes(&reader.read_bytes()?).map_err(invalid_data)?;
+        let value = HeaderValue::from_maybe_shared(reader.read_bytes()?).map_err(invalid_data)?;
+        parts.headers.append(name, value);
+    }
+    let age = reader.read_u64()?;
+
+    let mut body = CachedBody::default();
+    for _ in 0..reader.read_length()? {
+        let encoding = read_encoding(reader)?;
+        body.representations.insert(encoding, reader.read_bytes()?);
+    }
+
+    let mut trailers = Vec::default();
+    for _ in 0..reader.read_length()? {
+        let mut trailer = HeaderMap::default();
+        for _ in 0..reader.read_length()? {
+            let name = HeaderName::from_bytes(&reader.read_bytes()?).map_err(invalid_data)?;
+            let value = HeaderValue::from_maybe_shared(reader.read_bytes()?).map_err(invalid_data)?;
+            trailer.append(name, value);
+        }
+        trailers.push(trailer);
+    }
+
+    let duration = match reader.read_option(|reader| reader.read_duration())? {
+        Some(ttl) => match ttl.checked_sub(elapsed) {
+            Some(duration) if !duration.is_zero() => Some(duration),
+            _ => return Ok(None),
+        },
+
+        None => None,
+    };
+
+    // The time that has passed since the snapshot was written is added to the age
+    parts.headers.set_value(AGE, age + elapsed.as_secs());
+
+    Ok(Some(CachedResponse { parts, body, trailers, duration, created: SystemTime::now() }))
+}
+
+fn version_code(version: Version) -> u8 {
+    match version {
+        Version::HTTP_09 => 0,
+        Version::HTTP_10 => 1,
+        Version::HTTP_2 => 3,
+        Version::HTTP_3 => 4,
+        _ => 2,
+    }
+}
+
+fn version_from_code(code: u8) -> io::Result<Version> {
+    match code {
+        0 => Ok(Version::HTTP_09),
+        1 => Ok(Version::HTTP_10),
+        2 => Ok(Version::HTTP_11),
+        3 => Ok(Version::HTTP_2),
+        4 => Ok(Version::HTTP_3),
+        _ => Err(invalid_data(format!("unsupported HTTP version: {}", code))),
+    }
+}
+
+// Encodings with a dictionary are followed by the dictionary hash
+fn write_encoding<WriteT>(writer: &mut SnapshotWriter<WriteT>, encoding: &Encoding) -> io::Result<()>
+where
+    WriteT: Write,
+{
+    match encoding {
+        Encoding::Identity => writer.write_u8(0),
+        Encoding::Brotli => writer.write_u8(1),
+        Encoding::Deflate => writer.write_u8(2),
+        Encoding::GZip => writer.write_u8(3),
+        Encoding::Zstandard => writer.write_u8(4),
+
+        Encoding::DictionaryBrotli(hash) => {
+            writer.write_u8(5)?;
+            writer.write_bytes(&hash.0)
+        }
+
+        Encoding::DictionaryZstandard(hash) => {
+            writer.write_u8(6)?;
+            writer.write_bytes(&hash.0)
+        }
+    }
+}
+
+fn read_encoding<ReadT>(reader: &mut SnapshotReader<ReadT>) -> io::Result<Encoding>
+where
+    ReadT: Read,
+{
+    match reader.read_u8()? {
+        0 => Ok(Encoding::Identity),
+        1 => Ok(Encoding::Brotli),
+        2 => Ok(Encoding::Deflate),
+        3 => Ok(Encoding::GZip),
+        4 => Ok(Encoding::Zstandard),
+        5 => Ok(Encoding::DictionaryBrotli(read_dictionary_hash(reader)?)),
+        6 => Ok(Encoding::DictionaryZstandard(read_dictionary_hash(reader)?)),
+        code => Err(invalid_data(format!("unsupported encoding: {}", code))),
+    }
+}
+
+fn read_dictionary_hash<ReadT>(reader: &mut SnapshotReader<ReadT>) -> io::Result<DictionaryHash>
+where
+    ReadT: Read,
+{
+    let hash = reader.read_bytes()?;
+    Ok(DictionaryHash(hash.as_ref().try_into().map_err(invalid_data)?))
+}
@@ -1,4 +1,4 @@
-use super::{cache::*, key::*, response::*};
+use super::{super::super::std::collections::*, cache::*, enumerable::*, key::*, response::*};
 
 //
 // TieredCache
@@ -48,8 +48,34 @@ where
         self.next.invalidate(key).await
     }
 
+    async fn invalidate_local(&self, key: &CacheKeyT) {
+        self.first.invalidate_local(key).await;
+        self.next.invalidate_local(key).await
+    }
+
     async fn invalidate_all(&self) {
         self.first.invalidate_all().await;
         self.next.invalidate_all().await
     }
 }
+
+impl<CacheKeyT, FirstCacheT, NextCacheT> EnumerableCache<CacheKeyT> for TieredCache<FirstCacheT, NextCacheT>
+where
+    CacheKeyT: CacheKey,
+    FirstCacheT: EnumerableCache<CacheKeyT>,
+    NextCacheT: EnumerableCache<CacheKeyT>,
+{
+    async fn entries(&self) -> Vec<(CacheKeyT, CachedResponseRef)> {
+        let mut entries = self.first.entries().await;
+
+        // The first cache takes precedence
+        let keys: FastHashSet<_> = entries.iter().map(|(key, _cached_response)| key.clone()).collect();
+        for (key, cached_response) in self.next.entries().await {
+            if !keys.contains(&key) {
+                entries.push((key, cached_response));
+            }
+        }
+
+        entries
+    }
+}
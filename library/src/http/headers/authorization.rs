@@ -0,0 +1,560 @@
+use super::{
+    super::super::std::{immutable::*, string::*},
+    quoted::*,
+};
+
+use {
+    http::*,
+    std::{fmt, result::Result, str::*},
+};
+
+// Parameter values that are always serialized as tokens
+//
+// See IETF RFC 7616 section 3.3 and 3.4
+const UNQUOTED_AUTH_PARAMS: &[&str] = &["algorithm", "nc", "stale", "userhash"];
+
+//
+// AuthParams
+//
+
+/// Authentication parameters.
+///
+/// Ordered. Names are case-insensitive and are stored in lowercase.
+///
+/// See [IETF RFC 9110 section 11.2](https://datatracker.ietf.org/doc/html/rfc9110#section-11.2).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AuthParams(pub Vec<(ByteString, ByteString)>);
+
+impl AuthParams {
+    /// Whether there are no parameters.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Value of a parameter.
+    ///
+    /// The name is case-insensitive.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_ref())
+    }
+
+    /// Set a parameter.
+    ///
+    /// If the name already exists its value will be overwritten (but it will keep its position).
+    pub fn set(&mut self, name: &str, value: ByteString) {
+        match self.0.iter_mut().find(|(key, _)| key.eq_ignore_ascii_case(name)) {
+            Some((_, existing_value)) => *existing_value = value,
+            None => self.0.push((name.to_lowercase().into(), value)),
+        }
+    }
+}
+
+impl FromStr for AuthParams {
+    type Err = ParseError;
+
+    fn from_str(representation: &str) -> Result<Self, Self::Err> {
+        let mut params = Self::default();
+
+        for param in split_unquoted(representation, ',') {
+            let param = param.trim();
+            if param.is_empty() {
+                continue;
+            }
+
+            let Some((name, value)) = param.split_once('=') else {
+                return Err(format!("auth-param without value: {}", param).into());
+            };
+
+            let name = name.trim();
+            if !is_token(name) {
+                return Err(format!("malformed auth-param name: {}", name).into());
+            }
+
+            // Duplicates are not allowed
+            if params.get(name).is_some() {
+                return Err(format!("duplicate auth-param: {}", name).into());
+            }
+
+            params.0.push((name.to_lowercase().into(), unquote(value.trim()).into()));
+        }
+
+        Ok(params)
+    }
+}
+
+impl fmt::Display for AuthParams {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (name, value)) in self.0.iter().enumerate() {
+            if index != 0 {
+                formatter.write_str(", ")?;
+            }
+
+            if UNQUOTED_AUTH_PARAMS.contains(&name.as_ref()) && is_token(value) {
+                write!(formatter, "{}={}", name, value)?;
+            } else {
+                write!(formatter, "{}={}", name, quote(value))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+//
+// Credentials
+//
+
+/// [`Authorization`](http::header::AUTHORIZATION) request header value.
+///
+/// The scheme is case-insensitive.
+///
+/// See [IETF RFC 9110 section 11.4](https://datatracker.ietf.org/doc/html/rfc9110#section-11.4).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Credentials {
+    /// `Basic` scheme.
+    ///
+    /// Expects UTF-8 strings.
+    ///
+    /// See [IETF RFC 7617](https://datatracker.ietf.org/doc/html/rfc7617).
+    Basic {
+        /// Username.
+        username: ByteString,
+
+        /// Password.
+        password: ByteString,
+    },
+
+    /// `Bearer` scheme token.
+    ///
+    /// See [IETF RFC 6750](https://datatracker.ietf.org/doc/html/rfc6750).
+    Bearer(ByteString),
+
+    /// `Digest` scheme.
+    Digest(Box<DigestCredentials>),
+
+    /// Other scheme.
+    Other {
+        /// Scheme.
+        scheme: ByteString,
+
+        /// Token68.
+        token68: Option<ByteString>,
+
+        /// Parameters.
+        params: AuthParams,
+    },
+}
+
+impl Credentials {
+    /// Scheme.
+    pub fn scheme(&self) -> &str {
+        match self {
+            Self::Basic { .. } => "Basic",
+            Self::Bearer(_) => "Bearer",
+            Self::Digest(_) => "Digest",
+            Self::Other { scheme, .. } => scheme,
+        }
+    }
+}
+
+impl FromStr for Credentials {
+    type Err = ParseError;
+
+    fn from_str(representation: &str) -> Result<Self, Self::Err> {
+        let representation = representation.trim();
+        let (scheme, rest) = match representation.split_once(' ') {
+            Some((scheme, rest)) => (scheme, rest.trim()),
+            None => (representation, ""),
+        };
+
+        if !is_token(scheme) {
+            return Err(format!("malformed auth-scheme: {}", scheme).into());
+        }
+
+        if scheme.eq_ignore_ascii_case("Basic") {
+            if !is_token68(rest) {
+                return Err("Basic credentials are not token68".into());
+            }
+
+            let decoded = base64_simd::STANDARD
+                .decode_to_vec(rest)
+                .map_err(|error| ParseError::from(format!("malformed Basic credentials: {}", error)))?;
+            let decoded =
+                String::from_utf8(decoded).map_err(|_| ParseError::from("Basic credentials are not UTF-8"))?;
+
+            match decoded.split_once(':') {
+                Some((username, password)) => Ok(Self::Basic { username: username.into(), password: password.into() }),
+                None => Err("Basic credentials without ':'".into()),
+            }
+        } else if scheme.eq_ignore_ascii_case("Bearer") {
+            if is_token68(rest) { Ok(Self::Bearer(rest.into())) } else { Err("malformed Bearer token".into()) }
+        } else if scheme.eq_ignore_ascii_case("Digest") {
+            Ok(Self::Digest(Box::new(rest.parse()?)))
+        } else if rest.is_empty() {
+            Ok(Self::Other { scheme: scheme.into(), token68: None, params: Default::default() })
+        } else if is_token68(rest) {
+            Ok(Self::Other { scheme: scheme.into(), token68: Some(rest.into()), params: Default::default() })
+        } else {
+            Ok(Self::Other { scheme: scheme.into(), token68: None, params: rest.parse()? })
+        }
+    }
+}
+
+impl fmt::Display for Credentials {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Basic { username, password } => write!(
+                formatter,
+                "Basic {}",
+                base64_simd::STANDARD.encode_to_string(format!("{}:{}", username, password))
+            ),
+
+            Self::Bearer(token) => write!(formatter, "Bearer {}", token),
+
+            Self::Digest(digest) => write!(formatter, "Digest {}", digest),
+
+            Self::Other { scheme, token68, params } => {
+                formatter.write_str(scheme)?;
+                if let Some(token68) = token68 {
+                    write!(formatter, " {}", token68)?;
+                }
+                if !params.is_empty() {
+                    write!(formatter, " {}", params)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl TryFrom<Credentials> for HeaderValue {
+    type Error = ParseError;
+
+    /// Fails if the credentials contain control characters.
+    fn try_from(credentials: Credentials) -> Result<Self, Self::Error> {
+        HeaderValue::try_from(credentials.to_string()).map_err(|error| format!("invalid credentials: {}", error).into())
+    }
+}
+
+//
+// DigestCredentials
+//
+
+/// `Digest` scheme credentials.
+///
+/// Verifying them requires the request method (and possibly the body), so this is left to the
+/// application.
+///
+/// See [IETF RFC 7616 section 3.4](https://datatracker.ietf.org/doc/html/rfc7616#section-3.4).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DigestCredentials {
+    /// Username (or its hash if `userhash` is true).
+    ///
+    /// Decoded from `username*` if that was used instead.
+    pub username: ByteString,
+
+    /// Realm.
+    pub realm: ByteString,
+
+    /// URI.
+    pub uri: ByteString,
+
+    /// Nonce.
+    pub nonce: ByteString,
+
+    /// Response (hex digest).
+    pub response: ByteString,
+
+    /// Algorithm.
+    ///
+    /// [None] means `MD5`.
+    pub algorithm: Option<ByteString>,
+
+    /// Client nonce.
+    pub cnonce: Option<ByteString>,
+
+    /// Opaque.
+    pub opaque: Option<ByteString>,
+
+    /// Quality of protection.
+    pub qop: Option<ByteString>,
+
+    /// Nonce count.
+    pub nc: Option<ByteString>,
+
+    /// Whether the username is hashed.
+    pub userhash: bool,
+}
+
+impl FromStr for DigestCredentials {
+    type Err = ParseError;
+
+    fn from_str(representation: &str) -> Result<Self, Self::Err> {
+        let params: AuthParams = representation.parse()?;
+
+        let required = |name| match params.get(name) {
+            Some(value) => Ok(ByteString::from(value)),
+            None => Err(ParseError::from(format!("Digest credentials without {}", name))),
+        };
+        let optional = |name| params.get(name).map(ByteString::from);
+
+        let username = match (params.get("username"), params.get("username*")) {
+            (Some(username), None) => username.into(),
+            (None, Some(username)) => decode_ext_value(username)?.into(),
+            _ => return Err("Digest credentials must have exactly one of username or username*".into()),
+        };
+
+        Ok(Self {
+            username,
+            realm: required("realm")?,
+            uri: required("uri")?,
+            nonce: required("nonce")?,
+            response: required("response")?,
+            algorithm: optional("algorithm"),
+            cnonce: optional("cnonce"),
+            opaque: optional("opaque"),
+            qop: optional("qop"),
+            nc: optional("nc"),
+            userhash: params.get("userhash").map(|userhash| userhash.eq_ignore_ascii_case("true")).unwrap_or_default(),
+        })
+    }
+}
+
+impl fmt::Display for DigestCredentials {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "username={}, realm={}, uri={}, nonce={}, response={}",
+            quote(&self.username),
+            quote(&self.realm),
+            quote(&self.uri),
+            quote(&self.nonce),
+            quote(&self.response)
+        )?;
+
+        if let Some(algorithm) = &self.algorithm {
+            write!(formatter, ", algorithm={}", algorithm)?;
+        }
+
+        if let Some(cnonce) = &self.cnonce {
+            write!(formatter, ", cnonce={}", quote(cnonce))?;
+        }
+
+        if let Some(opaque) = &self.opaque {
+            write!(formatter, ", opaque={}", quote(opaque))?;
+        }
+
+        if let Some(qop) = &self.qop {
+            write!(formatter, ", qop={}", qop)?;
+        }
+
+        if let Some(nc) = &self.nc {
+            write!(formatter, ", nc={}", nc)?;
+        }
+
+        if self.userhash {
+            formatter.write_str(", userhash=true")?;
+        }
+
+        Ok(())
+    }
+}
+
+//
+// Challenge
+//
+
+/// [`WWW-Authenticate`](http::header::WWW_AUTHENTICATE) response header value.
+///
+/// See [IETF RFC 9110 section 11.6.1](https://datatracker.ietf.org/doc/html/rfc9110#section-11.6.1).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Challenge {
+    /// Scheme.
+    pub scheme: ByteString,
+
+    /// Token68.
+    ///
+    /// Cannot be used together with parameters.
+    pub token68: Option<ByteString>,
+
+    /// Parameters.
+    pub params: AuthParams,
+}
+
+impl Challenge {
+    /// Constructor.
+    pub fn new(scheme: ByteString) -> Self {
+        Self { scheme, token68: None, params: Default::default() }
+    }
+
+    /// `Basic` challenge.
+    ///
+    /// Announces that we expect UTF-8.
+    ///
+    /// See [IETF RFC 7617 section 2](https://datatracker.ietf.org/doc/html/rfc7617#section-2).
+    pub fn basic(realm: ByteString) -> Self {
+        Self::new("Basic".into()).with_realm(realm).with_param("charset", "UTF-8".into())
+    }
+
+    /// `Bearer` challenge.
+    ///
+    /// See [IETF RFC 6750 section 3](https://datatracker.ietf.org/doc/html/rfc6750#section-3).
+    pub fn bearer(realm: ByteString) -> Self {
+        Self::new("Bearer".into()).with_realm(realm)
+    }
+
+    /// `Digest` challenge.
+    ///
+    /// See [IETF RFC 7616 section 3.3](https://datatracker.ietf.org/doc/html/rfc7616#section-3.3).
+    pub fn digest(realm: ByteString, nonce: ByteString) -> Self {
+        Self::new("Digest".into()).with_realm(realm).with_param("nonce", nonce)
+    }
+
+    /// With parameter.
+    pub fn with_param(mut self, name: &str, value: ByteString) -> Self {
+        self.params.set(name, value);
+        self
+    }
+
+    /// With realm.
+    pub fn with_realm(self, realm: ByteString) -> Self {
+        self.with_param("realm", realm)
+    }
+
+    /// With `Bearer` error.
+    ///
+    /// See [IETF RFC 6750 section 3.1](https://datatracker.ietf.org/doc/html/rfc6750#section-3.1).
+    pub fn with_bearer_error(self, error: BearerError, description: Option<ByteString>) -> Self {
+        let challenge = self.with_param("error", error.as_str().into());
+        match description {
+            Some(description) => challenge.with_param("error_description", description),
+            None => challenge,
+        }
+    }
+
+    /// With `Bearer` scope.
+    pub fn with_scope(self, scope: ByteString) -> Self {
+        self.with_param("scope", scope)
+    }
+
+    /// With `Digest` algorithm.
+    pub fn with_algorithm(self, algorithm: ByteString) -> Self {
+        self.with_param("algorithm", algorithm)
+    }
+
+    /// With `Digest` quality of protection (e.g. "auth").
+    pub fn with_qop(self, qop: ByteString) -> Self {
+        self.with_param("qop", qop)
+    }
+
+    /// With `Digest` opaque.
+    pub fn with_opaque(self, opaque: ByteString) -> Self {
+        self.with_param("opaque", opaque)
+    }
+
+    /// With `Digest` stale flag.
+    ///
+    /// Tells the client that the credentials were fine but the nonce has expired.
+    pub fn with_stale(self, stale: bool) -> Self {
+        self.with_param("stale", if stale { "true" } else { "false" }.into())
+    }
+}
+
+impl fmt::Display for Challenge {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.scheme)?;
+        if let Some(token68) = &self.token68 {
+            write!(formatter, " {}", token68)?;
+        } else if !self.params.is_empty() {
+            write!(formatter, " {}", self.params)?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<Challenge> for HeaderValue {
+    type Error = ParseError;
+
+    /// Fails if the challenge contains control characters.
+    fn try_from(challenge: Challenge) -> Result<Self, Self::Error> {
+        HeaderValue::try_from(challenge.to_string()).map_err(|error| format!("invalid challenge: {}", error).into())
+    }
+}
+
+//
+// BearerError
+//
+
+/// `Bearer` challenge error.
+///
+/// See [IETF RFC 6750 section 3.1](https://datatracker.ietf.org/doc/html/rfc6750#section-3.1).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BearerError {
+    /// Invalid request.
+    InvalidRequest,
+
+    /// Invalid token.
+    InvalidToken,
+
+    /// Insufficient scope.
+    InsufficientScope,
+}
+
+impl BearerError {
+    /// As string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::InvalidRequest => "invalid_request",
+            Self::InvalidToken => "invalid_token",
+            Self::InsufficientScope => "insufficient_scope",
+        }
+    }
+
+    /// The status code that should accompany the challenge.
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Self::InvalidRequest => StatusCode::BAD_REQUEST,
+            Self::InvalidToken => StatusCode::UNAUTHORIZED,
+            Self::InsufficientScope => StatusCode::FORBIDDEN,
+        }
+    }
+}
+
+impl fmt::Display for BearerError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+// See IETF RFC 9110 section 11.2
+fn is_token68(value: &str) -> bool {
+    let trimmed = value.trim_end_matches('=');
+    !trimmed.is_empty() && trimmed.chars().all(|c| c.is_ascii_alphanumeric() || "-._~+/".contains(c))
+}
+
+// See IETF RFC 8187 section 3.2
+fn decode_ext_value(value: &str) -> Result<String, ParseError> {
+    let Some((charset, rest)) = value.split_once('\'') else {
+        return Err(format!("malformed ext-value: {}", value).into());
+    };
+
+    if !charset.eq_ignore_ascii_case("UTF-8") {
+        return Err(format!("unsupported ext-value charset: {}", charset).into());
+    }
+
+    // Skip language
+    let encoded = rest.split_once('\'').map(|(_, encoded)| encoded).unwrap_or(rest);
+
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut encoded = encoded.bytes();
+    while let Some(byte) = encoded.next() {
+        if byte == b'%' {
+            let hex = [encoded.next().unwrap_or_default(), encoded.next().unwrap_or_default()];
+            let hex = str::from_utf8(&hex).map_err(|_| ParseError::from("malformed ext-value"))?;
+            bytes.push(u8::from_str_radix(hex, 16).map_err(|_| ParseError::from("malformed ext-value"))?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+
+    String::from_utf8(bytes).map_err(|_| "ext-value is not UTF-8".into())
+}
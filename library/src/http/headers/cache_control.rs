@@ -0,0 +1,348 @@
+use super::{
+    super::super::std::{immutable::*, string::*},
+    into::*,
+    quoted::*,
+};
+
+use {
+    http::*,
+    std::{convert::*, fmt, result::Result, str::*, time::*},
+};
+
+//
+// CacheControl
+//
+
+/// `Cache-Control` HTTP header value.
+///
+/// Supports all request and response directives in
+/// [IETF RFC 9111 section 5.2](https://datatracker.ietf.org/doc/html/rfc9111#section-5.2),
+/// `immutable` ([IETF RFC 8246](https://datatracker.ietf.org/doc/html/rfc8246)),
+/// `stale-while-revalidate` and `stale-if-error`
+/// ([IETF RFC 5861](https://datatracker.ietf.org/doc/html/rfc5861)), as well as extension
+/// directives.
+///
+/// Directive names are case-insensitive. Malformed directive values are skipped.
+///
+/// When formatting, extension directives and field names that cannot be represented in a header
+/// are skipped.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CacheControl {
+    /// `max-age` (request and response).
+    pub max_age: Option<Duration>,
+
+    /// `max-stale` (request).
+    ///
+    /// The inner [None] means that any staleness is acceptable.
+    pub max_stale: Option<Option<Duration>>,
+
+    /// `min-fresh` (request).
+    pub min_fresh: Option<Duration>,
+
+    /// `must-revalidate` (response).
+    pub must_revalidate: bool,
+
+    /// `must-understand` (response).
+    pub must_understand: bool,
+
+    /// `no-cache` (request and response).
+    ///
+    /// An empty list means the directive applies to the whole response. Otherwise it applies only
+    /// to the listed header fields (response only).
+    pub no_cache: Option<Vec<ByteString>>,
+
+    /// `no-store` (request and response).
+    pub no_store: bool,
+
+    /// `no-transform` (request and response).
+    pub no_transform: bool,
+
+    /// `only-if-cached` (request).
+    pub only_if_cached: bool,
+
+    /// `private` (response).
+    ///
+    /// An empty list means the directive applies to the whole response. Otherwise it applies only
+    /// to the listed header fields.
+    pub private: Option<Vec<ByteString>>,
+
+    /// `proxy-revalidate` (response).
+    pub proxy_revalidate: bool,
+
+    /// `public` (response).
+    pub public: bool,
+
+    /// `s-maxage` (response).
+    pub s_maxage: Option<Duration>,
+
+    /// `immutable` (response).
+    pub immutable: bool,
+
+    /// `stale-while-revalidate` (response).
+    pub stale_while_revalidate: Option<Duration>,
+
+    /// `stale-if-error` (request and response).
+    pub stale_if_error: Option<Duration>,
+
+    /// Extension directives, with optional values.
+    pub extensions: Vec<(ByteString, Option<ByteString>)>,
+}
+
+impl CacheControl {
+    /// Whether there are no directives.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// With `max-age`.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// With `s-maxage`.
+    pub fn with_s_maxage(mut self, s_maxage: Duration) -> Self {
+        self.s_maxage = Some(s_maxage);
+        self
+    }
+
+    /// With `no-cache` for the whole response.
+    pub fn with_no_cache(mut self) -> Self {
+        self.no_cache = Some(Default::default());
+        self
+    }
+
+    /// With `no-store`.
+    pub fn with_no_store(mut self) -> Self {
+        self.no_store = true;
+        self
+    }
+
+    /// With `public`.
+    pub fn with_public(mut self) -> Self {
+        self.public = true;
+        self
+    }
+
+    /// With `private` for the whole response.
+    pub fn with_private(mut self) -> Self {
+        self.private = Some(Default::default());
+        self
+    }
+
+    /// With `immutable`.
+    pub fn with_immutable(mut self) -> Self {
+        self.immutable = true;
+        self
+    }
+
+    /// With `must-revalidate`.
+    pub fn with_must_revalidate(mut self) -> Self {
+        self.must_revalidate = true;
+        self
+    }
+
+    /// With `stale-while-revalidate`.
+    pub fn with_stale_while_revalidate(mut self, stale_while_revalidate: Duration) -> Self {
+        self.stale_while_revalidate = Some(stale_while_revalidate);
+        self
+    }
+
+    /// With `stale-if-error`.
+    pub fn with_stale_if_error(mut self, stale_if_error: Duration) -> Self {
+        self.stale_if_error = Some(stale_if_error);
+        self
+    }
+
+    /// With an extension directive.
+    ///
+    /// The name must be a token and the value must not contain control characters.
+    pub fn with_extension(mut self, name: ByteString, value: Option<ByteString>) -> Result<Self, ParseError> {
+        if !is_valid_extension(&name, value.as_ref()) {
+            return Err(format!("invalid Cache-Control extension: {}", name).into());
+        }
+
+        self.extensions.push((name, value));
+        Ok(self)
+    }
+
+    /// Parse and add a directive.
+    fn add_directive(&mut self, name: &str, value: Option<&str>) {
+        match name {
+            "max-age" => self.max_age = delta_seconds(name, value).or(self.max_age),
+            "max-stale" => match value {
+                // Without a value any staleness is acceptable
+                None => self.max_stale = Some(None),
+                Some(_) => self.max_stale = delta_seconds(name, value).map(Some).or(self.max_stale),
+            },
+            "min-fresh" => self.min_fresh = delta_seconds(name, value).or(self.min_fresh),
+            "must-revalidate" => self.must_revalidate = true,
+            "must-understand" => self.must_understand = true,
+            "no-cache" => self.no_cache = Some(field_names(value)),
+            "no-store" => self.no_store = true,
+            "no-transform" => self.no_transform = true,
+            "only-if-cached" => self.only_if_cached = true,
+            "private" => self.private = Some(field_names(value)),
+            "proxy-revalidate" => self.proxy_revalidate = true,
+            "public" => self.public = true,
+            "s-maxage" => self.s_maxage = delta_seconds(name, value).or(self.s_maxage),
+            "immutable" => self.immutable = true,
+            "stale-while-revalidate" => {
+                self.stale_while_revalidate = delta_seconds(name, value).or(self.stale_while_revalidate)
+            }
+            "stale-if-error" => self.stale_if_error = delta_seconds(name, value).or(self.stale_if_error),
+            _ => self.extensions.push((name.into(), value.map(|value| value.into()))),
+        }
+    }
+}
+
+impl FromStr for CacheControl {
+    type Err = Infallible;
+
+    fn from_str(representation: &str) -> Result<Self, Self::Err> {
+        let mut cache_control = Self::default();
+
+        for directive in split_unquoted(representation, ',') {
+            let (name, value) = match directive.split_once('=') {
+                Some((name, value)) => (name, Some(unquote(value.trim()))),
+                None => (directive, None),
+            };
+
+            let name = name.trim().to_lowercase();
+            if !name.is_empty() {
+                cache_control.add_directive(&name, value.as_deref());
+            }
+        }
+
+        Ok(cache_control)
+    }
+}
+
+impl fmt::Display for CacheControl {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut directives = Vec::new();
+
+        if self.public {
+            directives.push("public".into());
+        }
+
+        if let Some(field_names) = &self.private {
+            directives.push(with_field_names("private", field_names));
+        }
+
+        if let Some(field_names) = &self.no_cache {
+            directives.push(with_field_names("no-cache", field_names));
+        }
+
+        if self.no_store {
+            directives.push("no-store".into());
+        }
+
+        if self.no_transform {
+            directives.push("no-transform".into());
+        }
+
+        if let Some(max_age) = self.max_age {
+            directives.push(format!("max-age={}", max_age.as_secs()));
+        }
+
+        if let Some(s_maxage) = self.s_maxage {
+            directives.push(format!("s-maxage={}", s_maxage.as_secs()));
+        }
+
+        if let Some(max_stale) = self.max_stale {
+            directives.push(match max_stale {
+                Some(max_stale) => format!("max-stale={}", max_stale.as_secs()),
+                None => "max-stale".into(),
+            });
+        }
+
+        if let Some(min_fresh) = self.min_fresh {
+            directives.push(format!("min-fresh={}", min_fresh.as_secs()));
+        }
+
+        if self.must_revalidate {
+            directives.push("must-revalidate".into());
+        }
+
+        if self.proxy_revalidate {
+            directives.push("proxy-revalidate".into());
+        }
+
+        if self.must_understand {
+            directives.push("must-understand".into());
+        }
+
+        if self.only_if_cached {
+            directives.push("only-if-cached".into());
+        }
+
+        if self.immutable {
+            directives.push("immutable".into());
+        }
+
+        if let Some(stale_while_revalidate) = self.stale_while_revalidate {
+            directives.push(format!("stale-while-revalidate={}", stale_while_revalidate.as_secs()));
+        }
+
+        if let Some(stale_if_error) = self.stale_if_error {
+            directives.push(format!("stale-if-error={}", stale_if_error.as_secs()));
+        }
+
+        for (name, value) in &self.extensions {
+            if !is_valid_extension(name, value.as_ref()) {
+                tracing::warn!("skip (invalid Cache-Control extension): {}", name);
+                continue;
+            }
+
+            directives.push(match value {
+                Some(value) => format!("{}={}", name, quote_if_necessary(value)),
+                None => name.to_string(),
+            });
+        }
+
+        write!(formatter, "{}", directives.join(", "))
+    }
+}
+
+impl IntoHeaderValue for CacheControl {
+    fn into_header_value(self) -> HeaderValue {
+        HeaderValue::try_from(self.to_string()).expect("cache control in HTTP header")
+    }
+}
+
+fn delta_seconds(name: &str, value: Option<&str>) -> Option<Duration> {
+    match value?.parse() {
+        Ok(seconds) => Some(Duration::from_secs(seconds)),
+
+        Err(error) => {
+            tracing::warn!("malformed {}: {}", name, error);
+            None
+        }
+    }
+}
+
+fn field_names(value: Option<&str>) -> Vec<ByteString> {
+    value
+        .map(|value| {
+            value
+                .split(',')
+                .map(|field_name| field_name.trim())
+                .filter(|field_name| !field_name.is_empty())
+                .map(|field_name| field_name.to_lowercase().into())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn is_valid_extension(name: &str, value: Option<&ByteString>) -> bool {
+    is_token(name) && value.is_none_or(|value| is_quotable(value))
+}
+
+// If no valid field names remain we fall back to the stricter whole-response directive
+fn with_field_names(name: &str, field_names: &[ByteString]) -> String {
+    let field_names: Vec<_> =
+        field_names.iter().map(|field_name| field_name.as_ref()).filter(|field_name| is_token(field_name)).collect();
+
+    if field_names.is_empty() { name.into() } else { format!("{}=\"{}\"", name, field_names.join(", ")) }
+}
@@ -1,16 +1,29 @@
-use super::{date::*, headers::*};
+use super::{super::super::std::string::*, date::*, etag::*, headers::*, preferences::*};
 
-use http::*;
+use {
+    http::{header::*, *},
+    httpdate::*,
+    std::{result::Result, str::*},
+};
+
+/// Response headers that should be sent with a 304 (Not Modified).
+///
+/// See [IETF RFC 9110 section 15.4.5](https://datatracker.ietf.org/doc/html/rfc9110#section-15.4.5).
+pub const NOT_MODIFIED_HEADERS: &[HeaderName] =
+    &[CACHE_CONTROL, CONTENT_LOCATION, DATE, ETAG, EXPIRES, LAST_MODIFIED, VARY];
 
 /// Conditional HTTP.
 ///
+/// Only handles `If-None-Match` and `If-Modified-Since`. See [evaluate_preconditions] for complete
+/// evaluation.
+///
 /// If there is not enough information we will assume that we have been modified and return true.
 pub fn modified(request_headers: &HeaderMap, response_headers: &HeaderMap) -> bool {
     // `If-None-Match` takes precedence over `If-Modified-Since`
 
     // Note that ETagMatch::Any has a special meaning when not GET or HEAD
     if let Some(if_none_match) = request_headers.if_none_match()
-        && if_none_match.matches(response_headers.etag().as_ref())
+        && if_none_match.matches_weak(response_headers.etag().as_ref())
     {
         tracing::debug!("not modified (If-None-Match)");
         return false;
@@ -26,3 +39,176 @@ pub fn modified(request_headers: &HeaderMap, response_headers: &HeaderMap) -> bo
 
     true
 }
+
+/// Evaluate the request's preconditions against the current representation.
+///
+/// Follows the order in
+/// [IETF RFC 9110 section 13.2.2](https://datatracker.ietf.org/doc/html/rfc9110#section-13.2.2):
+/// `If-Match`, `If-Unmodified-Since`, `If-None-Match`, `If-Modified-Since`, and finally
+/// `If-Range`.
+///
+/// Entity tags are compared with strong comparison for `If-Match` and `If-Range` and with weak
+/// comparison for `If-None-Match`.
+///
+/// The representation's validators are taken from its `ETag` and `Last-Modified` headers.
+/// `representation_headers` should be [None] if there is no current representation, e.g. when a
+/// `PUT` would create it.
+///
+/// Malformed precondition headers are ignored.
+pub fn evaluate_preconditions(
+    method: &Method,
+    request_headers: &HeaderMap,
+    representation_headers: Option<&HeaderMap>,
+) -> Precondition {
+    let is_get_or_head = (method == Method::GET) || (method == Method::HEAD);
+    let exists = representation_headers.is_some();
+    let etag = representation_headers.and_then(|headers| headers.etag());
+    let last_modified = representation_headers.and_then(|headers| headers.last_modified());
+
+    // 1. `If-Match`
+    // 2. Otherwise `If-Unmodified-Since`
+    if let Some(if_match) = request_headers.if_match() {
+        if !matches_representation(&if_match, etag.as_ref(), exists, ETagMatcher::matches_strong) {
+            tracing::debug!("precondition failed (If-Match)");
+            return Precondition::Failed;
+        }
+    } else if let Some(if_unmodified_since) = request_headers.if_unmodified_since()
+        && let Some(last_modified) = last_modified
+        && last_modified > if_unmodified_since
+    {
+        tracing::debug!("precondition failed (If-Unmodified-Since)");
+        return Precondition::Failed;
+    }
+
+    // 3. `If-None-Match`
+    // 4. Otherwise `If-Modified-Since` (only for GET and HEAD)
+    if let Some(if_none_match) = request_headers.if_none_match() {
+        if matches_representation(&if_none_match, etag.as_ref(), exists, ETagMatcher::matches_weak) {
+            return if is_get_or_head {
+                tracing::debug!("not modified (If-None-Match)");
+                Precondition::NotModified
+            } else {
+                tracing::debug!("precondition failed (If-None-Match)");
+                Precondition::Failed
+            };
+        }
+    } else if is_get_or_head
+        && let Some(if_modified_since) = request_headers.if_modified_since()
+        && !modified_since(last_modified, Some(if_modified_since))
+    {
+        tracing::debug!("not modified (If-Modified-Since)");
+        return Precondition::NotModified;
+    }
+
+    // 5. `If-Range` (only for GET with `Range`)
+    if method == Method::GET
+        && request_headers.contains_key(RANGE)
+        && let Some(if_range) = request_headers.if_range()
+        && !if_range.matches(etag.as_ref(), last_modified)
+    {
+        tracing::debug!("ignoring range (If-Range)");
+        return Precondition::ProceedWithoutRange;
+    }
+
+    Precondition::Proceed
+}
+
+// "*" matches only if the representation exists
+fn matches_representation<MatchesT>(matcher: &ETagMatcher, etag: Option<&ETag>, exists: bool, matches: MatchesT) -> bool
+where
+    MatchesT: Fn(&ETagMatcher, Option<&ETag>) -> bool,
+{
+    match matcher.0 {
+        Selector::Any => exists,
+        Selector::Specific(_) => matches(matcher, etag),
+    }
+}
+
+/// Copy the [NOT_MODIFIED_HEADERS] from the representation headers.
+pub fn not_modified_headers(representation_headers: &HeaderMap) -> HeaderMap {
+    let mut headers = HeaderMap::default();
+    for name in NOT_MODIFIED_HEADERS {
+        for value in representation_headers.get_all(name) {
+            headers.append(name, value.clone());
+        }
+    }
+    headers
+}
+
+//
+// Precondition
+//
+
+/// Outcome of [evaluate_preconditions].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Precondition {
+    /// Proceed with the request.
+    Proceed,
+
+    /// Proceed with the request but ignore its `Range` header, i.e. respond with the complete
+    /// representation.
+    ProceedWithoutRange,
+
+    /// Respond with 304 (Not Modified).
+    ///
+    /// Only for `GET` and `HEAD`.
+    NotModified,
+
+    /// Respond with 412 (Precondition Failed).
+    Failed,
+}
+
+impl Precondition {
+    /// Whether to proceed with the request.
+    pub fn proceed(&self) -> bool {
+        matches!(self, Self::Proceed | Self::ProceedWithoutRange)
+    }
+
+    /// The status code to respond with if we should *not* proceed.
+    pub fn status_code(&self) -> Option<StatusCode> {
+        match self {
+            Self::Proceed | Self::ProceedWithoutRange => None,
+            Self::NotModified => Some(StatusCode::NOT_MODIFIED),
+            Self::Failed => Some(StatusCode::PRECONDITION_FAILED),
+        }
+    }
+}
+
+//
+// IfRange
+//
+
+/// `If-Range` request header value.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum IfRange {
+    /// Entity tag.
+    ETag(ETag),
+
+    /// Date.
+    Date(HttpDate),
+}
+
+impl IfRange {
+    /// Whether the representation's validators match.
+    ///
+    /// Entity tags use strong comparison. Dates must match exactly.
+    pub fn matches(&self, etag: Option<&ETag>, last_modified: Option<HttpDate>) -> bool {
+        match self {
+            Self::ETag(tag) => etag.is_some_and(|etag| tag.strong_eq(etag)),
+            Self::Date(date) => last_modified.is_some_and(|last_modified| last_modified == *date),
+        }
+    }
+}
+
+impl FromStr for IfRange {
+    type Err = ParseError;
+
+    fn from_str(representation: &str) -> Result<Self, Self::Err> {
+        let representation = representation.trim();
+        if representation.ends_with('"') {
+            Ok(Self::ETag(representation.parse()?))
+        } else {
+            representation.parse().map(Self::Date).map_err(|_| "malformed date".into())
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use super::super::super::{
+    super::std::{borrow::*, foster::*, immutable::*},
+    cache::*,
+};
+
+use std::fmt;
+
+//
+// Cookies
+//
+
+/// [`Cookie`](http::header::COOKIE) request header value.
+///
+/// Ordered, and names can repeat (the client sends more specific paths first). Names and values
+/// are case-sensitive.
+///
+/// See [IETF RFC 6265 section 5.4](https://datatracker.ietf.org/doc/html/rfc6265#section-5.4).
+#[derive(Clone, Debug)]
+pub struct Cookies(pub FosterCookies);
+
+/// [Foster] for cookie names and values.
+pub type FosterCookies = Foster<Vec<(ByteString, ByteString)>, &'static [(&'static str, &'static str)]>;
+
+impl Cookies {
+    /// No cookies.
+    pub const NONE: Self = Self::new_fostered(&[]);
+
+    /// Constructor.
+    pub const fn new_owned(cookies: Vec<(ByteString, ByteString)>) -> Self {
+        Self(Foster::new_owned(cookies))
+    }
+
+    /// Constructor.
+    pub const fn new_fostered(cookies: &'static [(&'static str, &'static str)]) -> Self {
+        Self(Foster::new_fostered(cookies))
+    }
+
+    /// Parse.
+    ///
+    /// Multiple header values are combined (HTTP/2 and HTTP/3 clients may split the cookies).
+    /// Surrounding quotes are removed from values. Malformed pairs are skipped.
+    pub fn parse(representations: &Vec<&str>) -> Self {
+        let cookies: Vec<_> = representations
+            .iter()
+            .flat_map(|representation| representation.split(';'))
+            .filter_map(|cookie| {
+                let cookie = cookie.trim();
+                if cookie.is_empty() {
+                    return None;
+                }
+
+                match cookie.split_once('=') {
+                    Some((name, value)) if !name.trim().is_empty() => {
+                        let value = value.trim();
+                        let value = value.strip_prefix('"').and_then(|value| value.strip_suffix('"')).unwrap_or(value);
+                        Some((name.trim().into(), value.into()))
+                    }
+
+                    _ => {
+                        tracing::warn!("malformed cookie: {}", cookie);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        if cookies.is_empty() { Self::NONE } else { Self::new_owned(cookies) }
+    }
+
+    /// Whether there are no cookies.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate names and values.
+    pub fn iter(&self) -> Box<dyn Iterator<Item = (&str, &str)> + '_> {
+        match &self.0 {
+            Foster::Owned(cookies) => Box::new(cookies.iter().map(|(name, value)| (name.as_ref(), value.as_ref()))),
+            Foster::Fostered(cookies) => Box::new(cookies.iter().map(|(name, value)| (*name, *value))),
+        }
+    }
+
+    /// Value of a cookie.
+    ///
+    /// If the name repeats, returns the first value.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.iter().find(|(cookie_name, _)| *cookie_name == name).map(|(_, value)| value)
+    }
+
+    /// All values of a cookie.
+    pub fn get_all<'own>(&'own self, name: &'own str) -> impl Iterator<Item = &'own str> {
+        self.iter().filter(move |(cookie_name, _)| *cookie_name == name).map(|(_, value)| value)
+    }
+
+    /// Whether we have a cookie.
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+}
+
+impl Default for Cookies {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+impl HasLength for Cookies {
+    fn len(&self) -> usize {
+        match &self.0 {
+            Foster::Owned(cookies) => cookies.len(),
+            Foster::Fostered(cookies) => cookies.len(),
+        }
+    }
+}
+
+impl IntoOwned for Cookies {
+    fn into_owned(self) -> Self {
+        match self.0 {
+            Foster::Owned(_) => self,
+            Foster::Fostered(cookies) => {
+                Self::new_owned(cookies.iter().map(|(name, value)| ((*name).into(), (*value).into())).collect())
+            }
+        }
+    }
+}
+
+impl CacheWeight for Cookies {
+    fn cache_weight(&self) -> usize {
+        let mut size = size_of::<Self>();
+        if let Foster::Owned(cookies) = &self.0 {
+            for (name, value) in cookies {
+                size += name.len() + value.len();
+            }
+        }
+        size
+    }
+}
+
+impl PartialEq for Cookies {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for Cookies {}
+
+impl fmt::Display for Cookies {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (name, value)) in self.iter().enumerate() {
+            if index != 0 {
+                formatter.write_str("; ")?;
+            }
+            write!(formatter, "{}={}", name, value)?;
+        }
+        Ok(())
+    }
+}
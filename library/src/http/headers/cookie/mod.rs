@@ -0,0 +1,5 @@
+mod cookies;
+mod set_cookie;
+
+#[allow(unused_imports)]
+pub use {cookies::*, set_cookie::*};
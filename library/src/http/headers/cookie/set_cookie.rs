@@ -0,0 +1,356 @@
+use super::super::{
+    super::super::std::{immutable::*, string::*},
+    quoted::*,
+};
+
+use {
+    http::*,
+    httpdate::*,
+    std::{fmt, result::Result, str::*, time::*},
+};
+
+//
+// SetCookie
+//
+
+/// [`Set-Cookie`](http::header::SET_COOKIE) response header value.
+///
+/// See [IETF RFC 6265 section 4.1](https://datatracker.ietf.org/doc/html/rfc6265#section-4.1).
+///
+/// The builders validate their arguments. If you set the fields directly, conversion to a
+/// [HeaderValue] will validate them.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SetCookie {
+    /// Name.
+    pub name: ByteString,
+
+    /// Value.
+    pub value: ByteString,
+
+    /// Expires.
+    pub expires: Option<HttpDate>,
+
+    /// Max age.
+    ///
+    /// Takes precedence over [expires](Self::expires). Zero means that the cookie should be
+    /// removed.
+    pub max_age: Option<Duration>,
+
+    /// Domain.
+    pub domain: Option<ByteString>,
+
+    /// Path.
+    pub path: Option<ByteString>,
+
+    /// Secure.
+    pub secure: bool,
+
+    /// HTTP only (not accessible to JavaScript).
+    pub http_only: bool,
+
+    /// Same site.
+    pub same_site: Option<SameSite>,
+
+    /// Partitioned (CHIPS).
+    ///
+    /// See [the proposal](https://github.com/privacycg/CHIPS).
+    pub partitioned: bool,
+}
+
+impl SetCookie {
+    /// Constructor.
+    ///
+    /// The name must be a token and the value must be cookie octets (optionally quoted).
+    pub fn new(name: ByteString, value: ByteString) -> Result<Self, ParseError> {
+        validate_name(&name)?;
+        validate_value(&value)?;
+        Ok(Self::new_unchecked(name, value))
+    }
+
+    /// Constructor for removing a cookie.
+    ///
+    /// Sets an empty value, a zero max age, and the Unix epoch as the expiry (for old clients).
+    ///
+    /// Note that the domain and path must match those with which the cookie was set.
+    pub fn removal(name: ByteString) -> Result<Self, ParseError> {
+        Ok(Self::new(name, Default::default())?.with_max_age(Duration::ZERO).with_expires(UNIX_EPOCH.into()))
+    }
+
+    /// Validate.
+    pub fn validate(&self) -> Result<(), ParseError> {
+        validate_name(&self.name)?;
+        validate_value(&self.value)?;
+
+        if let Some(domain) = &self.domain {
+            validate_attribute_value("Domain", domain)?;
+        }
+
+        if let Some(path) = &self.path {
+            validate_attribute_value("Path", path)?;
+        }
+
+        Ok(())
+    }
+
+    fn new_unchecked(name: ByteString, value: ByteString) -> Self {
+        Self {
+            name,
+            value,
+            expires: None,
+            max_age: None,
+            domain: None,
+            path: None,
+            secure: false,
+            http_only: false,
+            same_site: None,
+            partitioned: false,
+        }
+    }
+
+    /// With expires.
+    pub fn with_expires(mut self, expires: HttpDate) -> Self {
+        self.expires = Some(expires);
+        self
+    }
+
+    /// With max age.
+    ///
+    /// Sub-second precision is ignored.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// With domain.
+    ///
+    /// Must not contain control characters or ";".
+    pub fn with_domain(mut self, domain: ByteString) -> Result<Self, ParseError> {
+        validate_attribute_value("Domain", &domain)?;
+        self.domain = Some(domain);
+        Ok(self)
+    }
+
+    /// With path.
+    ///
+    /// Must not contain control characters or ";".
+    pub fn with_path(mut self, path: ByteString) -> Result<Self, ParseError> {
+        validate_attribute_value("Path", &path)?;
+        self.path = Some(path);
+        Ok(self)
+    }
+
+    /// With secure.
+    pub fn with_secure(mut self) -> Self {
+        self.secure = true;
+        self
+    }
+
+    /// With HTTP only.
+    pub fn with_http_only(mut self) -> Self {
+        self.http_only = true;
+        self
+    }
+
+    /// With same site.
+    ///
+    /// [SameSite::None] also sets [secure](Self::secure), because clients require it.
+    pub fn with_same_site(mut self, same_site: SameSite) -> Self {
+        if same_site == SameSite::None {
+            self.secure = true;
+        }
+        self.same_site = Some(same_site);
+        self
+    }
+
+    /// With partitioned.
+    ///
+    /// Also sets [secure](Self::secure), because clients require it.
+    pub fn with_partitioned(mut self) -> Self {
+        self.partitioned = true;
+        self.secure = true;
+        self
+    }
+}
+
+impl FromStr for SetCookie {
+    type Err = ParseError;
+
+    /// Lenient, as required by
+    /// [IETF RFC 6265 section 5.2](https://datatracker.ietf.org/doc/html/rfc6265#section-5.2):
+    /// unknown and malformed attributes are ignored.
+    fn from_str(representation: &str) -> Result<Self, Self::Err> {
+        let mut attributes = representation.split(';');
+
+        let (name, value) = match attributes.next().and_then(|pair| pair.split_once('=')) {
+            Some((name, value)) if !name.trim().is_empty() => (name.trim(), value.trim()),
+            _ => return Err(format!("malformed Set-Cookie: {}", representation).into()),
+        };
+
+        let mut set_cookie = Self::new_unchecked(name.into(), value.into());
+
+        for attribute in attributes {
+            let (attribute_name, attribute_value) = match attribute.split_once('=') {
+                Some((attribute_name, attribute_value)) => (attribute_name.trim(), attribute_value.trim()),
+                None => (attribute.trim(), ""),
+            };
+
+            match attribute_name.to_lowercase().as_str() {
+                "expires" => {
+                    if let Ok(expires) = attribute_value.parse() {
+                        set_cookie.expires = Some(expires);
+                    }
+                }
+
+                "max-age" => {
+                    if let Ok(max_age) = attribute_value.parse::<i64>() {
+                        set_cookie.max_age = Some(Duration::from_secs(max_age.max(0) as u64));
+                    }
+                }
+
+                "domain" => {
+                    let domain = attribute_value.trim_start_matches('.');
+                    if !domain.is_empty() {
+                        set_cookie.domain = Some(domain.to_lowercase().into());
+                    }
+                }
+
+                "path" if attribute_value.starts_with('/') => set_cookie.path = Some(attribute_value.into()),
+
+                "secure" => set_cookie.secure = true,
+
+                "httponly" => set_cookie.http_only = true,
+
+                "samesite" => {
+                    if let Ok(same_site) = attribute_value.parse() {
+                        set_cookie.same_site = Some(same_site);
+                    }
+                }
+
+                "partitioned" => set_cookie.partitioned = true,
+
+                _ => {}
+            }
+        }
+
+        Ok(set_cookie)
+    }
+}
+
+impl fmt::Display for SetCookie {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}={}", self.name, self.value)?;
+
+        if let Some(expires) = &self.expires {
+            write!(formatter, "; Expires={}", expires)?;
+        }
+
+        if let Some(max_age) = &self.max_age {
+            write!(formatter, "; Max-Age={}", max_age.as_secs())?;
+        }
+
+        if let Some(domain) = &self.domain {
+            write!(formatter, "; Domain={}", domain)?;
+        }
+
+        if let Some(path) = &self.path {
+            write!(formatter, "; Path={}", path)?;
+        }
+
+        if self.secure {
+            formatter.write_str("; Secure")?;
+        }
+
+        if self.http_only {
+            formatter.write_str("; HttpOnly")?;
+        }
+
+        if let Some(same_site) = &self.same_site {
+            write!(formatter, "; SameSite={}", same_site)?;
+        }
+
+        if self.partitioned {
+            formatter.write_str("; Partitioned")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl TryFrom<SetCookie> for HeaderValue {
+    type Error = ParseError;
+
+    /// Fails if [SetCookie::validate] fails.
+    fn try_from(set_cookie: SetCookie) -> Result<Self, Self::Error> {
+        set_cookie.validate()?;
+        HeaderValue::try_from(set_cookie.to_string()).map_err(|error| error.to_string().into())
+    }
+}
+
+//
+// SameSite
+//
+
+/// [SetCookie] same site policy.
+///
+/// See [IETF draft-ietf-httpbis-rfc6265bis section 4.1.2.7](https://datatracker.ietf.org/doc/html/draft-ietf-httpbis-rfc6265bis#section-4.1.2.7).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SameSite {
+    /// Strict.
+    Strict,
+
+    /// Lax.
+    Lax,
+
+    /// None.
+    None,
+}
+
+impl FromStr for SameSite {
+    type Err = ParseError;
+
+    fn from_str(representation: &str) -> Result<Self, Self::Err> {
+        if representation.eq_ignore_ascii_case("Strict") {
+            Ok(Self::Strict)
+        } else if representation.eq_ignore_ascii_case("Lax") {
+            Ok(Self::Lax)
+        } else if representation.eq_ignore_ascii_case("None") {
+            Ok(Self::None)
+        } else {
+            Err(format!("unsupported SameSite: {}", representation).into())
+        }
+    }
+}
+
+impl fmt::Display for SameSite {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::Strict => "Strict",
+            Self::Lax => "Lax",
+            Self::None => "None",
+        })
+    }
+}
+
+// https://datatracker.ietf.org/doc/html/rfc6265#section-4.1.1
+fn validate_name(name: &str) -> Result<(), ParseError> {
+    if is_token(name) { Ok(()) } else { Err(format!("invalid cookie name: {:?}", name).into()) }
+}
+
+// https://datatracker.ietf.org/doc/html/rfc6265#section-4.1.1
+fn validate_value(value: &str) -> Result<(), ParseError> {
+    let octets = value.strip_prefix('"').and_then(|value| value.strip_suffix('"')).unwrap_or(value);
+    if octets.bytes().all(|byte| matches!(byte, 0x21 | 0x23..=0x2b | 0x2d..=0x3a | 0x3c..=0x5b | 0x5d..=0x7e)) {
+        Ok(())
+    } else {
+        Err(format!("invalid cookie value: {:?}", value).into())
+    }
+}
+
+// https://datatracker.ietf.org/doc/html/rfc6265#section-4.1.1
+fn validate_attribute_value(name: &str, value: &str) -> Result<(), ParseError> {
+    if value.chars().all(|c| (' '..='~').contains(&c) && (c != ';')) {
+        Ok(())
+    } else {
+        Err(format!("invalid cookie {}: {:?}", name, value).into())
+    }
+}
@@ -8,9 +8,23 @@ pub const XX_CACHE: HeaderName = HeaderName::from_static("xx-cache");
 /// `XX-Cache-Duration` HTTP response header specifying the cache duration in seconds.
 pub const XX_CACHE_DURATION: HeaderName = HeaderName::from_static("xx-cache-duration");
 
+/// `XX-Cache-Tags` HTTP response header specifying comma-separated tags for the cache entry.
+pub const XX_CACHE_TAGS: HeaderName = HeaderName::from_static("xx-cache-tags");
+
 /// `XX-Encode` HTTP response header specifying whether to encode the response.
 pub const XX_ENCODE: HeaderName = HeaderName::from_static("xx-encode");
 
+/// `X-Cache` HTTP response header.
+///
+/// Non-standard, but commonly used by caches to report "HIT" or "MISS".
+pub const X_CACHE: HeaderName = HeaderName::from_static("x-cache");
+
+/// `X-Accel-Buffering` HTTP response header.
+///
+/// Non-standard, but commonly used (originally by nginx) to disable buffering with "no", e.g. for
+/// streaming responses.
+pub const X_ACCEL_BUFFERING: HeaderName = HeaderName::from_static("x-accel-buffering");
+
 /// `Content-Digest` HTTP response header.
 ///
 /// See [MDN](https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Headers/Content-Digest).
@@ -30,8 +44,18 @@ pub trait CustomHeaderValues {
     /// Parse `XX-Cache-Duration` response header value.
     fn xx_cache_duration(&self) -> Option<Duration>;
 
+    /// Parse `XX-Cache-Tags` response header values.
+    ///
+    /// Tags are comma-separated and can be split across several headers. Empty tags are skipped.
+    fn xx_cache_tags(&self) -> Vec<&str>;
+
     /// Parse `XX-Encode` response header value.
     fn xx_encode(&self, default: bool) -> bool;
+
+    /// Parse `X-Accel-Buffering` response header value.
+    ///
+    /// Expects "yes" or "no" (case-insensitive).
+    fn x_accel_buffering(&self, default: bool) -> bool;
 }
 
 impl CustomHeaderValues for HeaderMap {
@@ -43,7 +67,24 @@ impl CustomHeaderValues for HeaderMap {
         self.duration_value(XX_CACHE_DURATION)
     }
 
+    fn xx_cache_tags(&self) -> Vec<&str> {
+        self.string_values(XX_CACHE_TAGS)
+            .into_iter()
+            .flat_map(|tags| tags.split(","))
+            .map(|tag| tag.trim())
+            .filter(|tag| !tag.is_empty())
+            .collect()
+    }
+
     fn xx_encode(&self, default: bool) -> bool {
         self.bool_value(XX_ENCODE, default)
     }
+
+    fn x_accel_buffering(&self, default: bool) -> bool {
+        match self.string_value(X_ACCEL_BUFFERING) {
+            Some(value) if value.eq_ignore_ascii_case("yes") => true,
+            Some(value) if value.eq_ignore_ascii_case("no") => false,
+            _ => default,
+        }
+    }
 }
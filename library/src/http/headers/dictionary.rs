@@ -0,0 +1,130 @@
+use super::{
+    super::super::{std::string::*, transcoding::*},
+    structured::*,
+};
+
+use {
+    http::*,
+    std::{fmt, result::Result},
+};
+
+/// `Available-Dictionary` HTTP request header.
+///
+/// See [IETF RFC 9842](https://datatracker.ietf.org/doc/rfc9842/).
+pub const AVAILABLE_DICTIONARY: HeaderName = HeaderName::from_static("available-dictionary");
+
+/// `Dictionary-ID` HTTP request header.
+///
+/// See [IETF RFC 9842](https://datatracker.ietf.org/doc/rfc9842/).
+pub const DICTIONARY_ID: HeaderName = HeaderName::from_static("dictionary-id");
+
+/// `Use-As-Dictionary` HTTP response header.
+///
+/// See [IETF RFC 9842](https://datatracker.ietf.org/doc/rfc9842/).
+pub const USE_AS_DICTIONARY: HeaderName = HeaderName::from_static("use-as-dictionary");
+
+//
+// DictionaryHeaderValues
+//
+
+/// Access compression dictionary header values.
+pub trait DictionaryHeaderValues {
+    /// Parse `Available-Dictionary` request header value.
+    ///
+    /// It's a structured field byte sequence (base64 between colons) of the SHA-256 hash.
+    ///
+    /// [None] could mean that there is no such header *or* that it is malformed.
+    fn available_dictionary(&self) -> Option<DictionaryHash>;
+
+    /// Whether we have a `Use-As-Dictionary` response header.
+    fn has_use_as_dictionary(&self) -> bool;
+}
+
+impl DictionaryHeaderValues for HeaderMap {
+    fn available_dictionary(&self) -> Option<DictionaryHash> {
+        match self.structured_item(AVAILABLE_DICTIONARY)?.bare_item {
+            BareItem::ByteSequence(hash) => Some(DictionaryHash(hash.try_into().ok()?)),
+            _ => None,
+        }
+    }
+
+    fn has_use_as_dictionary(&self) -> bool {
+        self.contains_key(USE_AS_DICTIONARY)
+    }
+}
+
+//
+// UseAsDictionary
+//
+
+/// `Use-As-Dictionary` HTTP response header value.
+///
+/// Tells the client to store the response as a dictionary for future requests with URLs that
+/// match the pattern.
+#[derive(Clone, Debug)]
+pub struct UseAsDictionary {
+    /// URL pattern (relative to the response URL).
+    pub match_pattern: String,
+
+    /// Request destinations (e.g. "script"). Empty means all.
+    pub match_destinations: Vec<String>,
+
+    /// ID to be sent back in the `Dictionary-ID` request header.
+    pub id: Option<String>,
+}
+
+impl UseAsDictionary {
+    /// Constructor.
+    pub fn new(match_pattern: String) -> Self {
+        Self { match_pattern, match_destinations: Default::default(), id: None }
+    }
+
+    /// With a request destination.
+    pub fn with_match_destination(mut self, destination: String) -> Self {
+        self.match_destinations.push(destination);
+        self
+    }
+
+    /// With an ID.
+    pub fn with_id(mut self, id: String) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// To structured field dictionary.
+    pub fn to_structured_dictionary(&self) -> StructuredDictionary {
+        let mut dictionary = StructuredDictionary::default()
+            .with_member("match".into(), StructuredItem::new(BareItem::String(self.match_pattern.clone())).into());
+
+        if !self.match_destinations.is_empty() {
+            let destinations = self
+                .match_destinations
+                .iter()
+                .map(|destination| StructuredItem::new(BareItem::String(destination.clone())))
+                .collect();
+            dictionary = dictionary.with_member("match-dest".into(), InnerList::new(destinations).into());
+        }
+
+        if let Some(id) = &self.id {
+            dictionary = dictionary.with_member("id".into(), StructuredItem::new(BareItem::String(id.clone())).into());
+        }
+
+        dictionary
+    }
+}
+
+impl fmt::Display for UseAsDictionary {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_structured_dictionary(), formatter)
+    }
+}
+
+impl TryFrom<UseAsDictionary> for HeaderValue {
+    type Error = ParseError;
+
+    /// Fails if the strings are not printable ASCII.
+    fn try_from(use_as_dictionary: UseAsDictionary) -> Result<Self, Self::Error> {
+        let serialized = use_as_dictionary.to_structured_dictionary().serialize()?;
+        HeaderValue::try_from(serialized).map_err(|error| error.to_string().into())
+    }
+}
@@ -38,6 +38,21 @@ pub enum EncodingHeaderValue {
     /// Zstandard.
     #[strings("zstd")]
     Zstandard,
+
+    /// Brotli with a dictionary.
+    #[strings("dcb")]
+    DictionaryBrotli,
+
+    /// Zstandard with a dictionary.
+    #[strings("dcz")]
+    DictionaryZstandard,
+}
+
+impl EncodingHeaderValue {
+    /// Whether this is an encoding with a dictionary.
+    pub fn is_dictionary(&self) -> bool {
+        matches!(self, Self::DictionaryBrotli | Self::DictionaryZstandard)
+    }
 }
 
 impl From<Encoding> for EncodingHeaderValue {
@@ -48,11 +63,15 @@ impl From<Encoding> for EncodingHeaderValue {
             Encoding::Deflate => Self::Deflate,
             Encoding::GZip => Self::GZip,
             Encoding::Zstandard => Self::Zstandard,
+            Encoding::DictionaryBrotli(_) => Self::DictionaryBrotli,
+            Encoding::DictionaryZstandard(_) => Self::DictionaryZstandard,
         }
     }
 }
 
 impl Into<Encoding> for EncodingHeaderValue {
+    /// Note that for encodings with a dictionary the [DictionaryHash] is unknown here (it's in the
+    /// encoded header), so we use the default.
     fn into(self) -> Encoding {
         match self {
             Self::Identity => Encoding::Identity,
@@ -60,6 +79,8 @@ impl Into<Encoding> for EncodingHeaderValue {
             Self::Deflate => Encoding::Deflate,
             Self::GZip => Encoding::GZip,
             Self::Zstandard => Encoding::Zstandard,
+            Self::DictionaryBrotli => Encoding::DictionaryBrotli(Default::default()),
+            Self::DictionaryZstandard => Encoding::DictionaryZstandard(Default::default()),
         }
     }
 }
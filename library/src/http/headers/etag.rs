@@ -28,6 +28,24 @@ impl ETag {
         Self { tag, weak }
     }
 
+    /// Strong comparison.
+    ///
+    /// Both tags must be strong and identical.
+    ///
+    /// See [IETF RFC 9110 section 8.8.3.2](https://datatracker.ietf.org/doc/html/rfc9110#section-8.8.3.2).
+    pub fn strong_eq(&self, other: &Self) -> bool {
+        !self.weak && !other.weak && (self.tag == other.tag)
+    }
+
+    /// Weak comparison.
+    ///
+    /// The tags must be identical, whether or not they are weak.
+    ///
+    /// See [IETF RFC 9110 section 8.8.3.2](https://datatracker.ietf.org/doc/html/rfc9110#section-8.8.3.2).
+    pub fn weak_eq(&self, other: &Self) -> bool {
+        self.tag == other.tag
+    }
+
     /// Parse list.
     pub fn parse_list(representation: &str) -> Option<Vec<Self>> {
         let tags: Vec<_> = representation.split(",").map(|tag| tag.parse()).flatten().collect();
@@ -107,21 +125,41 @@ impl ETagMatcher {
     /// Whether any one of our tags matches the reference.
     ///
     /// [Any](Selector::Any) will always match. Weak tags will *never* match.
+    ///
+    /// Same as [matches_strong](Self::matches_strong).
     pub fn matches(&self, reference: Option<&ETag>) -> bool {
-        return match &self.0 {
+        self.matches_strong(reference)
+    }
+
+    /// Whether any one of our tags matches the reference using strong comparison.
+    ///
+    /// [Any](Selector::Any) will always match. Weak tags will *never* match.
+    ///
+    /// Used for `If-Match`.
+    pub fn matches_strong(&self, reference: Option<&ETag>) -> bool {
+        self.matches_with(reference, ETag::strong_eq)
+    }
+
+    /// Whether any one of our tags matches the reference using weak comparison.
+    ///
+    /// [Any](Selector::Any) will always match.
+    ///
+    /// Used for `If-None-Match`.
+    pub fn matches_weak(&self, reference: Option<&ETag>) -> bool {
+        self.matches_with(reference, ETag::weak_eq)
+    }
+
+    fn matches_with<ComparatorT>(&self, reference: Option<&ETag>, comparator: ComparatorT) -> bool
+    where
+        ComparatorT: Fn(&ETag, &ETag) -> bool,
+    {
+        match &self.0 {
             Selector::Any => true,
 
             Selector::Specific(selector) => {
-                if let Some(reference) = reference
-                    && !reference.weak
-                    && selector.0.contains(&reference)
-                {
-                    return true;
-                }
-
-                false
+                reference.is_some_and(|reference| selector.0.iter().any(|tag| comparator(tag, reference)))
             }
-        };
+        }
     }
 }
 
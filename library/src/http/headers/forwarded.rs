@@ -0,0 +1,212 @@
+use super::{super::super::std::string::*, headers::*, quoted::*};
+
+use {
+    http::header::*,
+    std::{net::*, result::Result, str::*},
+};
+
+/// `X-Forwarded-For` HTTP request header.
+pub const X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+
+/// `X-Forwarded-Host` HTTP request header.
+pub const X_FORWARDED_HOST: HeaderName = HeaderName::from_static("x-forwarded-host");
+
+/// `X-Forwarded-Proto` HTTP request header.
+pub const X_FORWARDED_PROTO: HeaderName = HeaderName::from_static("x-forwarded-proto");
+
+//
+// ForwardedHeaderValues
+//
+
+/// Access forwarding header values.
+///
+/// Note that these headers can be set by anybody. Only trust them if they were set by a trusted
+/// proxy. See [EffectiveOrigin](crate::http::EffectiveOrigin).
+pub trait ForwardedHeaderValues {
+    /// Parse the [`Forwarded`](FORWARDED) request header values.
+    ///
+    /// Multiple header values are combined. Malformed elements are skipped.
+    fn forwarded(&self) -> Vec<ForwardedElement>;
+
+    /// Parse the `X-Forwarded-For` request header values.
+    ///
+    /// Multiple header values are combined. Malformed nodes are returned as
+    /// [Unknown](ForwardedNode::Unknown) in order to preserve their position.
+    fn x_forwarded_for(&self) -> Vec<ForwardedNode>;
+
+    /// The `X-Forwarded-Host` request header values.
+    ///
+    /// Multiple header values are combined.
+    fn x_forwarded_host(&self) -> Vec<&str>;
+
+    /// The `X-Forwarded-Proto` request header values.
+    ///
+    /// Multiple header values are combined.
+    fn x_forwarded_proto(&self) -> Vec<&str>;
+}
+
+impl ForwardedHeaderValues for HeaderMap {
+    fn forwarded(&self) -> Vec<ForwardedElement> {
+        self.string_values(FORWARDED)
+            .into_iter()
+            .flat_map(|value| split_unquoted(value, ','))
+            .filter_map(|element| match element.parse() {
+                Ok(element) => Some(element),
+
+                Err(error) => {
+                    tracing::warn!("malformed Forwarded element: {}", error);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn x_forwarded_for(&self) -> Vec<ForwardedNode> {
+        comma_separated(self, X_FORWARDED_FOR).map(|node| node.parse().unwrap_or(ForwardedNode::Unknown)).collect()
+    }
+
+    fn x_forwarded_host(&self) -> Vec<&str> {
+        comma_separated(self, X_FORWARDED_HOST).collect()
+    }
+
+    fn x_forwarded_proto(&self) -> Vec<&str> {
+        comma_separated(self, X_FORWARDED_PROTO).collect()
+    }
+}
+
+//
+// ForwardedElement
+//
+
+/// Element of the `Forwarded` HTTP request header value.
+///
+/// Each proxy appends an element describing the request that *it* received.
+///
+/// See [IETF RFC 7239](https://datatracker.ietf.org/doc/html/rfc7239).
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ForwardedElement {
+    /// `by` parameter: the interface where the request came in to the proxy.
+    pub by: Option<ForwardedNode>,
+
+    /// `for` parameter: the client that sent the request to the proxy.
+    pub for_node: Option<ForwardedNode>,
+
+    /// `host` parameter: the `Host` request header as received by the proxy.
+    pub host: Option<String>,
+
+    /// `proto` parameter: the scheme used to make the request to the proxy.
+    pub proto: Option<String>,
+}
+
+impl FromStr for ForwardedElement {
+    type Err = ParseError;
+
+    fn from_str(representation: &str) -> Result<Self, Self::Err> {
+        let mut element = Self::default();
+
+        for pair in split_unquoted(representation, ';') {
+            let pair = pair.trim();
+            if pair.is_empty() {
+                continue;
+            }
+
+            let Some((name, value)) = pair.split_once('=') else {
+                return Err(format!("missing '=': {}", pair).into());
+            };
+
+            let value = unquote(value.trim());
+            match name.trim().to_lowercase().as_str() {
+                "by" => element.by = Some(value.parse()?),
+                "for" => element.for_node = Some(value.parse()?),
+                "host" => element.host = Some(value),
+                "proto" => element.proto = Some(value),
+                _ => {}
+            }
+        }
+
+        Ok(element)
+    }
+}
+
+//
+// ForwardedNode
+//
+
+/// Node in the `Forwarded` and `X-Forwarded-For` HTTP request header values.
+///
+/// See [IETF RFC 7239 section 6](https://datatracker.ietf.org/doc/html/rfc7239#section-6).
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ForwardedNode {
+    /// IP address with optional port.
+    Address(IpAddr, Option<u16>),
+
+    /// Obfuscated identifier (starts with "_").
+    Obfuscated(String),
+
+    /// Unknown.
+    Unknown,
+}
+
+impl ForwardedNode {
+    /// IP address.
+    pub fn address(&self) -> Option<IpAddr> {
+        match self {
+            Self::Address(address, _) => Some(*address),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for ForwardedNode {
+    type Err = ParseError;
+
+    fn from_str(representation: &str) -> Result<Self, Self::Err> {
+        let representation = representation.trim();
+
+        if representation.eq_ignore_ascii_case("unknown") {
+            return Ok(Self::Unknown);
+        }
+
+        if representation.starts_with('_') {
+            return Ok(Self::Obfuscated(representation.into()));
+        }
+
+        // "[IPv6]" with optional ":port"
+        if let Some(representation) = representation.strip_prefix('[') {
+            let Some((address, port)) = representation.split_once(']') else {
+                return Err("missing ']'".into());
+            };
+
+            let address: Ipv6Addr = address.parse().map_err(|_| ParseError::from("malformed IPv6 address"))?;
+            return Ok(Self::Address(address.into(), parse_port(port.strip_prefix(':'))));
+        }
+
+        // Bare IPv6 (common in `X-Forwarded-For`) or IPv4
+        if let Ok(address) = representation.parse() {
+            return Ok(Self::Address(address, None));
+        }
+
+        // IPv4 with ":port"
+        if let Some((address, port)) = representation.split_once(':')
+            && let Ok(address) = address.parse::<Ipv4Addr>()
+        {
+            return Ok(Self::Address(address.into(), parse_port(Some(port))));
+        }
+
+        Err(format!("malformed node: {}", representation).into())
+    }
+}
+
+// Obfuscated ports are ignored
+fn parse_port(port: Option<&str>) -> Option<u16> {
+    port.and_then(|port| port.parse().ok())
+}
+
+fn comma_separated(headers: &HeaderMap, name: HeaderName) -> impl Iterator<Item = &str> {
+    headers
+        .string_values(name)
+        .into_iter()
+        .flat_map(|value| value.split(','))
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+}
@@ -2,8 +2,13 @@ use super::{
     super::super::std::{
         collections::*,
         immutable::{Bytes, *},
+        string::*,
     },
+    authorization::*,
     bool::*,
+    cache_control::*,
+    conditional::*,
+    cookie::*,
     encoding::*,
     etag::*,
     into::*,
@@ -172,8 +177,41 @@ pub trait HeaderValues {
         self.set_into_header_value(name, if value { TRUE_HEADER_VALUE } else { FALSE_HEADER_VALUE });
     }
 
+    /// Set the [`Cache-Control`](CACHE_CONTROL) header value.
+    ///
+    /// Makes sure to remove existing values first. If there are no directives the header will
+    /// only be removed.
+    fn set_cache_control(&mut self, cache_control: CacheControl);
+
+    /// Set the [`WWW-Authenticate`](WWW_AUTHENTICATE) header values.
+    ///
+    /// Makes sure to remove existing values first. Each challenge gets its own header value. Fails
+    /// (without changing anything) if a challenge contains control characters.
+    fn set_www_authenticate(&mut self, challenges: Vec<Challenge>) -> Result<(), ParseError>;
+
+    /// Add a [`Set-Cookie`](SET_COOKIE) header value.
+    ///
+    /// Existing values are kept, because each cookie gets its own header value. Fails if the
+    /// cookie is invalid (see [SetCookie::validate]).
+    fn add_set_cookie(&mut self, set_cookie: SetCookie) -> Result<(), ParseError>;
+
+    /// Add header names to the [`Vary`](VARY) header value (if they are not already there).
+    ///
+    /// Existing names are kept and merged into a single value. Does nothing if the value is "*".
+    fn add_vary(&mut self, names: &[HeaderName]);
+
     // Request and response headers
 
+    /// Parse the [`Cache-Control`](CACHE_CONTROL) header values.
+    ///
+    /// Multiple header values are combined.
+    ///
+    /// [None] means that there is no such header.
+    fn cache_control(&self) -> Option<CacheControl> {
+        let values = self.string_values(CACHE_CONTROL);
+        if !values.is_empty() { values.join(",").parse().ok() } else { None }
+    }
+
     /// Parse the [`Content-Length`](CONTENT_LENGTH) header value.
     ///
     /// [None] could mean that there is no such header *or* that it is malformed.
@@ -241,6 +279,20 @@ pub trait HeaderValues {
         self.parse_value(IF_MATCH)
     }
 
+    /// Parse the [`If-Range`](IF_RANGE) request header value.
+    ///
+    /// [None] could mean that there is no such header *or* that it is malformed.
+    fn if_range(&self) -> Option<IfRange> {
+        self.parse_value(IF_RANGE)
+    }
+
+    /// Parse the [`Authorization`](AUTHORIZATION) request header value.
+    ///
+    /// [None] could mean that there is no such header *or* that it is malformed.
+    fn authorization(&self) -> Option<Credentials> {
+        self.parse_value(AUTHORIZATION)
+    }
+
     /// Parse the [`Authorization`](AUTHORIZATION) request header value for the `Basic` scheme.
     ///
     /// Expects UTF-8 strings.
@@ -249,19 +301,49 @@ pub trait HeaderValues {
     ///
     /// [None] could mean that there is no such header *or* that it is malformed.
     fn authorization_basic(&self) -> Option<(String, String)> {
-        if let Some(authorization) = self.string_value(AUTHORIZATION)
-            && authorization.starts_with("Basic ")
-        {
-            let authorization = &authorization[6..];
-            if let Ok(authorization) = base64_simd::STANDARD.decode_to_vec(authorization)
-                && let Ok(authorization) = str::from_utf8(&authorization)
-                && let Some((username, password)) = authorization.split_once(':')
-            {
-                return Some((username.into(), password.into()));
-            }
+        match self.authorization()? {
+            Credentials::Basic { username, password } => Some((username.into(), password.into())),
+            _ => None,
         }
+    }
 
-        None
+    /// Parse the [`Authorization`](AUTHORIZATION) request header value for the `Bearer` scheme.
+    ///
+    /// Returns the token.
+    ///
+    /// [None] could mean that there is no such header *or* that it is malformed.
+    fn authorization_bearer(&self) -> Option<ByteString> {
+        match self.authorization()? {
+            Credentials::Bearer(token) => Some(token),
+            _ => None,
+        }
+    }
+
+    /// Parse the [`Authorization`](AUTHORIZATION) request header value for the `Digest` scheme.
+    ///
+    /// [None] could mean that there is no such header *or* that it is malformed.
+    fn authorization_digest(&self) -> Option<DigestCredentials> {
+        match self.authorization()? {
+            Credentials::Digest(digest) => Some(*digest),
+            _ => None,
+        }
+    }
+
+    /// Parse the [`Cookie`](COOKIE) request header values.
+    ///
+    /// Multiple header values are combined. Malformed cookies are skipped.
+    fn cookies(&self) -> Cookies {
+        Cookies::parse(&self.string_values(COOKIE))
+    }
+
+    /// Whether the [`Connection`](CONNECTION) header includes the `upgrade` token.
+    ///
+    /// The token is case-insensitive and there can be more than one header.
+    fn connection_upgrade(&self) -> bool {
+        self.string_values(CONNECTION)
+            .into_iter()
+            .flat_map(|connection| connection.split(','))
+            .any(|token| token.trim().eq_ignore_ascii_case("upgrade"))
     }
 
     // Response headers
@@ -284,6 +366,13 @@ pub trait HeaderValues {
         Language::parse_list(self.string_value(CONTENT_LANGUAGE)?)
     }
 
+    /// Parse the [`Age`](AGE) response header value (in seconds).
+    ///
+    /// [None] could mean that there is no such header *or* that it is malformed.
+    fn age(&self) -> Option<Duration> {
+        self.parse_value(AGE).map(Duration::from_secs)
+    }
+
     /// Parse the [`Last-Modified`](LAST_MODIFIED) response header value.
     ///
     /// [None] mean that there is no such header *or* that it is malformed.
@@ -297,6 +386,23 @@ pub trait HeaderValues {
     fn etag(&self) -> Option<ETag> {
         self.parse_value(ETAG)
     }
+
+    /// Parse the [`Set-Cookie`](SET_COOKIE) response header values.
+    ///
+    /// Malformed values are skipped.
+    fn set_cookies(&self) -> Vec<SetCookie> {
+        self.string_values(SET_COOKIE)
+            .into_iter()
+            .filter_map(|set_cookie| match set_cookie.parse() {
+                Ok(set_cookie) => Some(set_cookie),
+
+                Err(error) => {
+                    tracing::warn!("{}", error);
+                    None
+                }
+            })
+            .collect()
+    }
 }
 
 impl HeaderValues for HeaderMap {
@@ -358,4 +464,52 @@ impl HeaderValues for HeaderMap {
         self.insert(name, HeaderValue::from_str(value)?);
         Ok(())
     }
+
+    fn set_cache_control(&mut self, cache_control: CacheControl) {
+        self.remove(CACHE_CONTROL);
+        if !cache_control.is_empty() {
+            self.insert(CACHE_CONTROL, cache_control.into_header_value());
+        }
+    }
+
+    fn set_www_authenticate(&mut self, challenges: Vec<Challenge>) -> Result<(), ParseError> {
+        let values: Vec<HeaderValue> = challenges.into_iter().map(TryInto::try_into).collect::<Result<_, _>>()?;
+
+        self.remove(WWW_AUTHENTICATE);
+        for value in values {
+            self.append(WWW_AUTHENTICATE, value);
+        }
+
+        Ok(())
+    }
+
+    fn add_set_cookie(&mut self, set_cookie: SetCookie) -> Result<(), ParseError> {
+        self.append(SET_COOKIE, set_cookie.try_into()?);
+        Ok(())
+    }
+
+    fn add_vary(&mut self, names: &[HeaderName]) {
+        let mut vary: Vec<_> = self
+            .string_values(VARY)
+            .into_iter()
+            .flat_map(|value| value.split(','))
+            .map(|name| name.trim().to_lowercase())
+            .filter(|name| !name.is_empty())
+            .collect();
+
+        if vary.iter().any(|name| name == "*") {
+            return;
+        }
+
+        let length = vary.len();
+        for name in names {
+            if !vary.iter().any(|existing| existing == name.as_str()) {
+                vary.push(name.as_str().into());
+            }
+        }
+
+        if vary.len() != length {
+            self.set_string_value(VARY, &vary.join(", ")).expect("Vary in HTTP header");
+        }
+    }
 }
@@ -1,11 +1,14 @@
-use super::super::{
-    super::std::{collections::*, foster::*},
-    cache::*,
+use super::{
+    super::{
+        super::std::{borrow::*, collections::*, foster::*, immutable::*},
+        cache::*,
+    },
+    preferences::*,
 };
 
 use {
     http::header::*,
-    std::{convert::*, fmt, str::*},
+    std::{cmp::*, convert::*, fmt, hash::*, slice, str::*},
 };
 
 //
@@ -16,21 +19,136 @@ use {
 ///
 /// See [IETF RFC 5646 section 2.1](https://datatracker.ietf.org/doc/html/rfc5646#section-2.1).
 ///
-/// Stored as a sequence of subtags.
+/// Stored as a sequence of subtags. Can also be used as a language range
+/// ([IETF RFC 4647 section 2](https://datatracker.ietf.org/doc/html/rfc4647#section-2)), in which
+/// case subtags can be the "*" wildcard.
 ///
-/// Note that even though ISO recommends cased representations, they are case-insensitive in HTTP.
-/// Thus we convert all subtags to lowercase for comparison efficiency.
+/// Parsed subtags are canonicalized according to
+/// [IETF RFC 5646 section 2.1.1](https://datatracker.ietf.org/doc/html/rfc5646#section-2.1.1):
+/// lowercase language, titlecase script ("Hant"), uppercase region ("US"), and lowercase for
+/// everything else, including everything after a singleton. However, since they are
+/// case-insensitive in HTTP, comparison and hashing are case-insensitive, so fostered subtags do
+/// not have to be canonical.
 #[derive(Clone, Debug)]
 pub struct Language(pub FosterByteStringVector);
 
-delegate_newtype_of_foster_byte_string_vector!(Language);
-
 impl Language {
+    /// Constructor.
+    pub fn new_owned(subtags: Vec<ByteString>) -> Self {
+        Self(Foster::new_owned(subtags))
+    }
+
+    /// Constructor.
+    pub const fn new_fostered(subtags: &'static [&'static str]) -> Self {
+        Self(Foster::new_fostered(subtags))
+    }
+
     /// Parse list.
     pub fn parse_list(representation: &str) -> Option<FastHashSet<Self>> {
         let languages: FastHashSet<_> = representation.split(",").map(|language| language.trim().into()).collect();
         if !languages.is_empty() { Some(languages) } else { None }
     }
+
+    /// Iterate subtags.
+    pub fn iter(&self) -> <&Self as IntoIterator>::IntoIter {
+        self.into_iter()
+    }
+
+    /// Canonical version.
+    pub fn canonical(&self) -> Self {
+        Self::new_owned(canonical_subtags(self.iter()))
+    }
+
+    /// Whether we are the "*" wildcard range.
+    pub fn is_wildcard(&self) -> bool {
+        let mut subtags = self.iter();
+        (subtags.next() == Some("*")) && subtags.next().is_none()
+    }
+
+    /// Whether we, as a basic language range, match a language tag.
+    ///
+    /// The "*" wildcard matches all tags. Otherwise we match if we are equal to the tag or to a
+    /// prefix of its subtags. For example, "en" matches "en" and "en-US" but not "eng".
+    ///
+    /// See [IETF RFC 4647 section 3.3.1](https://datatracker.ietf.org/doc/html/rfc4647#section-3.3.1).
+    pub fn matches_basic(&self, tag: &Self) -> bool {
+        if self.is_wildcard() {
+            return true;
+        }
+
+        let mut tag_subtags = tag.iter();
+        self.iter().all(|subtag| tag_subtags.next().is_some_and(|tag_subtag| subtag.eq_ignore_ascii_case(tag_subtag)))
+    }
+
+    /// Whether we, as an extended language range, match a language tag.
+    ///
+    /// Any of our subtags can be the "*" wildcard. Non-wildcard subtags must appear in the tag in
+    /// the same order, but the tag may have additional subtags in between (except for
+    /// singletons). For example, "de-*-DE" matches "de-DE", "de-Latn-DE", and "de-Latn-DE-1996".
+    ///
+    /// See [IETF RFC 4647 section 3.3.2](https://datatracker.ietf.org/doc/html/rfc4647#section-3.3.2).
+    pub fn matches_extended(&self, tag: &Self) -> bool {
+        let mut subtags = self.iter();
+        let mut tag_subtags = tag.iter().peekable();
+
+        match (subtags.next(), tag_subtags.next()) {
+            (Some(subtag), Some(tag_subtag)) if (subtag == "*") || subtag.eq_ignore_ascii_case(tag_subtag) => {}
+            _ => return false,
+        }
+
+        for subtag in subtags {
+            if subtag == "*" {
+                continue;
+            }
+
+            loop {
+                match tag_subtags.next() {
+                    Some(tag_subtag) if subtag.eq_ignore_ascii_case(tag_subtag) => break,
+                    Some(tag_subtag) if tag_subtag.len() == 1 => return false,
+                    Some(_) => {}
+                    None => return false,
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Truncated by one subtag, for lookup fallback.
+    ///
+    /// A trailing singleton is removed, too. For example, "zh-Hant-CN-x-private1" becomes
+    /// "zh-Hant-CN", which becomes "zh-Hant", which becomes "zh". [None] if there is nothing left.
+    ///
+    /// See [IETF RFC 4647 section 3.4](https://datatracker.ietf.org/doc/html/rfc4647#section-3.4).
+    pub fn truncated(&self) -> Option<Self> {
+        let mut subtags: Vec<_> = self.iter().collect();
+        subtags.pop();
+
+        if subtags.last().is_some_and(|subtag| subtag.len() == 1) {
+            subtags.pop();
+        }
+
+        if subtags.is_empty() {
+            None
+        } else {
+            Some(Self::new_owned(subtags.into_iter().map(|subtag| subtag.into()).collect()))
+        }
+    }
+}
+
+impl IntoOwned for Language {
+    fn into_owned(self) -> Self {
+        match self.0 {
+            Foster::Owned(_) => self,
+            Foster::Fostered(_) => Self(self.0.into_owned()),
+        }
+    }
+}
+
+impl HasLength for Language {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
 }
 
 impl CacheWeight for Language {
@@ -44,15 +162,82 @@ impl CacheWeight for Language {
     }
 }
 
+impl PartialEq for Language {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Language {}
+
+impl PartialOrd for Language {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Language {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let mut other_subtags = other.iter();
+        for subtag in self {
+            match other_subtags.next() {
+                Some(other_subtag) => match cmp_ignore_ascii_case(subtag, other_subtag) {
+                    Ordering::Equal => {}
+                    not_equal => return not_equal,
+                },
+
+                None => return Ordering::Greater,
+            }
+        }
+
+        if other_subtags.next().is_some() { Ordering::Less } else { Ordering::Equal }
+    }
+}
+
+impl Hash for Language {
+    fn hash<HasherT>(&self, state: &mut HasherT)
+    where
+        HasherT: Hasher,
+    {
+        for subtag in self {
+            for byte in subtag.bytes() {
+                state.write_u8(byte.to_ascii_lowercase());
+            }
+            state.write_u8(b'-');
+        }
+    }
+}
+
+impl<'own> IntoIterator for &'own Language {
+    type Item = &'own str;
+    type IntoIter = FosterIterator<
+        &'own str,
+        &'own ByteString,
+        &'own &'static str,
+        slice::Iter<'own, ByteString>,
+        slice::Iter<'own, &'static str>,
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
 impl Into<HeaderValue> for Language {
     fn into(self) -> HeaderValue {
         HeaderValue::from_str(&self.to_string()).expect("language in HTTP header")
     }
 }
 
+impl From<Vec<ByteString>> for Language {
+    fn from(subtags: Vec<ByteString>) -> Self {
+        Self::new_owned(subtags)
+    }
+}
+
 impl From<&str> for Language {
     fn from(representation: &str) -> Self {
-        Self::new_owned(representation.split("-").map(|subtag| subtag.to_lowercase().into()).collect())
+        Self::new_owned(canonical_subtags(representation.split("-")))
     }
 }
 
@@ -72,3 +257,183 @@ impl fmt::Display for Language {
         }
     }
 }
+
+//
+// Preferences<Language>
+//
+
+impl Preferences<Language> {
+    /// Select the most preferred language tag.
+    ///
+    /// For each language range, from highest weight to lowest, we first try
+    /// [lookup](Self::lookup), which falls back to shorter ranges (e.g. "de-CH" to "de"), and then
+    /// [basic filtering](Language::matches_basic), which accepts longer tags (e.g. "en" for
+    /// "en-US"). If there is a tie, we will go by the order of tags.
+    ///
+    /// Ranges with a zero weight exclude the tags they match.
+    pub fn best_language<'own>(&self, tags: &'own [Language]) -> Option<&'own Language> {
+        let excluded = self.excluded(tags);
+        let mut preferences = self.0.iter().filter(|preference| preference.weight > Weight::new(0)).peekable();
+
+        while let Some(preference) = preferences.next() {
+            let mut candidates = FastHashSet::default();
+            candidates.extend(select_language(&preference.selector, tags, &excluded));
+
+            // Preferences of equal weight (they would be right after us)
+            while let Some(tied) = preferences.next_if(|tied| tied.weight == preference.weight) {
+                candidates.extend(select_language(&tied.selector, tags, &excluded));
+            }
+
+            if let Some(tag) = tags.iter().find(|tag| candidates.contains(tag)) {
+                return Some(tag);
+            }
+        }
+
+        None
+    }
+
+    /// Basic filtering.
+    ///
+    /// Returns all tags that are matched by a language range with a non-zero weight, from the
+    /// highest weight to the lowest, and otherwise in the order of tags.
+    ///
+    /// See [IETF RFC 4647 section 3.3.1](https://datatracker.ietf.org/doc/html/rfc4647#section-3.3.1).
+    pub fn filter<'own>(&self, tags: &'own [Language]) -> Vec<&'own Language> {
+        self.filter_with(tags, Language::matches_basic)
+    }
+
+    /// Extended filtering.
+    ///
+    /// Like [filter](Self::filter) but with the language ranges used as extended language ranges.
+    ///
+    /// See [IETF RFC 4647 section 3.3.2](https://datatracker.ietf.org/doc/html/rfc4647#section-3.3.2).
+    pub fn filter_extended<'own>(&self, tags: &'own [Language]) -> Vec<&'own Language> {
+        self.filter_with(tags, Language::matches_extended)
+    }
+
+    /// Lookup.
+    ///
+    /// For each language range with a non-zero weight, from the highest weight to the lowest, we
+    /// look for an equal tag, progressively [truncating](Language::truncated) the range until we
+    /// find one. The "*" wildcard range is ignored.
+    ///
+    /// See [IETF RFC 4647 section 3.4](https://datatracker.ietf.org/doc/html/rfc4647#section-3.4).
+    pub fn lookup<'own>(&self, tags: &'own [Language]) -> Option<&'own Language> {
+        self.0.iter().filter(|preference| preference.weight > Weight::new(0)).find_map(|preference| {
+            match &preference.selector {
+                Selector::Any => None,
+                Selector::Specific(range) => lookup_language(range, tags),
+            }
+        })
+    }
+
+    fn filter_with<'own, MatchesT>(&self, tags: &'own [Language], matches: MatchesT) -> Vec<&'own Language>
+    where
+        MatchesT: Fn(&Language, &Language) -> bool,
+    {
+        let excluded = self.excluded(tags);
+        let mut filtered = Vec::new();
+
+        for preference in &self.0 {
+            if preference.weight == Weight::new(0) {
+                continue;
+            }
+
+            for tag in tags {
+                let matches = match &preference.selector {
+                    Selector::Any => true,
+                    Selector::Specific(range) => matches(range, tag),
+                };
+
+                if matches && !excluded.contains(tag) && !filtered.contains(&tag) {
+                    filtered.push(tag);
+                }
+            }
+        }
+
+        filtered
+    }
+
+    // Tags matched by a language range with a zero weight
+    fn excluded<'own>(&self, tags: &'own [Language]) -> FastHashSet<&'own Language> {
+        let mut excluded = FastHashSet::default();
+        for preference in &self.0 {
+            if preference.weight == Weight::new(0)
+                && let Selector::Specific(range) = &preference.selector
+            {
+                excluded.extend(tags.iter().filter(|tag| range.matches_basic(tag)));
+            }
+        }
+        excluded
+    }
+}
+
+fn select_language<'own>(
+    selector: &Selector<Language>,
+    tags: &'own [Language],
+    excluded: &FastHashSet<&'own Language>,
+) -> Option<&'own Language> {
+    let mut candidates = tags.iter().filter(|tag| !excluded.contains(tag));
+    match selector {
+        Selector::Any => candidates.next(),
+
+        Selector::Specific(range) => lookup_language(range, tags)
+            .filter(|tag| !excluded.contains(tag))
+            .or_else(|| candidates.find(|tag| range.matches_basic(tag))),
+    }
+}
+
+fn lookup_language<'own>(range: &Language, tags: &'own [Language]) -> Option<&'own Language> {
+    if range.is_wildcard() {
+        return None;
+    }
+
+    let mut range = Some(range.clone());
+    while let Some(current) = range {
+        if let Some(tag) = tags.iter().find(|tag| **tag == current) {
+            return Some(tag);
+        }
+        range = current.truncated();
+    }
+
+    None
+}
+
+fn canonical_subtags<'own, IteratorT>(subtags: IteratorT) -> Vec<ByteString>
+where
+    IteratorT: Iterator<Item = &'own str>,
+{
+    let mut after_singleton = false;
+
+    subtags
+        .enumerate()
+        .map(|(index, subtag)| {
+            let subtag = subtag.trim();
+
+            // Language, singletons, and everything after a singleton remain lowercase
+            let canonical = if (index == 0) || after_singleton || (subtag.len() == 1) {
+                subtag.to_ascii_lowercase()
+            } else if (subtag.len() == 2) && subtag.chars().all(|c| c.is_ascii_alphabetic()) {
+                // Region
+                subtag.to_ascii_uppercase()
+            } else if (subtag.len() == 4) && subtag.chars().all(|c| c.is_ascii_alphabetic()) {
+                // Script
+                let mut script = subtag.to_ascii_lowercase();
+                script[..1].make_ascii_uppercase();
+                script
+            } else {
+                subtag.to_ascii_lowercase()
+            };
+
+            if subtag.len() == 1 {
+                after_singleton = true;
+            }
+
+            canonical.into()
+        })
+        .collect()
+}
+
+fn cmp_ignore_ascii_case(a: &str, b: &str) -> Ordering {
+    a.bytes().map(|byte| byte.to_ascii_lowercase()).cmp(b.bytes().map(|byte| byte.to_ascii_lowercase()))
+}
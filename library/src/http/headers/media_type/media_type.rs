@@ -1,8 +1,12 @@
 use super::{
-    super::super::{
-        super::std::{borrow::*, foster::*, string::*},
-        cache::*,
+    super::{
+        super::{
+            super::std::{borrow::*, string::*},
+            cache::*,
+        },
+        quoted::*,
     },
+    parameters::*,
     segment::*,
 };
 
@@ -16,19 +20,34 @@ use {
 //
 
 /// Media type value in HTTP headers.
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+///
+/// The main and subtype segments are compared case-insensitively. See
+/// [MediaTypeParameters] for how parameters are compared.
+#[derive(Clone, Debug)]
 pub struct MediaType {
     /// Main segment.
     pub main: MediaTypeSegment,
 
     /// Subtype segment.
     pub subtype: MediaTypeSegment,
+
+    /// Parameters.
+    pub parameters: MediaTypeParameters,
 }
 
 impl MediaType {
     /// Constructor.
     pub const fn new(main: MediaTypeSegment, subtype: MediaTypeSegment) -> Self {
-        Self { main, subtype }
+        Self::new_with_parameters(main, subtype, MediaTypeParameters::NONE)
+    }
+
+    /// Constructor.
+    pub const fn new_with_parameters(
+        main: MediaTypeSegment,
+        subtype: MediaTypeSegment,
+        parameters: MediaTypeParameters,
+    ) -> Self {
+        Self { main, subtype, parameters }
     }
 
     /// Constructor.
@@ -40,42 +59,62 @@ impl MediaType {
     pub const fn new_fostered(main: &'static str, subtype: &'static str) -> Self {
         Self::new(MediaTypeSegment::new_fostered(main), MediaTypeSegment::new_fostered(subtype))
     }
+
+    /// Constructor.
+    pub const fn new_fostered_with_parameters(
+        main: &'static str,
+        subtype: &'static str,
+        parameters: &'static [(&'static str, &'static str)],
+    ) -> Self {
+        Self::new_with_parameters(
+            MediaTypeSegment::new_fostered(main),
+            MediaTypeSegment::new_fostered(subtype),
+            MediaTypeParameters::new_fostered(parameters),
+        )
+    }
+
+    /// The `charset` parameter.
+    pub fn charset(&self) -> Option<&str> {
+        self.parameters.get("charset")
+    }
+
+    /// Without parameters.
+    pub fn essence(&self) -> Self {
+        Self::new(self.main.clone(), self.subtype.clone())
+    }
 }
 
 impl IntoOwned for MediaType {
     fn into_owned(self) -> Self {
-        match self.main {
-            MediaTypeSegment(Foster::Owned(_)) => match self.subtype {
-                MediaTypeSegment(Foster::Owned(_)) => {
-                    // Both main and subtype are already owned
-                    self
-                }
-
-                MediaTypeSegment(Foster::Fostered(subtype)) => {
-                    // Main is owned, subtype isn't
-                    Self::new(self.main, subtype.into())
-                }
-            },
-
-            MediaTypeSegment(Foster::Fostered(main)) => match self.subtype {
-                MediaTypeSegment(Foster::Owned(_)) => {
-                    // Subtype is owned, main isn't
-                    Self::new(main.into(), self.subtype)
-                }
-
-                MediaTypeSegment(Foster::Fostered(subtype)) => {
-                    // Both are not owned
-                    Self::new_owned(main.into(), subtype.into())
-                }
-            },
-        }
+        Self::new_with_parameters(self.main.into_owned(), self.subtype.into_owned(), self.parameters.into_owned())
     }
 }
 
 impl CacheWeight for MediaType {
     fn cache_weight(&self) -> usize {
         const SELF_SIZE: usize = size_of::<MediaType>();
-        SELF_SIZE + self.main.cache_weight() + self.subtype.cache_weight()
+        SELF_SIZE + self.main.cache_weight() + self.subtype.cache_weight() + self.parameters.cache_weight()
+    }
+}
+
+impl PartialEq for MediaType {
+    fn eq(&self, other: &Self) -> bool {
+        self.main.as_ref().eq_ignore_ascii_case(other.main.as_ref())
+            && self.subtype.as_ref().eq_ignore_ascii_case(other.subtype.as_ref())
+            && (self.parameters == other.parameters)
+    }
+}
+
+impl Eq for MediaType {}
+
+impl Hash for MediaType {
+    fn hash<HasherT>(&self, state: &mut HasherT)
+    where
+        HasherT: Hasher,
+    {
+        hash_lowercase(self.main.as_ref(), state);
+        hash_lowercase(self.subtype.as_ref(), state);
+        self.parameters.hash(state);
     }
 }
 
@@ -89,15 +128,19 @@ impl FromStr for MediaType {
     type Err = ParseError;
 
     fn from_str(representation: &str) -> Result<Self, Self::Err> {
-        let (main, subtype) = representation.split_once("/").ok_or_else(|| "missing '/'")?;
-        let Ok(main) = main.parse();
-        let Ok(subtype) = subtype.parse();
-        Ok(Self::new(main, subtype))
+        let mut split = split_unquoted(representation, ';').into_iter();
+        let essence = split.next().expect("split not empty");
+
+        let (main, subtype) = essence.split_once("/").ok_or_else(|| "missing '/'")?;
+        let Ok(main) = main.trim().parse();
+        let Ok(subtype) = subtype.trim().parse();
+
+        Ok(Self::new_with_parameters(main, subtype, parse_parameters(split)))
     }
 }
 
 impl fmt::Display for MediaType {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(formatter, "{}/{}", self.main, self.subtype)
+        write!(formatter, "{}/{}{}", self.main, self.subtype, self.parameters)
     }
 }
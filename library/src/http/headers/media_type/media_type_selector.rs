@@ -5,8 +5,10 @@ use super::{
             cache::*,
         },
         preferences::*,
+        quoted::*,
     },
     media_type::*,
+    parameters::*,
     segment::*,
 };
 
@@ -20,13 +22,19 @@ use std::{cmp::*, convert::*, fmt, hash::*, str::*};
 ///
 /// Either of the segments can be [Any](Selector::Any). However, if `main` is [Any](Selector::Any),
 /// `subtype` must also be [Any](Selector::Any). Use [Self::is_valid] to check for this.
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+///
+/// The segments are compared case-insensitively. See [MediaTypeParameters] for how parameters are
+/// compared.
+#[derive(Clone, Debug)]
 pub struct MediaTypeSelector {
     /// Main segment.
     pub main: Selector<MediaTypeSegment>,
 
     /// Subtype segment.
     pub subtype: Selector<MediaTypeSegment>,
+
+    /// Parameters.
+    pub parameters: MediaTypeParameters,
 }
 
 impl MediaTypeSelector {
@@ -35,7 +43,16 @@ impl MediaTypeSelector {
 
     /// Constructor.
     pub const fn new(main: Selector<MediaTypeSegment>, subtype: Selector<MediaTypeSegment>) -> Self {
-        Self { main, subtype }
+        Self::new_with_parameters(main, subtype, MediaTypeParameters::NONE)
+    }
+
+    /// Constructor.
+    pub const fn new_with_parameters(
+        main: Selector<MediaTypeSegment>,
+        subtype: Selector<MediaTypeSegment>,
+        parameters: MediaTypeParameters,
+    ) -> Self {
+        Self { main, subtype, parameters }
     }
 
     /// Constructor.
@@ -51,12 +68,63 @@ impl MediaTypeSelector {
         )
     }
 
+    /// Constructor.
+    pub const fn new_fostered_with_parameters(
+        main: &'static str,
+        subtype: &'static str,
+        parameters: &'static [(&'static str, &'static str)],
+    ) -> Self {
+        Self::new_with_parameters(
+            Selector::Specific(MediaTypeSegment::new_fostered(main)),
+            Selector::Specific(MediaTypeSegment::new_fostered(subtype)),
+            MediaTypeParameters::new_fostered(parameters),
+        )
+    }
+
     /// Whether we are valid.
     ///
     /// If `main` is [Any](Selector::Any), `subtype` must also be [Any](Selector::Any).
     pub fn is_valid(&self) -> bool {
         self.main.is_specific() || !self.subtype.is_specific()
     }
+
+    /// Whether we match a media type.
+    ///
+    /// Segments are compared case-insensitively. All our parameters must be present in the media
+    /// type, but it may have additional parameters.
+    pub fn matches(&self, media_type: &MediaType) -> bool {
+        let segments = match &self.main {
+            Selector::Any => true,
+
+            Selector::Specific(main) => {
+                main.as_ref().eq_ignore_ascii_case(media_type.main.as_ref())
+                    && match &self.subtype {
+                        Selector::Any => true,
+                        Selector::Specific(subtype) => {
+                            subtype.as_ref().eq_ignore_ascii_case(media_type.subtype.as_ref())
+                        }
+                    }
+            }
+        };
+
+        segments && media_type.parameters.contains_all(&self.parameters)
+    }
+
+    /// Specificity.
+    ///
+    /// The number of specific segments followed by the number of parameters. A higher value is
+    /// more specific, e.g. `text/html;level=1` > `text/html` > `text/*` > `*/*`.
+    ///
+    /// See [IETF RFC 9110 section 12.5.1](https://datatracker.ietf.org/doc/html/rfc9110#section-12.5.1).
+    pub fn specificity(&self) -> (usize, usize) {
+        let segments = match (&self.main, &self.subtype) {
+            (Selector::Any, _) => 0,
+            (Selector::Specific(_), Selector::Any) => 1,
+            (Selector::Specific(_), Selector::Specific(_)) => 2,
+        };
+
+        (segments, self.parameters.len())
+    }
 }
 
 impl IsSpecific for MediaTypeSelector {
@@ -67,50 +135,59 @@ impl IsSpecific for MediaTypeSelector {
 
 impl IntoOwned for MediaTypeSelector {
     fn into_owned(self) -> Self {
-        match self.main {
-            Selector::Any | Selector::Specific(MediaTypeSegment(Foster::Owned(_))) => match self.subtype {
-                Selector::Any | Selector::Specific(MediaTypeSegment(Foster::Owned(_))) => {
-                    // Both main and subtype are already owned
-                    self
-                }
-
-                Selector::Specific(MediaTypeSegment(Foster::Fostered(subtype))) => {
-                    // Main is owned, subtype isn't
-                    Self::new(self.main, Selector::Specific(subtype.into()))
-                }
-            },
-
-            Selector::Specific(MediaTypeSegment(Foster::Fostered(main))) => match self.subtype {
-                Selector::Any | Selector::Specific(MediaTypeSegment(Foster::Owned(_))) => {
-                    // Subtype is owned, main isn't
-                    Self::new(Selector::Specific(main.into()), self.subtype)
-                }
-
-                Selector::Specific(MediaTypeSegment(Foster::Fostered(subtype))) => {
-                    // Both are not owned
-                    Self::new_owned(main.into(), subtype.into())
-                }
-            },
-        }
+        Self::new_with_parameters(
+            into_owned_segment(self.main),
+            into_owned_segment(self.subtype),
+            self.parameters.into_owned(),
+        )
     }
 }
 
 impl CacheWeight for MediaTypeSelector {
     fn cache_weight(&self) -> usize {
         const SELF_SIZE: usize = size_of::<MediaTypeSelector>();
-        SELF_SIZE + self.main.cache_weight() + self.subtype.cache_weight()
+        SELF_SIZE + self.main.cache_weight() + self.subtype.cache_weight() + self.parameters.cache_weight()
     }
 }
 
 impl From<MediaType> for MediaTypeSelector {
     fn from(media_type: MediaType) -> Self {
-        Self::new(media_type.main.into(), media_type.subtype.into())
+        Self::new_with_parameters(media_type.main.into(), media_type.subtype.into(), media_type.parameters)
     }
 }
 
+impl PartialEq for MediaTypeSelector {
+    fn eq(&self, other: &Self) -> bool {
+        segment_eq(&self.main, &other.main)
+            && segment_eq(&self.subtype, &other.subtype)
+            && (self.parameters == other.parameters)
+    }
+}
+
+impl Eq for MediaTypeSelector {}
+
 impl PartialEq<MediaType> for MediaTypeSelector {
     fn eq(&self, other: &MediaType) -> bool {
-        (self.main == other.main) && (self.subtype == other.subtype)
+        match (&self.main, &self.subtype) {
+            (Selector::Specific(main), Selector::Specific(subtype)) => {
+                main.as_ref().eq_ignore_ascii_case(other.main.as_ref())
+                    && subtype.as_ref().eq_ignore_ascii_case(other.subtype.as_ref())
+                    && (self.parameters == other.parameters)
+            }
+
+            _ => false,
+        }
+    }
+}
+
+impl Hash for MediaTypeSelector {
+    fn hash<HasherT>(&self, state: &mut HasherT)
+    where
+        HasherT: Hasher,
+    {
+        hash_segment(&self.main, state);
+        hash_segment(&self.subtype, state);
+        self.parameters.hash(state);
     }
 }
 
@@ -118,12 +195,15 @@ impl FromStr for MediaTypeSelector {
     type Err = ParseError;
 
     fn from_str(representation: &str) -> Result<Self, Self::Err> {
-        match representation.split_once("/") {
+        let mut split = split_unquoted(representation, ';').into_iter();
+        let essence = split.next().expect("split not empty");
+
+        match essence.split_once("/") {
             Some((main, subtype)) => {
                 // Note: allowed because parse can be Infallible!
-                let Ok(main) = main.parse();
-                let Ok(subtype) = subtype.parse();
-                Ok(Self::new(main, subtype))
+                let Ok(main) = main.trim().parse();
+                let Ok(subtype) = subtype.trim().parse();
+                Ok(Self::new_with_parameters(main, subtype, parse_parameters(split)))
             }
 
             None => Err("missing '/'".into()),
@@ -133,6 +213,87 @@ impl FromStr for MediaTypeSelector {
 
 impl fmt::Display for MediaTypeSelector {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(formatter, "{}/{}", self.main, self.subtype)
+        write!(formatter, "{}/{}{}", self.main, self.subtype, self.parameters)
+    }
+}
+
+//
+// Preferences<MediaTypeSelector>
+//
+
+impl Preferences<MediaTypeSelector> {
+    /// Select the most preferred media type.
+    ///
+    /// Each media type is weighted by the most specific matching media range (see
+    /// [MediaTypeSelector::specificity]). Media types with a zero weight are not acceptable. If
+    /// there is a tie, we will go by the order of media types.
+    ///
+    /// See [IETF RFC 9110 section 12.5.1](https://datatracker.ietf.org/doc/html/rfc9110#section-12.5.1).
+    pub fn best_media_type<'own>(&self, media_types: &'own [MediaType]) -> Option<&'own MediaType> {
+        let mut best: Option<(&MediaType, Weight)> = None;
+
+        for media_type in media_types {
+            if let Some(weight) = self.media_type_weight(media_type)
+                && (weight > Weight::new(0))
+                && best.is_none_or(|(_, best_weight)| weight > best_weight)
+            {
+                best = Some((media_type, weight));
+            }
+        }
+
+        best.map(|(media_type, _)| media_type)
+    }
+
+    /// The weight of the most specific media range that matches the media type.
+    ///
+    /// [None] if no media range matches.
+    pub fn media_type_weight(&self, media_type: &MediaType) -> Option<Weight> {
+        self.0
+            .iter()
+            .filter_map(|preference| {
+                let selector = match &preference.selector {
+                    Selector::Any => &MediaTypeSelector::ANY,
+                    Selector::Specific(selector) => selector,
+                };
+
+                selector.matches(media_type).then(|| (selector.specificity(), preference.weight))
+            })
+            // On equal specificity the first (i.e. highest weight) wins
+            .fold(None, |best: Option<((usize, usize), Weight)>, (specificity, weight)| match best {
+                Some((best_specificity, _)) if best_specificity >= specificity => best,
+                _ => Some((specificity, weight)),
+            })
+            .map(|(_, weight)| weight)
+    }
+}
+
+fn segment_eq(segment: &Selector<MediaTypeSegment>, other: &Selector<MediaTypeSegment>) -> bool {
+    match (segment, other) {
+        (Selector::Any, Selector::Any) => true,
+        (Selector::Specific(segment), Selector::Specific(other)) => {
+            segment.as_ref().eq_ignore_ascii_case(other.as_ref())
+        }
+        _ => false,
+    }
+}
+
+fn hash_segment<HasherT>(segment: &Selector<MediaTypeSegment>, state: &mut HasherT)
+where
+    HasherT: Hasher,
+{
+    match segment {
+        Selector::Any => state.write_u8(0),
+
+        Selector::Specific(segment) => {
+            state.write_u8(1);
+            hash_lowercase(segment.as_ref(), state);
+        }
+    }
+}
+
+fn into_owned_segment(segment: Selector<MediaTypeSegment>) -> Selector<MediaTypeSegment> {
+    match segment {
+        Selector::Any => segment,
+        Selector::Specific(segment) => Selector::Specific(segment.into_owned()),
     }
 }
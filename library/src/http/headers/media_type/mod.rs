@@ -1,6 +1,7 @@
 mod media_type;
 mod media_type_selector;
+mod parameters;
 mod segment;
 
 #[allow(unused_imports)]
-pub use {media_type::*, media_type_selector::*, segment::*};
+pub use {media_type::*, media_type_selector::*, parameters::*, segment::*};
@@ -0,0 +1,223 @@
+use super::super::{
+    super::{
+        super::std::{borrow::*, foster::*, immutable::*},
+        cache::*,
+    },
+    quoted::*,
+};
+
+use std::{
+    fmt,
+    hash::{DefaultHasher, *},
+};
+
+//
+// MediaTypeParameters
+//
+
+/// [MediaType](super::media_type::MediaType) parameters.
+///
+/// Parameter names are compared case-insensitively. Values are compared case-sensitively, except
+/// for `charset`, which is case-insensitive. Order does not matter for comparison. If a name is
+/// repeated, only its first value is considered (as with [get](Self::get)).
+///
+/// See [IETF RFC 9110 section 8.3.1](https://datatracker.ietf.org/doc/html/rfc9110#section-8.3.1).
+#[derive(Clone, Debug)]
+pub struct MediaTypeParameters(pub FosterMediaTypeParameters);
+
+/// [Foster] for media type parameter names and values.
+pub type FosterMediaTypeParameters = Foster<Vec<(ByteString, ByteString)>, &'static [(&'static str, &'static str)]>;
+
+impl MediaTypeParameters {
+    /// No parameters.
+    pub const NONE: Self = Self::new_fostered(&[]);
+
+    /// Constructor.
+    pub const fn new_owned(parameters: Vec<(ByteString, ByteString)>) -> Self {
+        Self(Foster::new_owned(parameters))
+    }
+
+    /// Constructor.
+    pub const fn new_fostered(parameters: &'static [(&'static str, &'static str)]) -> Self {
+        Self(Foster::new_fostered(parameters))
+    }
+
+    /// Whether there are no parameters.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate names and values.
+    pub fn iter(&self) -> Box<dyn Iterator<Item = (&str, &str)> + '_> {
+        match &self.0 {
+            Foster::Owned(parameters) => {
+                Box::new(parameters.iter().map(|(name, value)| (name.as_ref(), value.as_ref())))
+            }
+            Foster::Fostered(parameters) => Box::new(parameters.iter().map(|(name, value)| (*name, *value))),
+        }
+    }
+
+    /// Value of a parameter.
+    ///
+    /// The name is case-insensitive.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.iter().find(|(parameter_name, _)| parameter_name.eq_ignore_ascii_case(name)).map(|(_, value)| value)
+    }
+
+    /// Whether we have a parameter with this value.
+    pub fn contains(&self, name: &str, value: &str) -> bool {
+        self.get(name).is_some_and(|own_value| value_eq(name, own_value, value))
+    }
+
+    /// Whether we contain all of the other's parameters.
+    pub fn contains_all(&self, other: &Self) -> bool {
+        other.distinct().all(|(name, value)| self.contains(name, value))
+    }
+
+    // Iterate names and values, skipping repeated names (the first one wins)
+    fn distinct(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.iter().enumerate().filter_map(|(index, (name, value))| {
+            if self.iter().take(index).any(|(previous_name, _)| previous_name.eq_ignore_ascii_case(name)) {
+                None
+            } else {
+                Some((name, value))
+            }
+        })
+    }
+}
+
+impl Default for MediaTypeParameters {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+impl HasLength for MediaTypeParameters {
+    fn len(&self) -> usize {
+        match &self.0 {
+            Foster::Owned(parameters) => parameters.len(),
+            Foster::Fostered(parameters) => parameters.len(),
+        }
+    }
+}
+
+impl IntoOwned for MediaTypeParameters {
+    fn into_owned(self) -> Self {
+        match self.0 {
+            Foster::Owned(_) => self,
+            Foster::Fostered(parameters) => {
+                Self::new_owned(parameters.iter().map(|(name, value)| ((*name).into(), (*value).into())).collect())
+            }
+        }
+    }
+}
+
+impl CacheWeight for MediaTypeParameters {
+    fn cache_weight(&self) -> usize {
+        let mut size = size_of::<Self>();
+        if let Foster::Owned(parameters) = &self.0 {
+            for (name, value) in parameters {
+                size += name.len() + value.len();
+            }
+        }
+        size
+    }
+}
+
+impl PartialEq for MediaTypeParameters {
+    fn eq(&self, other: &Self) -> bool {
+        (self.distinct().count() == other.distinct().count()) && self.contains_all(other)
+    }
+}
+
+impl Eq for MediaTypeParameters {}
+
+impl Hash for MediaTypeParameters {
+    fn hash<HasherT>(&self, state: &mut HasherT)
+    where
+        HasherT: Hasher,
+    {
+        // Order-insensitive, so we combine the hashes of the individual parameters
+        let mut count = 0;
+        let mut combined = 0u64;
+        for (name, value) in self.distinct() {
+            let mut hasher = DefaultHasher::new();
+            hash_lowercase(name, &mut hasher);
+            if is_case_insensitive(name) {
+                hash_lowercase(value, &mut hasher);
+            } else {
+                hasher.write(value.as_bytes());
+            }
+            combined = combined.wrapping_add(hasher.finish());
+            count += 1;
+        }
+
+        state.write_usize(count);
+        state.write_u64(combined);
+    }
+}
+
+impl fmt::Display for MediaTypeParameters {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, value) in self.iter() {
+            write!(formatter, "; {}={}", name, quote_if_necessary(value))?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse parameters (following the media type).
+///
+/// Malformed parameters are skipped. So are repeated names (the first one wins).
+pub(crate) fn parse_parameters<'own, IteratorT>(parameters: IteratorT) -> MediaTypeParameters
+where
+    IteratorT: Iterator<Item = &'own str>,
+{
+    let mut parsed_parameters: Vec<(ByteString, ByteString)> = Vec::default();
+
+    for parameter in parameters {
+        let parameter = parameter.trim();
+        if parameter.is_empty() {
+            continue;
+        }
+
+        match parameter.split_once('=') {
+            Some((name, value)) if !name.trim().is_empty() => {
+                let name = name.trim();
+                if parsed_parameters.iter().any(|(parsed_name, _)| parsed_name.eq_ignore_ascii_case(name)) {
+                    tracing::warn!("repeated media type parameter: {}", parameter);
+                    continue;
+                }
+
+                parsed_parameters.push((name.into(), unquote(value.trim()).into()));
+            }
+
+            _ => tracing::warn!("malformed media type parameter: {}", parameter),
+        }
+    }
+
+    if parsed_parameters.is_empty() {
+        MediaTypeParameters::NONE
+    } else {
+        MediaTypeParameters::new_owned(parsed_parameters)
+    }
+}
+
+/// Hash a string case-insensitively.
+pub(crate) fn hash_lowercase<HasherT>(string: &str, state: &mut HasherT)
+where
+    HasherT: Hasher,
+{
+    for byte in string.bytes() {
+        state.write_u8(byte.to_ascii_lowercase());
+    }
+    state.write_u8(0xff);
+}
+
+fn value_eq(name: &str, value: &str, other_value: &str) -> bool {
+    if is_case_insensitive(name) { value.eq_ignore_ascii_case(other_value) } else { value == other_value }
+}
+
+fn is_case_insensitive(name: &str) -> bool {
+    name.eq_ignore_ascii_case("charset")
+}
@@ -1,17 +1,25 @@
+mod authorization;
 mod bool;
+mod cache_control;
 mod conditional;
+mod cookie;
 mod custom;
 mod date;
+mod dictionary;
 mod encoding;
 mod etag;
+mod forwarded;
 mod headers;
 mod into;
 mod language;
 mod media_type;
 mod preferences;
+mod quoted;
+mod structured;
 
 #[allow(unused_imports)]
 pub use {
-    bool::*, conditional::*, custom::*, date::*, encoding::*, etag::*, headers::*, into::*, language::*, media_type::*,
-    preferences::*,
+    authorization::*, bool::*, cache_control::*, conditional::*, cookie::*, custom::*, date::*, dictionary::*,
+    encoding::*, etag::*, forwarded::*, headers::*, into::*, language::*, media_type::*, preferences::*, quoted::*,
+    structured::*,
 };
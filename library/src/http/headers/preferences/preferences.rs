@@ -1,4 +1,9 @@
-use super::{super::super::super::std::collections::*, preference::*, selector::*, weight::*};
+use super::{
+    super::{super::super::std::collections::*, quoted::*},
+    preference::*,
+    selector::*,
+    weight::*,
+};
 
 use std::{hash::*, iter::*, str::*};
 
@@ -24,13 +29,21 @@ impl<SelectionT> Preferences<SelectionT> {
     {
         let preferences: Vec<_> = representations
             .iter()
-            .flat_map(|representation| representation.split(","))
+            .flat_map(|representation| split_unquoted(representation, ','))
             .filter_map(move |format| {
-                let mut split = format.splitn(2, ';');
-
-                let selector = (split.next().expect("splitn not empty").trim()).parse().ok()?;
+                // Parameters before the weight belong to the selector (e.g. media type parameters);
+                // those after it are extensions, which we ignore
+                let split = split_unquoted(format, ';');
+                let weight_index = split.iter().position(|parameter| is_weight(parameter)).unwrap_or(split.len());
+
+                let selector = split[..weight_index].join(";");
+                let selector = selector.trim();
+                if selector.is_empty() {
+                    return None;
+                }
+                let selector = selector.parse().ok()?;
 
-                let weight = match split.next() {
+                let weight = match split.get(weight_index) {
                     Some(weight) => Weight::parse(weight.trim())?,
                     None => Weight::MAX,
                 };
@@ -123,3 +136,7 @@ impl<SelectionT> Preferences<SelectionT> {
         tied
     }
 }
+
+fn is_weight(parameter: &str) -> bool {
+    parameter.split_once('=').is_some_and(|(name, _)| name.trim().eq_ignore_ascii_case("q"))
+}
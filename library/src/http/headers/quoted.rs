@@ -0,0 +1,68 @@
+// Helpers for HTTP quoted strings
+//
+// See IETF RFC 9110 section 5.6
+
+/// Split at separators that are not inside quoted strings.
+pub(crate) fn split_unquoted(representation: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+
+    for (index, c) in representation.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if quoted && (c == '\\') {
+            escaped = true;
+        } else if c == '"' {
+            quoted = !quoted;
+        } else if !quoted && (c == separator) {
+            parts.push(&representation[start..index]);
+            start = index + c.len_utf8();
+        }
+    }
+
+    parts.push(&representation[start..]);
+    parts
+}
+
+/// Unquote if quoted.
+pub(crate) fn unquote(value: &str) -> String {
+    match value.strip_prefix('"').and_then(|value| value.strip_suffix('"')) {
+        Some(value) => {
+            let mut unquoted = String::with_capacity(value.len());
+            let mut escaped = false;
+            for c in value.chars() {
+                if !escaped && (c == '\\') {
+                    escaped = true;
+                } else {
+                    unquoted.push(c);
+                    escaped = false;
+                }
+            }
+            unquoted
+        }
+
+        None => value.into(),
+    }
+}
+
+/// Quote.
+pub(crate) fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Quote if not a token.
+pub(crate) fn quote_if_necessary(value: &str) -> String {
+    if is_token(value) { value.into() } else { quote(value) }
+}
+
+/// Whether the value is a token.
+pub(crate) fn is_token(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c))
+}
+
+/// Whether the value can be quoted, i.e. it has no control characters other than HTAB.
+pub(crate) fn is_quotable(value: &str) -> bool {
+    value.chars().all(|c| (c == '\t') || ((c >= ' ') && (c != '\x7f')))
+}
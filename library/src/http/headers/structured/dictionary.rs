@@ -0,0 +1,91 @@
+use super::{super::super::super::std::string::*, item::*, list::*, parser::*, validation::*};
+
+use std::{fmt, result::Result, str::*};
+
+//
+// StructuredDictionary
+//
+
+/// Structured field dictionary.
+///
+/// Ordered. Keys are unique.
+///
+/// See [IETF RFC 9651 section 3.2](https://datatracker.ietf.org/doc/html/rfc9651#section-3.2).
+///
+/// Note that [Display](fmt::Display) does not validate. Use [serialize](Self::serialize) for
+/// header values.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct StructuredDictionary(pub Vec<(String, ListMember)>);
+
+impl StructuredDictionary {
+    /// Whether there are no members.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Member.
+    pub fn get(&self, key: &str) -> Option<&ListMember> {
+        self.0.iter().find(|(member_key, _)| member_key == key).map(|(_, member)| member)
+    }
+
+    /// Item member.
+    pub fn get_item(&self, key: &str) -> Option<&StructuredItem> {
+        self.get(key)?.as_item()
+    }
+
+    /// Insert a member.
+    ///
+    /// If the key already exists its member will be overwritten (but it will keep its position).
+    pub fn insert(&mut self, key: String, member: ListMember) {
+        match self.0.iter_mut().find(|(member_key, _)| *member_key == key) {
+            Some((_, existing_member)) => *existing_member = member,
+            None => self.0.push((key, member)),
+        }
+    }
+
+    /// With member.
+    pub fn with_member(mut self, key: String, member: ListMember) -> Self {
+        self.insert(key, member);
+        self
+    }
+
+    /// Serialize.
+    ///
+    /// Fails if a value cannot be represented, as required by
+    /// [IETF RFC 9651 section 4.1](https://datatracker.ietf.org/doc/html/rfc9651#section-4.1).
+    pub fn serialize(&self) -> Result<String, ParseError> {
+        for (key, member) in &self.0 {
+            validate_key(key)?;
+            validate_list_member(member)?;
+        }
+        Ok(self.to_string())
+    }
+}
+
+impl FromStr for StructuredDictionary {
+    type Err = ParseError;
+
+    fn from_str(representation: &str) -> Result<Self, Self::Err> {
+        Parser::new(representation).parse_dictionary_field()
+    }
+}
+
+impl fmt::Display for StructuredDictionary {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (key, member)) in self.0.iter().enumerate() {
+            if index != 0 {
+                formatter.write_str(", ")?;
+            }
+
+            match member {
+                // Boolean true is implied
+                ListMember::Item(StructuredItem { bare_item: BareItem::Boolean(true), parameters }) => {
+                    write!(formatter, "{}{}", key, parameters)?
+                }
+
+                _ => write!(formatter, "{}={}", key, member)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,318 @@
+use super::{super::super::super::std::string::*, parameters::*, parser::*, validation::*};
+
+use std::{fmt, result::Result, str::*};
+
+//
+// StructuredItem
+//
+
+/// Structured field item.
+///
+/// See [IETF RFC 9651 section 3.3](https://datatracker.ietf.org/doc/html/rfc9651#section-3.3).
+///
+/// Note that [Display](fmt::Display) does not validate. Use [serialize](Self::serialize) for
+/// header values.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct StructuredItem {
+    /// Bare item.
+    pub bare_item: BareItem,
+
+    /// Parameters.
+    pub parameters: StructuredParameters,
+}
+
+impl StructuredItem {
+    /// Constructor.
+    pub fn new(bare_item: BareItem) -> Self {
+        Self { bare_item, parameters: Default::default() }
+    }
+
+    /// With parameter.
+    pub fn with_parameter(mut self, key: String, value: BareItem) -> Self {
+        self.parameters.insert(key, value);
+        self
+    }
+
+    /// Serialize.
+    ///
+    /// Fails if a value cannot be represented, as required by
+    /// [IETF RFC 9651 section 4.1](https://datatracker.ietf.org/doc/html/rfc9651#section-4.1).
+    pub fn serialize(&self) -> Result<String, ParseError> {
+        validate_item(self)?;
+        Ok(self.to_string())
+    }
+}
+
+impl From<BareItem> for StructuredItem {
+    fn from(bare_item: BareItem) -> Self {
+        Self::new(bare_item)
+    }
+}
+
+impl FromStr for StructuredItem {
+    type Err = ParseError;
+
+    fn from_str(representation: &str) -> Result<Self, Self::Err> {
+        Parser::new(representation).parse_item_field()
+    }
+}
+
+impl fmt::Display for StructuredItem {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}{}", self.bare_item, self.parameters)
+    }
+}
+
+//
+// BareItem
+//
+
+/// Structured field bare item.
+///
+/// See [IETF RFC 9651 section 3.3](https://datatracker.ietf.org/doc/html/rfc9651#section-3.3).
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum BareItem {
+    /// Integer.
+    ///
+    /// Up to 15 digits (see [MAX_STRUCTURED_INTEGER]).
+    Integer(i64),
+
+    /// Decimal.
+    Decimal(Decimal),
+
+    /// String.
+    ///
+    /// Printable ASCII only.
+    String(String),
+
+    /// Token.
+    ///
+    /// Must start with a letter or "*".
+    Token(String),
+
+    /// Byte sequence.
+    ///
+    /// Base64 between colons.
+    ByteSequence(Vec<u8>),
+
+    /// Boolean.
+    Boolean(bool),
+
+    /// Date (seconds since the Unix epoch).
+    ///
+    /// Up to 15 digits (see [MAX_STRUCTURED_INTEGER]).
+    Date(i64),
+
+    /// Display string.
+    ///
+    /// Unicode, percent-encoded.
+    DisplayString(String),
+}
+
+impl BareItem {
+    /// As integer.
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Self::Integer(integer) => Some(*integer),
+            _ => None,
+        }
+    }
+
+    /// As decimal.
+    ///
+    /// Integers are converted.
+    pub fn as_decimal(&self) -> Option<Decimal> {
+        match self {
+            Self::Decimal(decimal) => Some(*decimal),
+            Self::Integer(integer) => Decimal::from_integer(*integer),
+            _ => None,
+        }
+    }
+
+    /// As string.
+    ///
+    /// Also works for tokens and display strings.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(string) | Self::Token(string) | Self::DisplayString(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    /// As token.
+    pub fn as_token(&self) -> Option<&str> {
+        match self {
+            Self::Token(token) => Some(token),
+            _ => None,
+        }
+    }
+
+    /// As byte sequence.
+    pub fn as_byte_sequence(&self) -> Option<&[u8]> {
+        match self {
+            Self::ByteSequence(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// As boolean.
+    pub fn as_boolean(&self) -> Option<bool> {
+        match self {
+            Self::Boolean(boolean) => Some(*boolean),
+            _ => None,
+        }
+    }
+
+    /// As date (seconds since the Unix epoch).
+    pub fn as_date(&self) -> Option<i64> {
+        match self {
+            Self::Date(date) => Some(*date),
+            _ => None,
+        }
+    }
+}
+
+impl From<i64> for BareItem {
+    fn from(integer: i64) -> Self {
+        Self::Integer(integer)
+    }
+}
+
+impl From<Decimal> for BareItem {
+    fn from(decimal: Decimal) -> Self {
+        Self::Decimal(decimal)
+    }
+}
+
+impl From<bool> for BareItem {
+    fn from(boolean: bool) -> Self {
+        Self::Boolean(boolean)
+    }
+}
+
+impl From<Vec<u8>> for BareItem {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::ByteSequence(bytes)
+    }
+}
+
+impl FromStr for BareItem {
+    type Err = ParseError;
+
+    fn from_str(representation: &str) -> Result<Self, Self::Err> {
+        Parser::new(representation).parse_bare_item_field()
+    }
+}
+
+impl fmt::Display for BareItem {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(integer) => write!(formatter, "{}", integer),
+
+            Self::Decimal(decimal) => fmt::Display::fmt(decimal, formatter),
+
+            Self::String(string) => {
+                formatter.write_str("\"")?;
+                for c in string.chars() {
+                    if (c == '"') || (c == '\\') {
+                        formatter.write_str("\\")?;
+                    }
+                    write!(formatter, "{}", c)?;
+                }
+                formatter.write_str("\"")
+            }
+
+            Self::Token(token) => formatter.write_str(token),
+
+            Self::ByteSequence(bytes) => write!(formatter, ":{}:", base64_simd::STANDARD.encode_to_string(bytes)),
+
+            Self::Boolean(boolean) => formatter.write_str(if *boolean { "?1" } else { "?0" }),
+
+            Self::Date(date) => write!(formatter, "@{}", date),
+
+            Self::DisplayString(string) => {
+                formatter.write_str("%\"")?;
+                for byte in string.bytes() {
+                    if (byte == b'%') || (byte == b'"') || !(0x20..=0x7e).contains(&byte) {
+                        write!(formatter, "%{:02x}", byte)?;
+                    } else {
+                        write!(formatter, "{}", byte as char)?;
+                    }
+                }
+                formatter.write_str("\"")
+            }
+        }
+    }
+}
+
+//
+// Decimal
+//
+
+/// Structured field decimal.
+///
+/// Stored as an integer number of thousandths. We use an integer rather than a float in order to
+/// avoid comparison issues.
+///
+/// See [IETF RFC 9651 section 3.3.2](https://datatracker.ietf.org/doc/html/rfc9651#section-3.3.2).
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Decimal(i64);
+
+impl Decimal {
+    /// Max absolute number of thousandths (12 integer digits and 3 fractional digits).
+    pub const MAX_THOUSANDTHS: i64 = 999_999_999_999_999;
+
+    /// Constructor.
+    ///
+    /// [None] if out of range.
+    pub const fn from_thousandths(thousandths: i64) -> Option<Self> {
+        if (thousandths >= -Self::MAX_THOUSANDTHS) && (thousandths <= Self::MAX_THOUSANDTHS) {
+            Some(Self(thousandths))
+        } else {
+            None
+        }
+    }
+
+    /// Constructor.
+    ///
+    /// [None] if out of range.
+    pub const fn from_integer(integer: i64) -> Option<Self> {
+        match integer.checked_mul(1000) {
+            Some(thousandths) => Self::from_thousandths(thousandths),
+            None => None,
+        }
+    }
+
+    /// Constructor.
+    ///
+    /// Rounded to the nearest thousandth (ties to even). [None] if out of range.
+    pub fn from_f64(float: f64) -> Option<Self> {
+        let thousandths = (float * 1000.).round_ties_even();
+        if thousandths.is_finite() && (thousandths.abs() <= Self::MAX_THOUSANDTHS as f64) {
+            Some(Self(thousandths as i64))
+        } else {
+            None
+        }
+    }
+
+    /// Number of thousandths.
+    pub const fn thousandths(&self) -> i64 {
+        self.0
+    }
+
+    /// As float.
+    pub fn as_f64(&self) -> f64 {
+        self.0 as f64 / 1000.
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let absolute = self.0.unsigned_abs();
+        let fraction = format!("{:03}", absolute % 1000);
+        let fraction = fraction.trim_end_matches('0');
+        let fraction = if fraction.is_empty() { "0" } else { fraction };
+        write!(formatter, "{}{}.{}", sign, absolute / 1000, fraction)
+    }
+}
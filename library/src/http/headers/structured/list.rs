@@ -0,0 +1,169 @@
+use super::{super::super::super::std::string::*, item::*, parameters::*, parser::*, validation::*};
+
+use std::{fmt, result::Result, str::*};
+
+//
+// StructuredList
+//
+
+/// Structured field list.
+///
+/// See [IETF RFC 9651 section 3.1](https://datatracker.ietf.org/doc/html/rfc9651#section-3.1).
+///
+/// Note that [Display](fmt::Display) does not validate. Use [serialize](Self::serialize) for
+/// header values.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct StructuredList(pub Vec<ListMember>);
+
+impl StructuredList {
+    /// Whether there are no members.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// With member.
+    pub fn with_member(mut self, member: ListMember) -> Self {
+        self.0.push(member);
+        self
+    }
+
+    /// Serialize.
+    ///
+    /// Fails if a value cannot be represented, as required by
+    /// [IETF RFC 9651 section 4.1](https://datatracker.ietf.org/doc/html/rfc9651#section-4.1).
+    pub fn serialize(&self) -> Result<String, ParseError> {
+        for member in &self.0 {
+            validate_list_member(member)?;
+        }
+        Ok(self.to_string())
+    }
+}
+
+impl FromStr for StructuredList {
+    type Err = ParseError;
+
+    fn from_str(representation: &str) -> Result<Self, Self::Err> {
+        Parser::new(representation).parse_list_field()
+    }
+}
+
+impl fmt::Display for StructuredList {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, member) in self.0.iter().enumerate() {
+            if index != 0 {
+                formatter.write_str(", ")?;
+            }
+            fmt::Display::fmt(member, formatter)?;
+        }
+        Ok(())
+    }
+}
+
+//
+// ListMember
+//
+
+/// Member of a structured field [StructuredList] or [StructuredDictionary](super::dictionary::StructuredDictionary).
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ListMember {
+    /// Item.
+    Item(StructuredItem),
+
+    /// Inner list.
+    InnerList(InnerList),
+}
+
+impl ListMember {
+    /// As item.
+    pub fn as_item(&self) -> Option<&StructuredItem> {
+        match self {
+            Self::Item(item) => Some(item),
+            _ => None,
+        }
+    }
+
+    /// As inner list.
+    pub fn as_inner_list(&self) -> Option<&InnerList> {
+        match self {
+            Self::InnerList(inner_list) => Some(inner_list),
+            _ => None,
+        }
+    }
+
+    /// Parameters.
+    pub fn parameters(&self) -> &StructuredParameters {
+        match self {
+            Self::Item(item) => &item.parameters,
+            Self::InnerList(inner_list) => &inner_list.parameters,
+        }
+    }
+}
+
+impl From<StructuredItem> for ListMember {
+    fn from(item: StructuredItem) -> Self {
+        Self::Item(item)
+    }
+}
+
+impl From<BareItem> for ListMember {
+    fn from(bare_item: BareItem) -> Self {
+        Self::Item(bare_item.into())
+    }
+}
+
+impl From<InnerList> for ListMember {
+    fn from(inner_list: InnerList) -> Self {
+        Self::InnerList(inner_list)
+    }
+}
+
+impl fmt::Display for ListMember {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Item(item) => fmt::Display::fmt(item, formatter),
+            Self::InnerList(inner_list) => fmt::Display::fmt(inner_list, formatter),
+        }
+    }
+}
+
+//
+// InnerList
+//
+
+/// Structured field inner list.
+///
+/// See [IETF RFC 9651 section 3.1.1](https://datatracker.ietf.org/doc/html/rfc9651#section-3.1.1).
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct InnerList {
+    /// Items.
+    pub items: Vec<StructuredItem>,
+
+    /// Parameters.
+    pub parameters: StructuredParameters,
+}
+
+impl InnerList {
+    /// Constructor.
+    pub fn new(items: Vec<StructuredItem>) -> Self {
+        Self { items, parameters: Default::default() }
+    }
+
+    /// With parameter.
+    pub fn with_parameter(mut self, key: String, value: BareItem) -> Self {
+        self.parameters.insert(key, value);
+        self
+    }
+}
+
+impl fmt::Display for InnerList {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("(")?;
+        for (index, item) in self.items.iter().enumerate() {
+            if index != 0 {
+                formatter.write_str(" ")?;
+            }
+            fmt::Display::fmt(item, formatter)?;
+        }
+        write!(formatter, "){}", self.parameters)
+    }
+}
@@ -0,0 +1,10 @@
+mod dictionary;
+mod item;
+mod list;
+mod parameters;
+mod parser;
+mod validation;
+mod values;
+
+#[allow(unused_imports)]
+pub use {dictionary::*, item::*, list::*, parameters::*, validation::*, values::*};
@@ -0,0 +1,49 @@
+use super::item::*;
+
+use std::fmt;
+
+//
+// StructuredParameters
+//
+
+/// Structured field parameters.
+///
+/// Ordered. Keys are unique.
+///
+/// See [IETF RFC 9651 section 3.1.2](https://datatracker.ietf.org/doc/html/rfc9651#section-3.1.2).
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct StructuredParameters(pub Vec<(String, BareItem)>);
+
+impl StructuredParameters {
+    /// Whether there are no parameters.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Value of a parameter.
+    pub fn get(&self, key: &str) -> Option<&BareItem> {
+        self.0.iter().find(|(parameter_key, _)| parameter_key == key).map(|(_, value)| value)
+    }
+
+    /// Insert a parameter.
+    ///
+    /// If the key already exists its value will be overwritten (but it will keep its position).
+    pub fn insert(&mut self, key: String, value: BareItem) {
+        match self.0.iter_mut().find(|(parameter_key, _)| *parameter_key == key) {
+            Some((_, parameter_value)) => *parameter_value = value,
+            None => self.0.push((key, value)),
+        }
+    }
+}
+
+impl fmt::Display for StructuredParameters {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, value) in &self.0 {
+            match value {
+                BareItem::Boolean(true) => write!(formatter, ";{}", key)?,
+                _ => write!(formatter, ";{}={}", key, value)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,405 @@
+use super::{super::super::super::std::string::*, dictionary::*, item::*, list::*, parameters::*};
+
+use std::result::Result;
+
+//
+// Parser
+//
+
+/// Structured field parser.
+///
+/// Strict, as required by
+/// [IETF RFC 9651 section 4.2](https://datatracker.ietf.org/doc/html/rfc9651#section-4.2): any
+/// error fails the whole field.
+pub(crate) struct Parser<'own> {
+    input: &'own [u8],
+    position: usize,
+}
+
+impl<'own> Parser<'own> {
+    /// Constructor.
+    pub(crate) fn new(input: &'own str) -> Self {
+        Self { input: input.as_bytes(), position: 0 }
+    }
+
+    /// Parse a whole field as a list.
+    pub(crate) fn parse_list_field(mut self) -> Result<StructuredList, ParseError> {
+        self.parse_field(|parser| parser.parse_list())
+    }
+
+    /// Parse a whole field as a dictionary.
+    pub(crate) fn parse_dictionary_field(mut self) -> Result<StructuredDictionary, ParseError> {
+        self.parse_field(|parser| parser.parse_dictionary())
+    }
+
+    /// Parse a whole field as an item.
+    pub(crate) fn parse_item_field(mut self) -> Result<StructuredItem, ParseError> {
+        self.parse_field(|parser| parser.parse_item())
+    }
+
+    /// Parse a whole field as a bare item.
+    pub(crate) fn parse_bare_item_field(mut self) -> Result<BareItem, ParseError> {
+        self.parse_field(|parser| parser.parse_bare_item())
+    }
+
+    fn parse_field<ValueT, ParseT>(&mut self, parse: ParseT) -> Result<ValueT, ParseError>
+    where
+        ParseT: FnOnce(&mut Self) -> Result<ValueT, ParseError>,
+    {
+        self.skip_spaces();
+        let value = parse(self)?;
+        self.skip_spaces();
+
+        match self.peek() {
+            None => Ok(value),
+            Some(c) => Err(format!("unexpected trailing character: {:?}", c as char).into()),
+        }
+    }
+
+    fn parse_list(&mut self) -> Result<StructuredList, ParseError> {
+        let mut members = Vec::new();
+
+        while self.peek().is_some() {
+            members.push(self.parse_list_member()?);
+
+            self.skip_whitespace();
+            match self.next() {
+                None => break,
+                Some(b',') => {}
+                Some(c) => return Err(format!("expected ',': {:?}", c as char).into()),
+            }
+
+            self.skip_whitespace();
+            if self.peek().is_none() {
+                return Err("trailing ','".into());
+            }
+        }
+
+        Ok(StructuredList(members))
+    }
+
+    fn parse_dictionary(&mut self) -> Result<StructuredDictionary, ParseError> {
+        let mut dictionary = StructuredDictionary::default();
+
+        while self.peek().is_some() {
+            let key = self.parse_key()?;
+
+            let member = if self.peek() == Some(b'=') {
+                self.position += 1;
+                self.parse_list_member()?
+            } else {
+                let mut item = StructuredItem::new(BareItem::Boolean(true));
+                item.parameters = self.parse_parameters()?;
+                item.into()
+            };
+
+            dictionary.insert(key, member);
+
+            self.skip_whitespace();
+            match self.next() {
+                None => break,
+                Some(b',') => {}
+                Some(c) => return Err(format!("expected ',': {:?}", c as char).into()),
+            }
+
+            self.skip_whitespace();
+            if self.peek().is_none() {
+                return Err("trailing ','".into());
+            }
+        }
+
+        Ok(dictionary)
+    }
+
+    fn parse_list_member(&mut self) -> Result<ListMember, ParseError> {
+        if self.peek() == Some(b'(') { Ok(self.parse_inner_list()?.into()) } else { Ok(self.parse_item()?.into()) }
+    }
+
+    fn parse_inner_list(&mut self) -> Result<InnerList, ParseError> {
+        self.expect(b'(')?;
+
+        let mut items = Vec::new();
+        loop {
+            self.skip_spaces();
+
+            if self.peek() == Some(b')') {
+                self.position += 1;
+                let parameters = self.parse_parameters()?;
+                return Ok(InnerList { items, parameters });
+            }
+
+            items.push(self.parse_item()?);
+
+            match self.peek() {
+                Some(b' ' | b')') => {}
+                Some(c) => return Err(format!("expected ' ' or ')': {:?}", c as char).into()),
+                None => return Err("unterminated inner list".into()),
+            }
+        }
+    }
+
+    fn parse_item(&mut self) -> Result<StructuredItem, ParseError> {
+        let bare_item = self.parse_bare_item()?;
+        let parameters = self.parse_parameters()?;
+        Ok(StructuredItem { bare_item, parameters })
+    }
+
+    fn parse_bare_item(&mut self) -> Result<BareItem, ParseError> {
+        match self.peek() {
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            Some(b'"') => self.parse_string().map(BareItem::String),
+            Some(b'*' | b'a'..=b'z' | b'A'..=b'Z') => self.parse_token().map(BareItem::Token),
+            Some(b':') => self.parse_byte_sequence().map(BareItem::ByteSequence),
+            Some(b'?') => self.parse_boolean().map(BareItem::Boolean),
+            Some(b'@') => self.parse_date().map(BareItem::Date),
+            Some(b'%') => self.parse_display_string().map(BareItem::DisplayString),
+            Some(c) => Err(format!("unexpected character: {:?}", c as char).into()),
+            None => Err("missing item".into()),
+        }
+    }
+
+    fn parse_parameters(&mut self) -> Result<StructuredParameters, ParseError> {
+        let mut parameters = StructuredParameters::default();
+
+        while self.peek() == Some(b';') {
+            self.position += 1;
+            self.skip_spaces();
+
+            let key = self.parse_key()?;
+            let value = if self.peek() == Some(b'=') {
+                self.position += 1;
+                self.parse_bare_item()?
+            } else {
+                BareItem::Boolean(true)
+            };
+
+            parameters.insert(key, value);
+        }
+
+        Ok(parameters)
+    }
+
+    fn parse_key(&mut self) -> Result<String, ParseError> {
+        let start = self.position;
+
+        match self.peek() {
+            Some(b'*' | b'a'..=b'z') => self.position += 1,
+            Some(c) => return Err(format!("malformed key: {:?}", c as char).into()),
+            None => return Err("missing key".into()),
+        }
+
+        while let Some(b'a'..=b'z' | b'0'..=b'9' | b'_' | b'-' | b'.' | b'*') = self.peek() {
+            self.position += 1;
+        }
+
+        Ok(self.slice(start))
+    }
+
+    fn parse_number(&mut self) -> Result<BareItem, ParseError> {
+        let negative = self.peek() == Some(b'-');
+        if negative {
+            self.position += 1;
+        }
+
+        let start = self.position;
+        let mut point = None;
+
+        while let Some(c) = self.peek() {
+            match c {
+                b'0'..=b'9' => {}
+
+                b'.' if point.is_none() => {
+                    if self.position - start > 12 {
+                        return Err("decimal has too many integer digits".into());
+                    }
+                    point = Some(self.position);
+                }
+
+                _ => break,
+            }
+
+            self.position += 1;
+
+            let length = self.position - start;
+            if (point.is_none() && (length > 15)) || (point.is_some() && (length > 16)) {
+                return Err("number is too long".into());
+            }
+        }
+
+        let digits = self.slice(start);
+        if digits.is_empty() || digits.starts_with('.') {
+            return Err("missing digits".into());
+        }
+
+        match point {
+            None => {
+                let integer: i64 = digits.parse().map_err(|_| ParseError::from("malformed integer"))?;
+                Ok(BareItem::Integer(if negative { -integer } else { integer }))
+            }
+
+            Some(point) => {
+                let fraction = &digits[point - start + 1..];
+                if fraction.is_empty() || (fraction.len() > 3) {
+                    return Err("decimal must have 1 to 3 fractional digits".into());
+                }
+
+                let integer: i64 =
+                    digits[..point - start].parse().map_err(|_| ParseError::from("malformed decimal"))?;
+                let fraction: i64 =
+                    format!("{:0<3}", fraction).parse().map_err(|_| ParseError::from("malformed decimal"))?;
+                let thousandths = integer * 1000 + fraction;
+
+                Decimal::from_thousandths(if negative { -thousandths } else { thousandths })
+                    .map(BareItem::Decimal)
+                    .ok_or_else(|| "decimal out of range".into())
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, ParseError> {
+        self.expect(b'"')?;
+
+        let mut string = String::new();
+        loop {
+            match self.next() {
+                Some(b'\\') => match self.next() {
+                    Some(c @ (b'"' | b'\\')) => string.push(c as char),
+                    _ => return Err("malformed escape in string".into()),
+                },
+
+                Some(b'"') => return Ok(string),
+                Some(c @ 0x20..=0x7e) => string.push(c as char),
+                Some(c) => return Err(format!("invalid character in string: {:?}", c).into()),
+                None => return Err("unterminated string".into()),
+            }
+        }
+    }
+
+    fn parse_token(&mut self) -> Result<String, ParseError> {
+        let start = self.position;
+
+        match self.peek() {
+            Some(b'*' | b'a'..=b'z' | b'A'..=b'Z') => self.position += 1,
+            _ => return Err("malformed token".into()),
+        }
+
+        while let Some(c) = self.peek() {
+            if c.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~:/".contains(&c) {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+
+        Ok(self.slice(start))
+    }
+
+    fn parse_byte_sequence(&mut self) -> Result<Vec<u8>, ParseError> {
+        self.expect(b':')?;
+
+        let start = self.position;
+        while let Some(c) = self.peek() {
+            if c.is_ascii_alphanumeric() || (c == b'+') || (c == b'/') || (c == b'=') {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+        let encoded = self.slice(start);
+        self.expect(b':')?;
+
+        // Padding is optional for parsers
+        base64_simd::STANDARD
+            .decode_to_vec(&encoded)
+            .or_else(|_| base64_simd::STANDARD_NO_PAD.decode_to_vec(&encoded))
+            .map_err(|_| "malformed byte sequence".into())
+    }
+
+    fn parse_boolean(&mut self) -> Result<bool, ParseError> {
+        self.expect(b'?')?;
+
+        match self.next() {
+            Some(b'1') => Ok(true),
+            Some(b'0') => Ok(false),
+            _ => Err("malformed boolean".into()),
+        }
+    }
+
+    fn parse_date(&mut self) -> Result<i64, ParseError> {
+        self.expect(b'@')?;
+
+        match self.parse_number()? {
+            BareItem::Integer(date) => Ok(date),
+            _ => Err("date must be an integer".into()),
+        }
+    }
+
+    fn parse_display_string(&mut self) -> Result<String, ParseError> {
+        self.expect(b'%')?;
+        self.expect(b'"')?;
+
+        let mut bytes = Vec::new();
+        loop {
+            match self.next() {
+                Some(b'%') => {
+                    let high = self.next().and_then(lowercase_hex_digit);
+                    let low = self.next().and_then(lowercase_hex_digit);
+                    match (high, low) {
+                        (Some(high), Some(low)) => bytes.push((high << 4) | low),
+                        _ => return Err("malformed percent-encoding in display string".into()),
+                    }
+                }
+
+                Some(b'"') => {
+                    return String::from_utf8(bytes).map_err(|_| "display string is not UTF-8".into());
+                }
+
+                Some(c @ 0x20..=0x7e) => bytes.push(c),
+                Some(c) => return Err(format!("invalid character in display string: {:?}", c).into()),
+                None => return Err("unterminated display string".into()),
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let c = self.peek()?;
+        self.position += 1;
+        Some(c)
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<(), ParseError> {
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            _ => Err(format!("expected {:?}", expected as char).into()),
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek() == Some(b' ') {
+            self.position += 1;
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t') = self.peek() {
+            self.position += 1;
+        }
+    }
+
+    // Only called for ASCII ranges
+    fn slice(&self, start: usize) -> String {
+        String::from_utf8_lossy(&self.input[start..self.position]).into()
+    }
+}
+
+fn lowercase_hex_digit(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        _ => None,
+    }
+}
@@ -0,0 +1,87 @@
+use super::{super::super::super::std::string::*, item::*, list::*, parameters::*};
+
+use std::result::Result;
+
+// Serialization must fail for values that cannot be represented, as required by
+// IETF RFC 9651 section 4.1 (our Display implementations do not check)
+
+/// Max absolute value of structured field integers and dates.
+pub const MAX_STRUCTURED_INTEGER: i64 = 999_999_999_999_999;
+
+/// Whether the string is a structured field token.
+///
+/// See [IETF RFC 9651 section 3.3.4](https://datatracker.ietf.org/doc/html/rfc9651#section-3.3.4).
+pub(crate) fn is_structured_token(string: &str) -> bool {
+    let mut chars = string.chars();
+
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || (c == '*') => {}
+        _ => return false,
+    }
+
+    chars.all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~:/".contains(c))
+}
+
+/// Whether the string is a structured field key.
+///
+/// See [IETF RFC 9651 section 3.1.2](https://datatracker.ietf.org/doc/html/rfc9651#section-3.1.2).
+pub(crate) fn is_structured_key(string: &str) -> bool {
+    let mut chars = string.chars();
+
+    match chars.next() {
+        Some(c) if c.is_ascii_lowercase() || (c == '*') => {}
+        _ => return false,
+    }
+
+    chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "_-.*".contains(c))
+}
+
+pub(crate) fn validate_key(key: &str) -> Result<(), ParseError> {
+    if is_structured_key(key) { Ok(()) } else { Err(format!("invalid key: {:?}", key).into()) }
+}
+
+pub(crate) fn validate_bare_item(bare_item: &BareItem) -> Result<(), ParseError> {
+    match bare_item {
+        BareItem::Integer(integer) if integer.unsigned_abs() > MAX_STRUCTURED_INTEGER as u64 => {
+            Err(format!("integer out of range: {}", integer).into())
+        }
+
+        BareItem::Date(date) if date.unsigned_abs() > MAX_STRUCTURED_INTEGER as u64 => {
+            Err(format!("date out of range: {}", date).into())
+        }
+
+        BareItem::String(string) if !string.bytes().all(|byte| (0x20..=0x7e).contains(&byte)) => {
+            Err(format!("string is not printable ASCII: {:?}", string).into())
+        }
+
+        BareItem::Token(token) if !is_structured_token(token) => Err(format!("invalid token: {:?}", token).into()),
+
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn validate_parameters(parameters: &StructuredParameters) -> Result<(), ParseError> {
+    for (key, value) in &parameters.0 {
+        validate_key(key)?;
+        validate_bare_item(value)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn validate_item(item: &StructuredItem) -> Result<(), ParseError> {
+    validate_bare_item(&item.bare_item)?;
+    validate_parameters(&item.parameters)
+}
+
+pub(crate) fn validate_list_member(member: &ListMember) -> Result<(), ParseError> {
+    match member {
+        ListMember::Item(item) => validate_item(item),
+
+        ListMember::InnerList(inner_list) => {
+            for item in &inner_list.items {
+                validate_item(item)?;
+            }
+            validate_parameters(&inner_list.parameters)
+        }
+    }
+}
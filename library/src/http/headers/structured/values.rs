@@ -0,0 +1,102 @@
+use super::{
+    super::{super::super::std::string::*, headers::*},
+    dictionary::*,
+    item::*,
+    list::*,
+};
+
+use {
+    http::header::*,
+    std::{result::Result, str::*},
+};
+
+//
+// StructuredHeaderValues
+//
+
+/// Access structured header values.
+///
+/// Multiple header values are combined before parsing, as required by
+/// [IETF RFC 9651 section 4.2](https://datatracker.ietf.org/doc/html/rfc9651#section-4.2).
+/// Parsing is strict: [None] could mean that there is no such header *or* that it is malformed.
+pub trait StructuredHeaderValues {
+    /// Parse a header value as a structured field item.
+    fn structured_item(&self, name: HeaderName) -> Option<StructuredItem>;
+
+    /// Parse a header value as a structured field list.
+    fn structured_list(&self, name: HeaderName) -> Option<StructuredList>;
+
+    /// Parse a header value as a structured field dictionary.
+    fn structured_dictionary(&self, name: HeaderName) -> Option<StructuredDictionary>;
+
+    /// Set a header value to a structured field item.
+    ///
+    /// Makes sure to remove existing values first. Fails if the item cannot be serialized.
+    fn set_structured_item(&mut self, name: HeaderName, item: &StructuredItem) -> Result<(), ParseError>;
+
+    /// Set a header value to a structured field list.
+    ///
+    /// Makes sure to remove existing values first. Fails if the list cannot be serialized.
+    fn set_structured_list(&mut self, name: HeaderName, list: &StructuredList) -> Result<(), ParseError>;
+
+    /// Set a header value to a structured field dictionary.
+    ///
+    /// Makes sure to remove existing values first. Fails if the dictionary cannot be serialized.
+    fn set_structured_dictionary(
+        &mut self,
+        name: HeaderName,
+        dictionary: &StructuredDictionary,
+    ) -> Result<(), ParseError>;
+}
+
+impl StructuredHeaderValues for HeaderMap {
+    fn structured_item(&self, name: HeaderName) -> Option<StructuredItem> {
+        parse_structured(self, name)
+    }
+
+    fn structured_list(&self, name: HeaderName) -> Option<StructuredList> {
+        parse_structured(self, name)
+    }
+
+    fn structured_dictionary(&self, name: HeaderName) -> Option<StructuredDictionary> {
+        parse_structured(self, name)
+    }
+
+    fn set_structured_item(&mut self, name: HeaderName, item: &StructuredItem) -> Result<(), ParseError> {
+        set_serialized(self, name, item.serialize()?)
+    }
+
+    fn set_structured_list(&mut self, name: HeaderName, list: &StructuredList) -> Result<(), ParseError> {
+        set_serialized(self, name, list.serialize()?)
+    }
+
+    fn set_structured_dictionary(
+        &mut self,
+        name: HeaderName,
+        dictionary: &StructuredDictionary,
+    ) -> Result<(), ParseError> {
+        set_serialized(self, name, dictionary.serialize()?)
+    }
+}
+
+fn set_serialized(headers: &mut HeaderMap, name: HeaderName, serialized: String) -> Result<(), ParseError> {
+    headers.set_string_value(name, &serialized).map_err(|error| error.to_string().into())
+}
+
+fn parse_structured<FromStrT>(headers: &HeaderMap, name: HeaderName) -> Option<FromStrT>
+where
+    FromStrT: FromStr<Err = ParseError>,
+{
+    let values = headers.string_values(name.clone());
+    if values.is_empty() {
+        return None;
+    }
+
+    match values.join(",").parse() {
+        Ok(value) => Some(value),
+        Err(error) => {
+            tracing::warn!("malformed {}: {}", name, error);
+            None
+        }
+    }
+}
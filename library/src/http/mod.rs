@@ -1,6 +1,8 @@
+mod authentication;
 mod body;
 mod errors;
 mod headers;
+mod origin;
 mod pieces;
 mod uri;
 
@@ -27,4 +29,4 @@ pub mod tower;
 pub mod transcoding;
 
 #[allow(unused_imports)]
-pub use {body::*, errors::*, headers::*, pieces::*, uri::*};
+pub use {authentication::*, body::*, errors::*, headers::*, origin::*, pieces::*, uri::*};
@@ -0,0 +1,5 @@
+mod network;
+mod origin;
+
+#[allow(unused_imports)]
+pub use {network::*, origin::*};
@@ -0,0 +1,122 @@
+use super::super::super::std::string::*;
+
+use std::{fmt, net::*, result::Result, str::*};
+
+//
+// IPNetwork
+//
+
+/// IP network in CIDR notation, e.g. "10.0.0.0/8" or "fd00::/8".
+///
+/// A bare IP address is a network of just that address.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct IPNetwork {
+    /// Address.
+    pub address: IpAddr,
+
+    /// Prefix length in bits.
+    pub prefix_length: u8,
+}
+
+impl IPNetwork {
+    /// Constructor.
+    pub fn new(address: IpAddr, prefix_length: u8) -> Result<Self, ParseError> {
+        if prefix_length > max_prefix_length(&address) {
+            return Err(format!("prefix length too long: {}", prefix_length).into());
+        }
+
+        Ok(Self { address, prefix_length })
+    }
+
+    /// Whether the network contains the address.
+    ///
+    /// IPv4-mapped IPv6 addresses are treated as IPv4.
+    pub fn contains(&self, address: &IpAddr) -> bool {
+        match (self.address.to_canonical(), address.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(address)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix_length as u32).unwrap_or_default();
+                (network.to_bits() & mask) == (address.to_bits() & mask)
+            }
+
+            (IpAddr::V6(network), IpAddr::V6(address)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix_length as u32).unwrap_or_default();
+                (network.to_bits() & mask) == (address.to_bits() & mask)
+            }
+
+            _ => false,
+        }
+    }
+}
+
+impl From<IpAddr> for IPNetwork {
+    fn from(address: IpAddr) -> Self {
+        Self { address, prefix_length: max_prefix_length(&address) }
+    }
+}
+
+impl FromStr for IPNetwork {
+    type Err = ParseError;
+
+    fn from_str(representation: &str) -> Result<Self, Self::Err> {
+        let representation = representation.trim();
+
+        match representation.split_once('/') {
+            Some((address, prefix_length)) => {
+                let address = address.parse().map_err(|_| ParseError::from("malformed IP address"))?;
+                let prefix_length = prefix_length.parse().map_err(|_| ParseError::from("malformed prefix length"))?;
+                Self::new(address, prefix_length)
+            }
+
+            None => Ok(representation.parse::<IpAddr>().map_err(|_| ParseError::from("malformed IP address"))?.into()),
+        }
+    }
+}
+
+impl fmt::Display for IPNetwork {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}/{}", self.address, self.prefix_length)
+    }
+}
+
+//
+// TrustedProxies
+//
+
+/// Trusted proxies.
+///
+/// Can be parsed from a comma-separated list of networks. The default trusts nothing.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TrustedProxies(pub Vec<IPNetwork>);
+
+impl TrustedProxies {
+    /// Loopback networks ("127.0.0.0/8" and "::1").
+    pub fn loopback() -> Self {
+        Self(vec![
+            IPNetwork { address: Ipv4Addr::LOCALHOST.into(), prefix_length: 8 },
+            IpAddr::from(Ipv6Addr::LOCALHOST).into(),
+        ])
+    }
+
+    /// Whether we trust the address.
+    pub fn trusts(&self, address: &IpAddr) -> bool {
+        self.0.iter().any(|network| network.contains(address))
+    }
+}
+
+impl FromStr for TrustedProxies {
+    type Err = ParseError;
+
+    fn from_str(representation: &str) -> Result<Self, Self::Err> {
+        representation
+            .split(',')
+            .map(|network| network.trim())
+            .filter(|network| !network.is_empty())
+            .map(|network| network.parse())
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+fn max_prefix_length(address: &IpAddr) -> u8 {
+    if address.is_ipv4() { 32 } else { 128 }
+}
@@ -0,0 +1,205 @@
+use super::{
+    super::{super::std::immutable::*, headers::*},
+    network::*,
+};
+
+use {
+    http::{header::*, uri::*},
+    std::{fmt, net::*},
+};
+
+//
+// EffectiveOrigin
+//
+
+/// Effective request origin.
+///
+/// The client address, scheme, host, and port as seen by the client, even if the request came
+/// through trusted proxies. See [resolve](Self::resolve).
+///
+/// Intended to be stored as a request extension, e.g. via
+/// [EffectiveOriginLayer](crate::http::tower::origin::EffectiveOriginLayer), where it will be
+/// consumed by the cache key ([CacheKey::apply_origin](crate::http::cache::CacheKey::apply_origin)),
+/// the host router, and for logging (via [Display](fmt::Display)).
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct EffectiveOrigin {
+    /// Client address.
+    ///
+    /// [None] if unknown, e.g. if obfuscated by a proxy.
+    pub client_address: Option<IpAddr>,
+
+    /// Scheme.
+    ///
+    /// Only [HTTP](Scheme::HTTP) or [HTTPS](Scheme::HTTPS).
+    pub scheme: Option<Scheme>,
+
+    /// Host (without port).
+    pub host: Option<ByteString>,
+
+    /// Port.
+    pub port: Option<u16>,
+}
+
+impl EffectiveOrigin {
+    /// Resolve.
+    ///
+    /// If the peer (the immediate sender of the request) is a trusted proxy then we will use the
+    /// `Forwarded` header or, if it's not present, the `X-Forwarded-For`, `X-Forwarded-Proto`, and
+    /// `X-Forwarded-Host` headers. We go over the proxy chain from the nearest to the farthest and
+    /// stop at the first hop that is not a trusted proxy, which is the client. Otherwise the peer
+    /// is the client and the headers are ignored.
+    ///
+    /// Forwarded values are validated. Malformed or missing values fall back to those of the
+    /// request itself (its [Uri] and `Host` header) and the peer address.
+    pub fn resolve(
+        peer_address: Option<IpAddr>,
+        uri: &Uri,
+        headers: &HeaderMap,
+        trusted_proxies: &TrustedProxies,
+    ) -> Self {
+        let mut origin = Self::direct(peer_address, uri, headers);
+
+        if let Some(peer_address) = peer_address
+            && trusted_proxies.trusts(&peer_address)
+        {
+            let forwarded = headers.forwarded();
+            let (client_address, proto, host) = if !forwarded.is_empty() {
+                let element = &forwarded[client_hop(
+                    forwarded.iter().map(|element| element.for_node.as_ref().and_then(|node| node.address())),
+                    trusted_proxies,
+                )];
+                (
+                    element.for_node.as_ref().map(|node| node.address()),
+                    element.proto.as_deref(),
+                    element.host.as_deref(),
+                )
+            } else {
+                let x_forwarded_for = headers.x_forwarded_for();
+                if x_forwarded_for.is_empty() {
+                    return origin;
+                }
+
+                let hop = client_hop(x_forwarded_for.iter().map(|node| node.address()), trusted_proxies);
+                (
+                    Some(x_forwarded_for[hop].address()),
+                    aligned(headers.x_forwarded_proto(), hop, x_forwarded_for.len()),
+                    aligned(headers.x_forwarded_host(), hop, x_forwarded_for.len()),
+                )
+            };
+
+            // If the hop has no address at all we keep the peer address; an obfuscated one becomes None
+            if let Some(client_address) = client_address {
+                origin.client_address = client_address;
+            }
+
+            if let Some(proto) = proto {
+                match parse_scheme(proto) {
+                    Some(scheme) => origin.scheme = Some(scheme),
+                    None => tracing::warn!("malformed forwarded proto: {}", proto),
+                }
+            }
+
+            if let Some(host) = host {
+                match parse_host(host) {
+                    Some((host, port)) => {
+                        origin.host = Some(host);
+                        origin.port = port;
+                    }
+
+                    None => tracing::warn!("malformed forwarded host: {}", host),
+                }
+            }
+        }
+
+        origin
+    }
+
+    /// Host with optional port.
+    pub fn host_and_optional_port(&self) -> Option<ByteString> {
+        let host = self.host.as_ref()?;
+        Some(match self.port {
+            Some(port) => format!("{}:{}", host, port).into(),
+            None => host.clone(),
+        })
+    }
+
+    // Without proxies
+    fn direct(peer_address: Option<IpAddr>, uri: &Uri, headers: &HeaderMap) -> Self {
+        let scheme = uri.scheme_str().and_then(parse_scheme);
+
+        let host = uri
+            .authority()
+            .map(|authority| authority.as_str())
+            .or_else(|| headers.string_value(HOST))
+            .and_then(parse_host);
+
+        let (host, port) = host.map(|(host, port)| (Some(host), port)).unwrap_or_default();
+
+        Self { client_address: peer_address, scheme, host, port }
+    }
+}
+
+impl fmt::Display for EffectiveOrigin {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(scheme) = &self.scheme {
+            write!(formatter, "{}://", scheme)?;
+        }
+
+        match self.host_and_optional_port() {
+            Some(host) => write!(formatter, "{}", host)?,
+            None => write!(formatter, "?")?,
+        }
+
+        match &self.client_address {
+            Some(client_address) => write!(formatter, " from {}", client_address),
+            None => write!(formatter, " from unknown"),
+        }
+    }
+}
+
+// Index of the hop nearest to us that is not a trusted proxy
+// (or the farthest hop if they are all trusted)
+fn client_hop<IteratorT>(addresses: IteratorT, trusted_proxies: &TrustedProxies) -> usize
+where
+    IteratorT: DoubleEndedIterator<Item = Option<IpAddr>> + ExactSizeIterator,
+{
+    let mut hop = 0;
+    for (index, address) in addresses.enumerate().rev() {
+        hop = index;
+        match address {
+            Some(address) if trusted_proxies.trusts(&address) => {}
+            _ => break,
+        }
+    }
+    hop
+}
+
+// A single value applies to all hops; otherwise the values must be aligned with the hops
+fn aligned(values: Vec<&str>, hop: usize, hops: usize) -> Option<&str> {
+    match values.len() {
+        1 => Some(values[0]),
+        length if length == hops => Some(values[hop]),
+        _ => None,
+    }
+}
+
+fn parse_scheme(scheme: &str) -> Option<Scheme> {
+    if scheme.eq_ignore_ascii_case("http") {
+        Some(Scheme::HTTP)
+    } else if scheme.eq_ignore_ascii_case("https") {
+        Some(Scheme::HTTPS)
+    } else {
+        None
+    }
+}
+
+fn parse_host(host: &str) -> Option<(ByteString, Option<u16>)> {
+    let authority: Authority = host.trim().parse().ok()?;
+
+    // No user info
+    if authority.as_str().contains('@') || authority.host().is_empty() {
+        return None;
+    }
+
+    Some((authority.host().to_lowercase().into(), authority.port_u16()))
+}
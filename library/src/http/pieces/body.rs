@@ -1,6 +1,9 @@
 use super::super::super::std::immutable::*;
 
-use std::{error::*, fmt};
+use {
+    http::HeaderMap,
+    std::{error::*, fmt},
+};
 
 //
 // BodyPieces
@@ -17,12 +20,15 @@ pub struct BodyPieces<BodyT> {
 
     /// First bytes.
     pub first_bytes: Bytes,
+
+    /// Trailers that have already been read from the body.
+    pub trailers: Vec<HeaderMap>,
 }
 
 impl<BodyT> BodyPieces<BodyT> {
     /// Constructor.
-    pub fn new(body: BodyT, first_bytes: Bytes) -> Self {
-        Self { body, first_bytes }
+    pub fn new(body: BodyT, first_bytes: Bytes, trailers: Vec<HeaderMap>) -> Self {
+        Self { body, first_bytes, trailers }
     }
 }
 
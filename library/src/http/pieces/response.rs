@@ -1,7 +1,7 @@
 use super::{super::super::std::immutable::*, body::*};
 
 use {
-    http::response::*,
+    http::{HeaderMap, response::*},
     std::{error::*, fmt},
 };
 
@@ -20,17 +20,20 @@ pub struct ResponsePieces<ResponseBodyT> {
 
     /// First bytes.
     pub first_bytes: Bytes,
+
+    /// Trailers that have already been read from the body.
+    pub trailers: Vec<HeaderMap>,
 }
 
 impl<ResponseBodyT> ResponsePieces<ResponseBodyT> {
     /// Constructor.
-    pub fn new(parts: Parts, body: ResponseBodyT, first_bytes: Bytes) -> Self {
-        Self { response: Response::from_parts(parts, body), first_bytes }
+    pub fn new(parts: Parts, body: ResponseBodyT, first_bytes: Bytes, trailers: Vec<HeaderMap>) -> Self {
+        Self { response: Response::from_parts(parts, body), first_bytes, trailers }
     }
 
     /// Constructor.
     pub fn new_from_body_pieces(parts: Parts, body_pieces: BodyPieces<ResponseBodyT>) -> Self {
-        Self::new(parts, body_pieces.body, body_pieces.first_bytes)
+        Self::new(parts, body_pieces.body, body_pieces.first_bytes, body_pieces.trailers)
     }
 }
 
@@ -0,0 +1,95 @@
+use super::{
+    super::super::{
+        super::std::{immutable::*, string::*},
+        headers::*,
+    },
+    service::*,
+};
+
+use {std::result::Result, tower::*};
+
+//
+// AuthenticationLayer
+//
+
+/// Layer that authenticates requests via the `Authorization` header.
+///
+/// Supports the `Basic` and `Bearer` schemes. Credentials are checked by a pluggable
+/// [CredentialsVerifier](super::super::super::CredentialsVerifier). The resulting
+/// [Authenticated](super::super::super::Authenticated) identity is stored as a request extension.
+///
+/// Requests without acceptable credentials are answered with 401 (Unauthorized) and
+/// `WWW-Authenticate` challenges for all enabled schemes, unless authentication is
+/// [optional](Self::optional).
+///
+/// In order for authenticated responses to be cached (partitioned by identity) this layer should
+/// be applied before the [CachingLayer](super::super::caching::CachingLayer). Otherwise the
+/// caching layer will bypass the cache for all requests with an `Authorization` header.
+#[derive(Clone, Debug)]
+pub struct AuthenticationLayer<VerifierT> {
+    verifier: VerifierT,
+    realm: ByteString,
+    basic: bool,
+    bearer: bool,
+    optional: bool,
+}
+
+impl<VerifierT> AuthenticationLayer<VerifierT> {
+    /// Constructor.
+    ///
+    /// Both `Basic` and `Bearer` are enabled by default.
+    ///
+    /// Fails if the realm contains control characters, because it could not be sent in the
+    /// challenges.
+    pub fn new(verifier: VerifierT, realm: ByteString) -> Result<Self, ParseError> {
+        if !is_quotable(&realm) {
+            return Err(format!("invalid realm: {:?}", realm).into());
+        }
+
+        Ok(Self { verifier, realm, basic: true, bearer: true, optional: false })
+    }
+
+    /// Whether to accept the `Basic` scheme.
+    ///
+    /// The default is true.
+    pub fn basic(mut self, basic: bool) -> Self {
+        self.basic = basic;
+        self
+    }
+
+    /// Whether to accept the `Bearer` scheme.
+    ///
+    /// The default is true.
+    pub fn bearer(mut self, bearer: bool) -> Self {
+        self.bearer = bearer;
+        self
+    }
+
+    /// Whether to let requests without an `Authorization` header through (unauthenticated).
+    ///
+    /// Requests with rejected credentials are still answered with 401.
+    ///
+    /// The default is false.
+    pub fn optional(mut self, optional: bool) -> Self {
+        self.optional = optional;
+        self
+    }
+}
+
+impl<InnerServiceT, VerifierT> Layer<InnerServiceT> for AuthenticationLayer<VerifierT>
+where
+    VerifierT: Clone,
+{
+    type Service = AuthenticationService<InnerServiceT, VerifierT>;
+
+    fn layer(&self, inner_service: InnerServiceT) -> Self::Service {
+        AuthenticationService::new(
+            inner_service,
+            self.verifier.clone(),
+            self.realm.clone(),
+            self.basic,
+            self.bearer,
+            self.optional,
+        )
+    }
+}
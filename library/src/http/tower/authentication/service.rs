@@ -0,0 +1,166 @@
+use super::super::super::{
+    super::std::{future::*, immutable::*},
+    authentication::*,
+    headers::*,
+};
+
+use {
+    http::{request::*, response::*, *},
+    std::{mem, result::Result, task::*},
+    tower::*,
+};
+
+//
+// AuthenticationService
+//
+
+/// Service that authenticates requests.
+///
+/// See [AuthenticationLayer](super::layer::AuthenticationLayer).
+#[derive(Clone, Debug)]
+pub struct AuthenticationService<InnerServiceT, VerifierT> {
+    inner_service: InnerServiceT,
+    verifier: VerifierT,
+    realm: ByteString,
+    basic: bool,
+    bearer: bool,
+    optional: bool,
+}
+
+impl<InnerServiceT, VerifierT> AuthenticationService<InnerServiceT, VerifierT> {
+    /// Constructor.
+    pub fn new(
+        inner_service: InnerServiceT,
+        verifier: VerifierT,
+        realm: ByteString,
+        basic: bool,
+        bearer: bool,
+        optional: bool,
+    ) -> Self {
+        Self { inner_service, verifier, realm, basic, bearer, optional }
+    }
+
+    // Clone while keeping `inner_service`.
+    //
+    // See: https://docs.rs/tower/latest/tower/trait.Service.html#be-careful-when-cloning-inner-services
+    fn clone_and_keep_inner_service(&mut self) -> Self
+    where
+        InnerServiceT: Clone,
+        VerifierT: Clone,
+    {
+        let mut clone = self.clone();
+        clone.inner_service = mem::replace(&mut self.inner_service, clone.inner_service);
+        clone
+    }
+
+    // Whether we accept the scheme.
+    fn accepts(&self, credentials: &Credentials) -> bool {
+        match credentials {
+            Credentials::Basic { .. } => self.basic,
+            Credentials::Bearer(_) => self.bearer,
+            _ => false,
+        }
+    }
+
+    // 401 response with challenges for all enabled schemes.
+    //
+    // If Bearer credentials were rejected we tell the client that the token is invalid.
+    fn unauthorized<ResponseBodyT>(&self, rejected_bearer: bool) -> Response<ResponseBodyT>
+    where
+        ResponseBodyT: From<Bytes>,
+    {
+        let mut challenges = Vec::with_capacity(2);
+
+        if self.basic {
+            challenges.push(Challenge::basic(self.realm.clone()));
+        }
+
+        if self.bearer {
+            let challenge = Challenge::bearer(self.realm.clone());
+            challenges.push(if rejected_bearer {
+                challenge.with_bearer_error(BearerError::InvalidToken, None)
+            } else {
+                challenge
+            });
+        }
+
+        let mut response = Response::new(Bytes::default().into());
+        *response.status_mut() = StatusCode::UNAUTHORIZED;
+        if let Err(error) = response.headers_mut().set_www_authenticate(challenges) {
+            // Should not happen, because the layer validates the realm
+            tracing::warn!("{}", error);
+        }
+        response
+    }
+
+    // Handle request.
+    async fn handle<RequestBodyT, ResponseBodyT>(
+        mut self,
+        mut request: Request<RequestBodyT>,
+    ) -> Result<Response<ResponseBodyT>, InnerServiceT::Error>
+    where
+        InnerServiceT: Service<Request<RequestBodyT>, Response = Response<ResponseBodyT>>,
+        VerifierT: CredentialsVerifier,
+        ResponseBodyT: From<Bytes>,
+    {
+        match request.headers().authorization() {
+            Some(credentials) if self.accepts(&credentials) => {
+                let scheme: ByteString = credentials.scheme().into();
+                let bearer = matches!(credentials, Credentials::Bearer(_));
+
+                match self.verifier.verify(credentials).await {
+                    Some(identity) => {
+                        let authenticated = Authenticated::new(scheme, identity);
+                        tracing::debug!("{}", authenticated);
+                        request.extensions_mut().insert(authenticated);
+                    }
+
+                    None => {
+                        tracing::debug!("rejected credentials ({})", scheme);
+                        return Ok(self.unauthorized(bearer));
+                    }
+                }
+            }
+
+            Some(credentials) => {
+                tracing::debug!("unsupported scheme: {}", credentials.scheme());
+                return Ok(self.unauthorized(false));
+            }
+
+            None => {
+                if request.headers().contains_key(header::AUTHORIZATION) {
+                    tracing::debug!("malformed {}", header::AUTHORIZATION);
+                    return Ok(self.unauthorized(false));
+                } else if !self.optional {
+                    tracing::debug!("missing {}", header::AUTHORIZATION);
+                    return Ok(self.unauthorized(false));
+                }
+            }
+        }
+
+        self.inner_service.call(request).await
+    }
+}
+
+impl<InnerServiceT, VerifierT, RequestBodyT, ResponseBodyT> Service<Request<RequestBodyT>>
+    for AuthenticationService<InnerServiceT, VerifierT>
+where
+    InnerServiceT: 'static + Service<Request<RequestBodyT>, Response = Response<ResponseBodyT>> + Clone + Send,
+    InnerServiceT::Future: Send,
+    VerifierT: 'static + CredentialsVerifier + Clone + Send + Sync,
+    RequestBodyT: 'static + Send,
+    ResponseBodyT: From<Bytes>,
+{
+    type Response = InnerServiceT::Response;
+    type Error = InnerServiceT::Error;
+    type Future = CapturedFuture<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, context: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner_service.poll_ready(context)
+    }
+
+    fn call(&mut self, request: Request<RequestBodyT>) -> Self::Future {
+        let cloned_self = self.clone_and_keep_inner_service();
+        capture_async! { cloned_self.handle(request).await }
+    }
+}
@@ -1,5 +1,6 @@
 use super::{
     super::super::{
+        super::{std::immutable::*, transcoding::*},
         cache::{middleware::*, *},
         headers::*,
     },
@@ -29,15 +30,26 @@ use {
 /// resources reencoding data that has not changed.
 ///
 /// This layer also participates in client-side caching (conditional HTTP). A cache hit will
-/// respect the client's `If-None-Match` and `If-Modified-Since` headers and return a 304 (Not
-/// Modified) when appropriate, saving bandwidth as well as compute resources. If you don't set a
+/// evaluate the client's preconditions (`If-Match`, `If-Unmodified-Since`, `If-None-Match`, and
+/// `If-Modified-Since`) and return a 304 (Not Modified) or 412 (Precondition Failed) when
+/// appropriate, saving bandwidth as well as compute resources. See
+/// [evaluate_preconditions](crate::http::evaluate_preconditions). If you don't set a
 /// `Last-Modified` header yourself then this layer will default to the instant in which the cache
 /// entry was created.
 ///
+/// Responses from the cache will have an `Age` header, which is the time since the entry was
+/// stored (plus the upstream's `Age`, if it had one). You can also enable the `Cache-Status` header
+/// ([IETF RFC 9211](https://datatracker.ietf.org/doc/html/rfc9211)) and/or the legacy `X-Cache`
+/// header to let clients (and CDNs) know whether the response came from this cache. See
+/// [cache_status](Self::cache_status) and [x_cache](Self::x_cache).
+///
 /// For encoding we support the web's common compression formats: Brotli, Deflate, GZip, and
 /// Zstandard. We select the best encoding according to our and the client's preferences (HTTP
 /// content negotiation).
 ///
+/// We also support compression dictionary transport (Brotli and Zstandard with a dictionary) for
+/// cached responses. See [compression_dictionaries](Self::compression_dictionaries).
+///
 /// The cache and cache key implementations are provided as generic type parameters. The
 /// [CommonCacheKey] implementation should suffice for common use cases.
 ///
@@ -86,12 +98,17 @@ use {
 ///    ([Here](https://docs.rs/moka/latest/moka/policy/trait.Expiry.html#method.expire_after_create)
 ///    is the logic used for the Moka implementation.)
 ///
-/// 4. Though this layer transparently handles HTTP content negotiation for `Accept-Encoding`, for
-///    which the underlying content is the same, it cannot do so for `Accept` and
-///    `Accept-Language`, for which content can differ. We do, however, provide a solution for
-///    situations in which negotiation can be handled *without* the upstream response: the
-///    [cache_key](Self::cache_key) hook. Here you can handle negotiation yourself and update the
-///    cache key accordingly, so that different content will be cached separately. [CommonCacheKey]
+/// 4. This layer transparently handles HTTP content negotiation for `Accept-Encoding`, for which
+///    the underlying content is the same. For `Accept` and `Accept-Language`, for which content
+///    can differ, you can declare the media types and languages that a route can produce via
+///    [variants](Self::variants). We will then negotiate them with the request, respond with 406
+///    (Not Acceptable) if nothing fits, and otherwise insert the [NegotiatedVariant] as a request
+///    extension so that your handler knows which variant to produce. The variant is also applied
+///    to the cache key (see [CacheKey::apply_variant]), so that different content will be cached
+///    separately, and the `Vary` response header is set accordingly.
+///
+///    For more elaborate negotiation you can use the [cache_key](Self::cache_key) hook. Here you
+///    can handle negotiation yourself and update the cache key accordingly. [CommonCacheKey]
 ///    reserves fields for media type and languages, just for this purpose.
 ///
 ///    If this impossible or too cumbersome, the alternative to content negotiation is to make
@@ -100,15 +117,22 @@ use {
 ///    this for users by switching to the appropriate URL, for example adding "/en" to the path to
 ///    select English.
 ///
+/// 5. Requests with an `Authorization` header skip the cache, because the response might be
+///    specific to the user. The exception is requests that carry an
+///    [Authenticated](crate::http::Authenticated) extension, e.g. via the
+///    [AuthenticationLayer](super::super::authentication::AuthenticationLayer) applied before this
+///    layer. Their cache key is partitioned by identity (see [CacheKey::apply_authenticated]), so
+///    that cached responses never leak across users.
+///
 /// General advice
 /// ==============
 ///
 /// 1. Compressing already-compressed content is almost always a waste of compute for both the
-///    server and the client. For this reason it's a good idea to explicitly skip the encoding of
+///    server and the client. For this reason by default we skip the encoding of
 ///    [MIME types](https://developer.mozilla.org/en-US/docs/Web/HTTP/Guides/MIME_types/Common_types)
 ///    that are known to be already-compressed, such as those for audio, video, and images. You can
-///    do this via the [encodable_by_response](Self::encodable_by_response) hook mentioned above.
-///    (See the example.)
+///    configure this via [encodable_by_media_type](Self::encodable_by_media_type). Make sure to
+///    set the `Content-Type` header on your responses.
 ///
 /// 2. We advise setting the `Content-Length` header on your responses whenever possible as it
 ///    allows this layer to check for cacheability without having to read the body, and it's
@@ -129,6 +153,9 @@ use {
 ///       degradation (as well as outright failure) for busy, resource-heavy servers. You might
 ///       want to initialize your cache with popular entries before opening your server to
 ///       requests. If your cache is distributed it might also mean syncing the cache first.
+///       For an in-process cache you can export a
+///       [CacheSnapshot](crate::http::cache::snapshot::CacheSnapshot) to a file on shutdown and
+///       import it on startup.
 ///
 ///    2. Invalidating cache entries manually can be critical for ensuring that clients don't
 ///       see out-of-date data, especially when your cache durations are long. For example, when
@@ -137,6 +164,15 @@ use {
 ///       keys. When invalidating, you can then enumerate all existing keys that contain the
 ///       relevant ID. [CommonCacheKey] reserves an `extensions` fields just for this purpose.
 ///
+///       Alternatively, you can tag responses via the `XX-Cache-Tags` header (a comma-separated
+///       list) and then invalidate all entries with a tag via
+///       [EnumerableCache::invalidate_tag](crate::http::cache::EnumerableCache::invalidate_tag).
+///
+///    3. If you run several processes with their own in-process caches (e.g. for zero-downtime
+///       restarts) you can wrap each cache with a
+///       [BroadcastCache](crate::http::cache::bus::BroadcastCache) so that invalidations will be
+///       propagated to all of them.
+///
 /// Request handling
 /// ================
 ///
@@ -144,26 +180,45 @@ use {
 ///
 /// 1. A request arrives. Check if it is cacheable (for now). Reasons it won't be cacheable:
 ///
+///    * It is a protocol upgrade request (`Connection: upgrade` header, e.g. for WebSocket, or
+///      the `CONNECT` method)
 ///    * Caching is disabled for this layer
-///    * The request is non-idempotent (e.g. POST)
+///    * The request is non-idempotent (e.g. POST), unless it is a POST that has been opted in via
+///      the [cacheable_post](Self::cacheable_post) hook
 ///    * If we pass the checks above then we give the
 ///      [cacheable_by_request](Self::cacheable_by_request) hook a chance to skip caching.
 ///      If it returns false then we are non-cacheable.
 ///
 ///    If the response is non-cacheable then go to "Non-cached request handling" below.
 ///
-/// 2. Check if we have a cached response.
+///    For an opted-in POST we buffer the body if its `Content-Length` is within
+///    [max_cacheable_post_body_size](Self::max_cacheable_post_body_size) and create the cache key
+///    from it. Otherwise it is non-cacheable.
+///
+///    The cache key is created with our [normalize_cache_key](Self::normalize_cache_key)
+///    configuration and then given to the [cache_key](Self::cache_key) hook.
+///
+/// 2. Check if we have a cached response. If the entry has been in the cache longer than its
+///    duration (stale) then it is invalidated and we continue as if we don't have it.
+///
+///    A `HEAD` request is looked up with the same cache key as a `GET` request would be. A hit
+///    will be handled as below but sent without a body (the headers, including `Content-Length`,
+///    will be those of the selected encoding). A miss will be sent to the upstream and passed
+///    through without caching, as an empty `HEAD` body must never be stored for a later `GET`.
 ///
 /// 3. If we do, then:
 ///
-///    1. Select the best encoding according to our configured preferences and the priorities
-///       specified in the request's `Accept-Encoding`. If the cached response has `XX-Encode`
+///    1. Select the best encoding according to our configured preferences (for the cached
+///       response's media type, see [encodings_for_media_type](Self::encodings_for_media_type))
+///       and the priorities specified in the request's `Accept-Encoding`. If the cached response
+///       has `XX-Encode`
 ///       header as "false" then use Identity encoding.
 ///
 ///    2. If we have that encoding in the cache then:
 ///
-///       1. If the client sent `If-Modified-Since` then compare with our cached `Last-Modified`,
-///          and if not modified then send a 304 (Not Modified) status (conditional HTTP). END.
+///       1. Evaluate the client's preconditions against our cached `ETag` and `Last-Modified`. If
+///          not modified then send a 304 (Not Modified) status, and if a precondition failed then
+///          send a 412 (Precondition Failed) status (conditional HTTP). END.
 ///
 ///       2. Otherwise create a response from the cache entry and send it. Note that we know its
 ///          size so we set `Content-Length` accordingly. END.
@@ -181,12 +236,19 @@ use {
 ///
 ///    6. Encode the body and store it in the cache.
 ///
+///       If we are under [memory_pressure](Self::memory_pressure) then only one representation
+///       (encoding) of the body is kept in the cache. This also happens for hits that don't
+///       require reencoding.
+///
 ///    7. Go up to step 3.2.2.
 ///
 /// 4. If we don't have a cached response:
 ///
 ///    1. Get the upstream response and check if it is cacheable. Reasons it won't be cacheable:
 ///
+///       * It is a protocol upgrade (status 101) or a streaming response (media type in
+///         [streaming_media_types](Self::streaming_media_types) or a `X-Accel-Buffering` header
+///         as "no"). These are checked before anything else, so we never buffer them.
 ///       * Its status code is not "success" (200 to 299)
 ///       * Its `XX-Cache` header is "false"
 ///       * It has a `Content-Range` header (we don't cache partial responses)
@@ -195,6 +257,9 @@ use {
 ///       * If we pass all the checks above then we give the
 ///         [cacheable_by_response](Self::cacheable_by_response) hook one last chance to skip
 ///         caching. If it returns false then we are non-cacheable.
+///       * Finally, if we have an [admission](Self::admission) policy, it records the request
+///         and may refuse to admit the cache key (e.g. if it hasn't been requested often
+///         enough). If so then we are non-cacheable.
 ///
 ///       If the upstream response is non-cacheable then go to "Non-cached request handling" below.
 ///
@@ -203,9 +268,11 @@ use {
 ///       `XX-Encode` header as "false" or has `Content-Length` smaller than our configured
 ///       minimum, then use Identity encoding.
 ///
-///    3. If the selected encoding is not Identity then we give the
+///    3. If the selected encoding is not Identity then we reselect it according to the
+///       preferences for the response's media type (if we have any), check the media type
+///       against [encodable_by_media_type](Self::encodable_by_media_type) and give the
 ///       [encodable_by_response](Self::encodable_by_response) hook one last chance to skip
-///       encoding. If it returns false we set the encoding to Identity and add the `XX-Encode`
+///       encoding. If either says no we set the encoding to Identity and add the `XX-Encode`
 ///       header as "true" for use by step 3.1 above.
 ///
 ///    4. Read the upstream response body into a buffer. If there is no `Content-Length` header
@@ -214,41 +281,50 @@ use {
 ///    5. If there's still more data left or the data that was read is less than our configured
 ///       minimum size then it means the upstream response is non-cacheable, so:
 ///
-///       1. Push the data that we read back into the front of the upstream response body.
+///       1. Push the data that we read back into the front of the upstream response body (as
+///          well as any trailers that we read).
 ///
-///       2. Go to "Non-cached request handling" step 4 below.
+///       2. Go to "Non-cached request handling" step 5 below.
 ///
 ///    6. Otherwise store the read bytes in the cache, encoding them if necessary. We know the
 ///       size, so we can check if it's smaller than the configured minimum for encoding, in
 ///       which case we use Identity encoding. We also make sure to set the cached `Last-Modified`
 ///       header to the current time if the header wasn't already set. Go up to step 3.2.
 ///
-///       Note that upstream response trailers are discarded and *not* stored in the cache. (We
-///       make the assumption that trailers are only relevant to "real" responses.)
+///       Upstream response trailers are stored in the cache, too, and will be replayed after the
+///       body on hits (but not for `HEAD`).
 ///
 /// ### Non-cached request handling
 ///
-/// 1. If the upstream response has `XX-Encode` header as "false" or has `Content-Length` smaller
+/// 1. If the upstream response is a protocol upgrade (status 101) then pass it through as is.
+///    THE END.
+///
+///    If it is a streaming response then continue, but if we do end up encoding it the encoder
+///    will be flushed after every upstream data frame so that events won't be delayed.
+///
+/// 2. If the upstream response has `XX-Encode` header as "false" or has `Content-Length` smaller
 ///    than our configured minimum, then pass it through as is. THE END.
 ///
 ///    Note that without `Content-Length` there is no way for us to check against the minimum and
 ///    so we must continue.
 ///
-/// 2. Select the best encoding according to our configured preferences and the priorities
+/// 3. Select the best encoding according to our configured preferences and the priorities
 ///    specified in the request's `Accept-Encoding`.
 ///
-/// 3. If the selected encoding is not Identity then we give the
+/// 4. If the selected encoding is not Identity then we reselect it according to the preferences
+///    for the response's media type (if we have any), check the media type against
+///    [encodable_by_media_type](Self::encodable_by_media_type) and give the
 ///    [encodable_by_request](Self::encodable_by_request) and
 ///    [encodable_by_response](Self::encodable_by_response) hooks one last chance to skip encoding.
-///    If either returns false we set the encoding to Identity.
+///    If any says no we set the encoding to Identity.
 ///
-/// 4. If the upstream response is already in the selected encoding then pass it through. END.
+/// 5. If the upstream response is already in the selected encoding then pass it through. END.
 ///
-/// 5. Otherwise, if the upstream response is Identity, then wrap it in an encoder and send it
+/// 6. Otherwise, if the upstream response is Identity, then wrap it in an encoder and send it
 ///    downstream. Note that we do not know the encoded size in advance so we make sure there is no
 ///    `Content-Length` header. END.
 ///
-/// 6. However, if the upstream response is *not* Identity, then just pass it through as is. END.
+/// 7. However, if the upstream response is *not* Identity, then just pass it through as is. END.
 ///
 ///    Note that this is technically wrong and in fact there is no guarantee here that the client
 ///    would support the upstream response's encoding. However, we implement it this way because:
@@ -342,6 +418,53 @@ where
         self
     }
 
+    /// Provide a hook to opt in `POST` requests for caching.
+    ///
+    /// `POST` requests are not cached by default because they are non-idempotent. However, some
+    /// `POST` endpoints are in effect idempotent queries (e.g. GraphQL or search), for which the
+    /// response depends on the request body. For these requests the body will be buffered (up to
+    /// [max_cacheable_post_body_size](Self::max_cacheable_post_body_size)) and included in the
+    /// cache key via [CacheKey::for_request_with_body]. The buffered body is then passed on to
+    /// the upstream.
+    ///
+    /// Requests without a `Content-Length` header are not buffered and will not be cached.
+    ///
+    /// Note that the headers are *request* headers.
+    ///
+    /// [None] by default.
+    pub fn cacheable_post(
+        mut self,
+        cacheable_post: impl Fn(CacheableHookContext) -> bool + 'static + Send + Sync,
+    ) -> Self {
+        self.caching.cacheable_post = Some(Arc::new(Box::new(cacheable_post)));
+        self
+    }
+
+    /// Maximum size in bytes of `POST` request bodies to buffer for caching.
+    ///
+    /// See [cacheable_post](Self::cacheable_post).
+    ///
+    /// The default is 64 KiB.
+    pub fn max_cacheable_post_body_size(mut self, max_cacheable_post_body_size: usize) -> Self {
+        self.caching.max_post_body_size = max_cacheable_post_body_size;
+        self
+    }
+
+    /// Cache key normalization.
+    ///
+    /// Allows URIs that differ in insignificant ways (e.g. tracking query parameters, trailing
+    /// slashes) to share a cache entry. Applied before the [cache_key](Self::cache_key) hook.
+    /// Path normalization is also applied to the request forwarded to the inner service.
+    ///
+    /// Note that if you create keys manually (e.g. for invalidation) you should use
+    /// [CacheKey::for_normalized_request] with the same normalization.
+    ///
+    /// The default is [CacheKeyNormalization::NONE].
+    pub fn normalize_cache_key(mut self, key_normalization: CacheKeyNormalization) -> Self {
+        self.caching.key_normalization = key_normalization;
+        self
+    }
+
     /// [None] by default.
     pub fn cache_key(
         mut self,
@@ -351,6 +474,33 @@ where
         self
     }
 
+    /// Declare the content variants that a route can produce.
+    ///
+    /// The route is a path prefix, matched at path segment boundaries. If several routes match
+    /// then the longest wins. Can be called multiple times.
+    ///
+    /// The negotiated variant is applied to the cache key *before* the
+    /// [cache_key](Self::cache_key) hook.
+    ///
+    /// See [Variants::negotiate].
+    pub fn variants(mut self, path_prefix: ByteString, variants: Variants) -> Self {
+        self.caching.variants = self.caching.variants.with_route(path_prefix, variants);
+        self
+    }
+
+    /// Set an admission policy.
+    ///
+    /// The policy is consulted on every miss for which the upstream response is cacheable,
+    /// *before* we read and encode its body. If the policy does not admit the key then the
+    /// response is passed through without being stored. See [WindowAdmission] and
+    /// [FrequencySketchAdmission].
+    ///
+    /// [None] by default, meaning that all cacheable responses are stored.
+    pub fn admission(mut self, admission: impl AdmissionPolicy<CacheKeyT> + 'static) -> Self {
+        self.caching.admission = Some(Arc::new(admission));
+        self
+    }
+
     /// Provide a hook to get a response's cache duration.
     ///
     /// Will only be called if an `XX-Cache-Duration` response header is *not* provided. In other
@@ -367,13 +517,48 @@ where
         self
     }
 
+    /// Enable the `Cache-Status` response header
+    /// ([IETF RFC 9211](https://datatracker.ietf.org/doc/html/rfc9211)) with this cache name.
+    ///
+    /// Our entry reports a hit (with the remaining TTL if the entry has a duration) or why the
+    /// request was forwarded to the upstream ("bypass", "miss", or "stale"), and whether the
+    /// upstream response was stored.
+    ///
+    /// Not enabled by default.
+    pub fn cache_status(mut self, cache_name: &str) -> Self {
+        self.caching.cache_status_name = Some(cache_name.into());
+        self
+    }
+
+    /// Whether to set the legacy `X-Cache` response header to "HIT" or "MISS".
+    ///
+    /// The default is false.
+    pub fn x_cache(mut self, x_cache: bool) -> Self {
+        self.caching.x_cache = x_cache;
+        self
+    }
+
+    /// Media types of streaming responses.
+    ///
+    /// Such responses (as well as those with a `X-Accel-Buffering` header as "no") are never
+    /// buffered nor cached. Other long-lived responses (e.g. long polling) should send
+    /// `X-Accel-Buffering: no`, otherwise they will be buffered. If they are encoded, the encoder is flushed after every upstream data
+    /// frame so that events won't be delayed.
+    ///
+    /// The default is [STREAMING_MEDIA_TYPES](crate::http::cache::middleware::STREAMING_MEDIA_TYPES).
+    pub fn streaming_media_types(mut self, streaming_media_types: Vec<MediaTypeSelector>) -> Self {
+        self.caching.streaming_media_types = streaming_media_types;
+        self
+    }
+
     /// Enable encodings in order from most preferred to least.
     ///
     /// Will be negotiated with the client's preferences (in its `Accept-Encoding` header) to
     /// select the best.
     ///
     /// There is no need to specify [Identity](crate::transcoding::Encoding::Identity) as it is
-    /// always enabled.
+    /// always enabled. Encodings with a dictionary are negotiated separately, see
+    /// [compression_dictionaries](Self::compression_dictionaries).
     ///
     /// The default is [ENCODINGS_BY_PREFERENCE].
     pub fn enable_encodings(mut self, enabled_encodings_by_preference: Vec<EncodingHeaderValue>) -> Self {
@@ -381,6 +566,24 @@ where
         self
     }
 
+    /// Encodings in order from most preferred to least for matching media types. Overrides
+    /// [enable_encodings](Self::enable_encodings) for them. Can be called multiple times, in
+    /// which case the first match wins.
+    ///
+    /// Useful because the best encoding can depend on the content, e.g. you might prefer
+    /// [Zstandard](EncodingHeaderValue::Zstandard) for JSON and [Brotli](EncodingHeaderValue::Brotli)
+    /// for HTML.
+    ///
+    /// Ignored if encoding is disabled.
+    pub fn encodings_for_media_type(
+        mut self,
+        media_type: MediaTypeSelector,
+        encodings_by_preference: Vec<EncodingHeaderValue>,
+    ) -> Self {
+        self.encoding.encodings_by_media_type.push(MediaTypeEncodings::new(media_type, encodings_by_preference));
+        self
+    }
+
     /// Disables encoding.
     ///
     /// The default is [ENCODINGS_BY_PREFERENCE].
@@ -443,6 +646,18 @@ where
         self
     }
 
+    /// Set the policy for encoding by the response's media type (from its `Content-Type`).
+    ///
+    /// Checked before the [encodable_by_response](Self::encodable_by_response) hook.
+    ///
+    /// The default never encodes already-compressed media types, such as images (except SVG),
+    /// video, audio, and archives, and always encodes textual media types, such as text, JSON,
+    /// JavaScript, XML, and WebAssembly. See [MediaTypeEncodingPolicy::default].
+    pub fn encodable_by_media_type(mut self, encodable_by_media_type: MediaTypeEncodingPolicy) -> Self {
+        self.encoding.encodable_by_media_type = encodable_by_media_type;
+        self
+    }
+
     /// Whether to keep an [Identity](crate::transcoding::Encoding::Identity) in the cache if it is
     /// created during reencoding.
     ///
@@ -453,6 +668,44 @@ where
         self.encoding.inner.keep_identity_encoding = keep_identity_encoding;
         self
     }
+
+    /// Drop secondary representations (encodings) of cached bodies when under memory pressure.
+    ///
+    /// A cached body can accumulate several encodings, all counting towards its cache weight.
+    /// Under pressure we will keep only one of them according to the retention, thus making room
+    /// before entire entries have to be evicted. The trade-off is that requests for the dropped
+    /// encodings will require reencoding. See
+    /// [MokaMemoryPressure](crate::http::cache::implementation::moka::MokaMemoryPressure).
+    ///
+    /// [None] by default, meaning that we are never under pressure.
+    pub fn memory_pressure(
+        mut self,
+        memory_pressure: impl MemoryPressure + 'static,
+        retention: RepresentationRetention,
+    ) -> Self {
+        self.encoding.inner.memory_pressure = Some(Arc::new(memory_pressure));
+        self.encoding.inner.retention = retention;
+        self
+    }
+
+    /// Enable compression dictionary transport
+    /// ([IETF RFC 9842](https://datatracker.ietf.org/doc/rfc9842/)).
+    ///
+    /// When a request's `Available-Dictionary` header identifies a dictionary that we have and its
+    /// `Accept-Encoding` allows for `dcb` or `dcz` then cached responses will be encoded with that
+    /// dictionary. These representations are cached per dictionary and such responses will have a
+    /// `Vary: accept-encoding, available-dictionary` header. Non-cached responses are never encoded
+    /// with a dictionary.
+    ///
+    /// Cached responses with a `Use-As-Dictionary` header are registered automatically (see
+    /// [UseAsDictionary]). You can also register dictionaries yourself, as clones of `dictionaries`
+    /// share the same registry.
+    ///
+    /// [None] by default, meaning that encodings with a dictionary are disabled.
+    pub fn compression_dictionaries(mut self, dictionaries: Dictionaries) -> Self {
+        self.encoding.inner.dictionaries = Some(dictionaries);
+        self
+    }
 }
 
 impl<RequestBodyT, CacheT, CacheKeyT> Default for CachingLayer<RequestBodyT, CacheT, CacheKeyT>
@@ -1,5 +1,9 @@
 mod layer;
 mod service;
 
+/// Test utilities for the caching layer.
+#[cfg(feature = "test_util")]
+pub mod testing;
+
 #[allow(unused_imports)]
 pub use {layer::*, service::*};
@@ -1,14 +1,18 @@
 use super::super::super::{
-    super::std::{error::*, future::*, immutable::*},
+    super::{
+        std::{error::*, future::*, immutable::*},
+        transcoding::Encoding,
+    },
+    body::*,
     cache::{middleware::*, *},
     headers::*,
     transcoding::*,
 };
 
 use {
-    http::{request::*, response::*},
+    http::{request::*, response::*, *},
     http_body::*,
-    std::{convert::*, mem, result::Result, sync::*, task::*},
+    std::{borrow::*, convert::*, mem, result::Result, sync::*, task::*},
     tower::*,
 };
 
@@ -57,67 +61,341 @@ where
     }
 
     // Handle request.
+    //
+    // Normalizes the path first, so that the upstream sees the same path as the cache key. Then
+    // negotiates the content variant (if the route has registered variants) before anything else,
+    // because both the handler and the cache key depend on it.
     async fn handle<ResponseBodyT>(
-        mut self,
+        self,
+        mut request: Request<RequestBodyT>,
+    ) -> Result<Response<TranscodingBody<ResponseBodyT>>, InnerServiceT::Error>
+    where
+        InnerServiceT: Service<Request<RequestBodyT>, Response = Response<ResponseBodyT>>,
+        RequestBodyT: Body + From<Bytes> + Unpin,
+        RequestBodyT::Error: Into<CapturedError>,
+        ResponseBodyT: 'static + Body + From<Bytes> + Send + Unpin,
+        ResponseBodyT::Data: From<Bytes> + Send,
+        ResponseBodyT::Error: Into<CapturedError>,
+    {
+        if let Cow::Owned(uri) = self.caching.key_normalization.normalize_uri_path(request.uri()) {
+            tracing::debug!("normalized path: {} -> {}", request.uri().path(), uri.path());
+            *request.uri_mut() = uri;
+        }
+
+        let vary = match self.caching.variants.variants_for(request.uri().path()) {
+            Some(variants) => {
+                let vary = variants.vary();
+
+                match variants.negotiate(request.headers()) {
+                    Some(variant) => {
+                        tracing::debug!("negotiated variant: {:?}", variant);
+                        request.extensions_mut().insert(variant);
+                    }
+
+                    None => {
+                        tracing::debug!("not acceptable");
+                        let mut response = not_acceptable_transcoding_response();
+                        response.headers_mut().add_vary(&vary);
+                        return Ok(response);
+                    }
+                }
+
+                vary
+            }
+
+            None => Default::default(),
+        };
+
+        let mut response = self.handle_negotiated(request).await?;
+        if !vary.is_empty() {
+            response.headers_mut().add_vary(&vary);
+        }
+        Ok(response)
+    }
+
+    // Handle request after content negotiation.
+    async fn handle_negotiated<ResponseBodyT>(
+        self,
         request: Request<RequestBodyT>,
     ) -> Result<Response<TranscodingBody<ResponseBodyT>>, InnerServiceT::Error>
     where
         InnerServiceT: Service<Request<RequestBodyT>, Response = Response<ResponseBodyT>>,
+        RequestBodyT: Body + From<Bytes> + Unpin,
+        RequestBodyT::Error: Into<CapturedError>,
         ResponseBodyT: 'static + Body + From<Bytes> + Send + Unpin,
         ResponseBodyT::Data: From<Bytes> + Send,
         ResponseBodyT::Error: Into<CapturedError>,
     {
         if request.should_skip_cache(&self.caching) {
-            // Capture request data before moving the request to the inner service
-            let uri = request.uri().clone();
-            let encoding = request.select_encoding(&self.encoding);
-            let content_length = request.headers().content_length();
-
-            return self.inner_service.call(request).await.map(|upstream_response| {
-                let (encoding, _skip_encoding) =
-                    upstream_response.validate_encoding(&uri, encoding, content_length, &self.encoding);
-                upstream_response.with_transcoding_body(&encoding, self.encoding.inner.encodable_by_default)
-            });
+            return self.bypass(request).await;
+        }
+
+        let (request, cache_key) = if request.method() == Method::POST {
+            // The cache key depends on the body, so we must buffer it (and we only do so if we
+            // know its size in advance)
+            let content_length = match request.headers().content_length() {
+                Some(content_length) if content_length <= self.caching.max_post_body_size => content_length,
+
+                _ => {
+                    tracing::debug!("skip (POST body size)");
+                    return self.bypass(request).await;
+                }
+            };
+
+            let (parts, body) = request.into_parts();
+            let body = match body.read_into_bytes_or_pieces(Some(content_length), 0, content_length).await {
+                Ok((body, _trailers)) => body,
+
+                Err(error) => {
+                    tracing::warn!("could not read POST body: {}", error.error);
+                    let mut response = error_transcoding_response();
+                    *response.status_mut() = StatusCode::BAD_REQUEST;
+                    return Ok(response);
+                }
+            };
+
+            // Upstream gets the buffered body
+            let request = Request::from_parts(parts, body.clone().into());
+
+            match request.cache_key_with_body_and_hook(&body, &self.caching) {
+                Some(cache_key) => (request, cache_key),
+
+                None => {
+                    tracing::debug!("skip (cache key does not support body or authentication)");
+                    return self.bypass(request).await;
+                }
+            }
+        } else {
+            match request.cache_key_with_hook(&self.caching) {
+                Some(cache_key) => (request, cache_key),
+
+                None => {
+                    tracing::debug!("skip (cache key does not support authentication)");
+                    return self.bypass(request).await;
+                }
+            }
+        };
+
+        self.handle_cacheable(request, cache_key).await
+    }
+
+    // Handle request without the cache.
+    async fn bypass<ResponseBodyT>(
+        mut self,
+        request: Request<RequestBodyT>,
+    ) -> Result<Response<TranscodingBody<ResponseBodyT>>, InnerServiceT::Error>
+    where
+        InnerServiceT: Service<Request<RequestBodyT>, Response = Response<ResponseBodyT>>,
+        ResponseBodyT: 'static + Body + From<Bytes> + Send + Unpin,
+        ResponseBodyT::Data: From<Bytes> + Send,
+        ResponseBodyT::Error: Into<CapturedError>,
+    {
+        // Capture request data before moving the request to the inner service
+        let uri = request.uri().clone();
+        let encoding = request.select_encoding(&self.encoding);
+        let accept_encoding = request.accept_encoding_for_upstream(&self.encoding);
+        let content_length = request.headers().content_length();
+
+        self.inner_service.call(request).await.map(|upstream_response| {
+            let mut response =
+                self.uncached_response(upstream_response, &uri, encoding, accept_encoding.as_ref(), content_length);
+            CacheStatus::forward(CacheForward::Bypass, false).set_headers(response.headers_mut(), &self.caching);
+            response
+        })
+    }
+
+    // Response that will not be cached.
+    //
+    // Upgrade responses are passed through as is, and streaming responses are encoded with
+    // flushing after every upstream frame.
+    fn uncached_response<ResponseBodyT>(
+        &self,
+        upstream_response: Response<ResponseBodyT>,
+        uri: &Uri,
+        encoding: Encoding,
+        accept_encoding: Option<&Preferences<EncodingHeaderValue>>,
+        content_length: Option<usize>,
+    ) -> Response<TranscodingBody<ResponseBodyT>>
+    where
+        ResponseBodyT: Body,
+        ResponseBodyT::Error: Into<CapturedError>,
+    {
+        if upstream_response.is_upgrade() {
+            return upstream_response.with_transcoding_body_passthrough();
         }
 
+        let (encoding, _skip_encoding) =
+            upstream_response.validate_encoding(uri, encoding, accept_encoding, content_length, &self.encoding);
+
+        if upstream_response.is_streaming(&self.caching) {
+            upstream_response.with_streaming_transcoding_body(&encoding, self.encoding.inner.encodable_by_default)
+        } else {
+            upstream_response.with_transcoding_body(&encoding, self.encoding.inner.encodable_by_default)
+        }
+    }
+
+    // Handle cacheable request.
+    async fn handle_cacheable<ResponseBodyT>(
+        mut self,
+        request: Request<RequestBodyT>,
+        cache_key: CacheKeyT,
+    ) -> Result<Response<TranscodingBody<ResponseBodyT>>, InnerServiceT::Error>
+    where
+        InnerServiceT: Service<Request<RequestBodyT>, Response = Response<ResponseBodyT>>,
+        ResponseBodyT: 'static + Body + From<Bytes> + Send + Unpin,
+        ResponseBodyT::Data: From<Bytes> + Send,
+        ResponseBodyT::Error: Into<CapturedError>,
+    {
         let cache = self.caching.cache.clone().expect("has cache");
-        let cache_key = request.cache_key_with_hook(&self.caching);
+        let is_head = request.method() == Method::HEAD;
 
-        match cache.get(&cache_key).await {
+        let (cached_response, forward) = match cache.get(&cache_key).await {
+            Some(cached_response) if cached_response.is_stale() => {
+                tracing::debug!("stale");
+                cache.invalidate_local(&cache_key).await;
+                (None, CacheForward::Stale)
+            }
+
+            cached_response => (cached_response, CacheForward::Miss),
+        };
+
+        match cached_response {
             Some(cached_response) => Ok({
-                if modified(request.headers(), cached_response.headers()) {
-                    tracing::debug!("hit");
+                let status = CacheStatus::hit(cached_response.ttl());
 
-                    cached_response
-                        .to_transcoding_response(
-                            &request.select_encoding(&self.encoding),
-                            false,
-                            cache,
-                            cache_key,
-                            &self.encoding.inner,
-                        )
-                        .await
-                } else {
-                    tracing::debug!("hit (not modified)");
+                // Note that we never cache partial responses, so a `Range` is always ignored
+                let mut response = match evaluate_preconditions(
+                    request.method(),
+                    request.headers(),
+                    Some(cached_response.headers()),
+                ) {
+                    Precondition::Proceed | Precondition::ProceedWithoutRange => {
+                        tracing::debug!("hit");
 
-                    not_modified_transcoding_response()
-                }
+                        let mut encoding = request
+                            .select_encoding_for(cached_response.headers().content_type().as_ref(), &self.encoding);
+
+                        if encoding != Encoding::Identity
+                            && let Some(dictionary_encoding) = request.dictionary_encoding(&self.encoding)
+                        {
+                            encoding = dictionary_encoding;
+                        }
+
+                        let response = cached_response
+                            .to_transcoding_response(&encoding, false, cache, cache_key, &self.encoding.inner)
+                            .await;
+
+                        if is_head { head_transcoding_response(response) } else { response }
+                    }
+
+                    Precondition::NotModified => {
+                        tracing::debug!("hit (not modified)");
+
+                        let mut response = not_modified_transcoding_response();
+                        response.headers_mut().extend(not_modified_headers(cached_response.headers()));
+                        response
+                    }
+
+                    Precondition::Failed => {
+                        tracing::debug!("hit (precondition failed)");
+
+                        precondition_failed_transcoding_response()
+                    }
+                };
+
+                status.set_headers(response.headers_mut(), &self.caching);
+                response
             }),
 
+            None if is_head => {
+                // Capture request data before moving the request to the inner service
+                let uri = request.uri().clone();
+                let encoding = request.select_encoding(&self.encoding);
+                let accept_encoding = request.accept_encoding_for_upstream(&self.encoding);
+
+                // We must not store the (empty) body of a HEAD response, otherwise a later GET
+                // could be served from it
+                tracing::debug!("skip (HEAD miss)");
+
+                self.inner_service.call(request).await.map(|upstream_response| {
+                    let content_length = upstream_response.headers().content_length();
+                    let mut response = head_transcoding_response(self.uncached_response(
+                        upstream_response,
+                        &uri,
+                        encoding,
+                        accept_encoding.as_ref(),
+                        content_length,
+                    ));
+                    CacheStatus::forward(forward, false).set_headers(response.headers_mut(), &self.caching);
+                    response
+                })
+            }
+
             None => {
                 // Capture request data before moving the request to the inner service
                 let uri = request.uri().clone();
                 let encoding = request.select_encoding(&self.encoding);
+                let accept_encoding = request.accept_encoding_for_upstream(&self.encoding);
+                let dictionary_encoding = request.dictionary_encoding(&self.encoding);
 
                 let upstream_response = self.inner_service.call(request).await?;
 
+                // Must be checked before we start reading the body
+                let streaming = if upstream_response.is_upgrade() {
+                    tracing::debug!("skip (upgrade)");
+                    true
+                } else if upstream_response.is_streaming(&self.caching) {
+                    tracing::debug!("skip (streaming)");
+                    true
+                } else {
+                    false
+                };
+
+                if streaming {
+                    let content_length = upstream_response.headers().content_length();
+                    let mut response = self.uncached_response(
+                        upstream_response,
+                        &uri,
+                        encoding,
+                        accept_encoding.as_ref(),
+                        content_length,
+                    );
+                    CacheStatus::forward(forward, false).set_headers(response.headers_mut(), &self.caching);
+                    return Ok(response);
+                }
+
                 Ok({
-                    let (skip_caching, content_length) = upstream_response.should_skip_cache(&uri, &self.caching);
-                    let (encoding, skip_encoding) =
-                        upstream_response.validate_encoding(&uri, encoding.clone(), content_length, &self.encoding);
+                    let (mut skip_caching, content_length) = upstream_response.should_skip_cache(&uri, &self.caching);
+
+                    // Admission is checked last, because it records the request
+                    if !skip_caching
+                        && let Some(admission) = &self.caching.admission
+                        && !admission.admit(&cache_key)
+                    {
+                        tracing::debug!("skip (not admitted)");
+                        skip_caching = true;
+                    }
+
+                    let (encoding, skip_encoding) = upstream_response.validate_encoding(
+                        &uri,
+                        encoding.clone(),
+                        accept_encoding.as_ref(),
+                        content_length,
+                        &self.encoding,
+                    );
 
-                    if skip_caching {
-                        upstream_response.with_transcoding_body(&encoding, self.encoding.inner.encodable_by_default)
+                    // Encodings with a dictionary are only supported for cached responses
+                    let cached_encoding = match dictionary_encoding {
+                        Some(dictionary_encoding) if encoding != Encoding::Identity => dictionary_encoding,
+                        _ => encoding,
+                    };
+
+                    let (mut response, stored) = if skip_caching {
+                        (
+                            upstream_response
+                                .with_transcoding_body(&encoding, self.encoding.inner.encodable_by_default),
+                            false,
+                        )
                     } else {
                         tracing::debug!("miss");
 
@@ -125,7 +403,7 @@ where
                             &uri,
                             upstream_response,
                             content_length,
-                            encoding.clone(),
+                            cached_encoding,
                             skip_encoding,
                             &self.caching.inner,
                             &self.encoding.inner,
@@ -133,29 +411,47 @@ where
                         .await
                         {
                             Ok(cached_response) => {
-                                tracing::debug!("store ({})", encoding);
-                                Arc::new(cached_response)
-                                    .to_transcoding_response(&encoding, true, cache, cache_key, &self.encoding.inner)
-                                    .await
+                                tracing::debug!("store ({})", cached_encoding);
+                                (
+                                    Arc::new(cached_response)
+                                        .to_transcoding_response(
+                                            &cached_encoding,
+                                            true,
+                                            cache,
+                                            cache_key,
+                                            &self.encoding.inner,
+                                        )
+                                        .await,
+                                    true,
+                                )
                             }
 
                             Err(error) => match error.pieces {
                                 Some(pieces) => {
                                     tracing::debug!("skip ({})", error.error);
-                                    pieces.response.with_transcoding_body_with_first_bytes(
-                                        Some(pieces.first_bytes),
-                                        &encoding,
-                                        self.encoding.inner.encodable_by_default,
+                                    (
+                                        pieces
+                                            .response
+                                            .with_transcoding_body_with_first_bytes(
+                                                Some(pieces.first_bytes),
+                                                &encoding,
+                                                self.encoding.inner.encodable_by_default,
+                                            )
+                                            .map(|body| body.with_trailers(pieces.trailers)),
+                                        false,
                                     )
                                 }
 
                                 None => {
                                     tracing::error!("could not create cache entry: {} {}", cache_key, error);
-                                    error_transcoding_response()
+                                    return Ok(error_transcoding_response());
                                 }
                             },
                         }
-                    }
+                    };
+
+                    CacheStatus::forward(forward, stored).set_headers(response.headers_mut(), &self.caching);
+                    response
                 })
             }
         }
@@ -184,7 +480,8 @@ where
     InnerServiceT:
         'static + Service<Request<RequestBodyT>, Response = Response<ResponseBodyT>, Error = ErrorT> + Clone + Send,
     InnerServiceT::Future: Send,
-    RequestBodyT: 'static + Send,
+    RequestBodyT: 'static + Body + From<Bytes> + Send + Unpin,
+    RequestBodyT::Error: Into<CapturedError>,
     ResponseBodyT: 'static + Body + From<Bytes> + Send + Unpin,
     ResponseBodyT::Data: From<Bytes> + Send,
     ResponseBodyT::Error: Into<CapturedError>,
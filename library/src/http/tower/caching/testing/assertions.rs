@@ -0,0 +1,52 @@
+use super::super::super::super::{super::transcoding::*, cache::middleware::*, headers::*};
+
+use http::{header::*, *};
+
+/// Assert that the response was served from the cache.
+///
+/// Relies on the `X-Cache` header, so the layer must be configured with
+/// [x_cache](super::super::CachingLayer::x_cache).
+#[track_caller]
+pub fn assert_hit<BodyT>(response: &Response<BodyT>) {
+    assert_x_cache(response, HIT_HEADER_VALUE);
+}
+
+/// Assert that the response was *not* served from the cache.
+///
+/// Relies on the `X-Cache` header, so the layer must be configured with
+/// [x_cache](super::super::CachingLayer::x_cache).
+#[track_caller]
+pub fn assert_miss<BodyT>(response: &Response<BodyT>) {
+    assert_x_cache(response, MISS_HEADER_VALUE);
+}
+
+/// Assert the response's `Content-Encoding`.
+///
+/// [Identity](Encoding::Identity) means there should be no `Content-Encoding` header.
+#[track_caller]
+pub fn assert_content_encoding<BodyT>(response: &Response<BodyT>, encoding: Encoding) {
+    let content_encoding: Encoding = response.headers().content_encoding().into();
+    assert_eq!(content_encoding, encoding, "{}", CONTENT_ENCODING);
+}
+
+/// Assert that the response is 304 (Not Modified).
+#[track_caller]
+pub fn assert_not_modified<BodyT>(response: &Response<BodyT>) {
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED, "status");
+}
+
+/// Assert that none of our custom `XX-*` headers made it to the response.
+#[track_caller]
+pub fn assert_no_custom_headers<BodyT>(response: &Response<BodyT>) {
+    for name in response.headers().keys() {
+        assert!(!name.as_str().starts_with("xx-"), "custom header not removed: {}", name);
+    }
+}
+
+#[track_caller]
+fn assert_x_cache<BodyT>(response: &Response<BodyT>, expected: HeaderValue) {
+    match response.headers().get(X_CACHE) {
+        Some(value) => assert_eq!(value, expected, "{}", X_CACHE),
+        None => panic!("no {} header (is the layer configured with x_cache?)", X_CACHE),
+    }
+}
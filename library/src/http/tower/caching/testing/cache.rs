@@ -0,0 +1,170 @@
+use super::super::super::super::{
+    super::{std::collections::*, transcoding::*},
+    cache::*,
+};
+
+use std::sync::*;
+
+//
+// RecordingCache
+//
+
+/// In-memory [Cache] that records all operations.
+///
+/// Durations are ignored, so entries will remain until invalidated.
+#[derive(Clone)]
+pub struct RecordingCache<CacheKeyT = CommonCacheKey> {
+    entries: Arc<FastConcurrentHashMap<CacheKeyT, CachedResponseRef>>,
+    operations: Arc<Mutex<Vec<CacheOperation<CacheKeyT>>>>,
+}
+
+impl<CacheKeyT> RecordingCache<CacheKeyT>
+where
+    CacheKeyT: CacheKey,
+{
+    /// Constructor.
+    pub fn new() -> Self {
+        Self { entries: Default::default(), operations: Default::default() }
+    }
+
+    /// Recorded operations, in order.
+    pub fn operations(&self) -> Vec<CacheOperation<CacheKeyT>> {
+        self.operations.lock().expect("lock").clone()
+    }
+
+    /// Clear the recorded operations.
+    ///
+    /// Entries are kept.
+    pub fn clear_operations(&self) {
+        self.operations.lock().expect("lock").clear();
+    }
+
+    /// Number of recorded gets that returned an entry.
+    pub fn hits(&self) -> usize {
+        self.count(|operation| matches!(operation, CacheOperation::Get(_key, true)))
+    }
+
+    /// Number of recorded gets that did not return an entry.
+    pub fn misses(&self) -> usize {
+        self.count(|operation| matches!(operation, CacheOperation::Get(_key, false)))
+    }
+
+    /// Number of recorded puts.
+    pub fn puts(&self) -> usize {
+        self.count(|operation| matches!(operation, CacheOperation::Put(_key)))
+    }
+
+    /// Number of recorded invalidations (including [CacheOperation::InvalidateAll]).
+    pub fn invalidations(&self) -> usize {
+        self.count(|operation| matches!(operation, CacheOperation::Invalidate(_) | CacheOperation::InvalidateAll))
+    }
+
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get an entry *without* recording the operation.
+    pub fn peek(&self, key: &CacheKeyT) -> Option<CachedResponseRef> {
+        self.entries.pin().get(key).cloned()
+    }
+
+    /// Assert that an entry is stored with exactly these encodings.
+    #[track_caller]
+    pub fn assert_stored_encodings(&self, key: &CacheKeyT, encodings: &[Encoding]) {
+        let cached_response = self.peek(key).unwrap_or_else(|| panic!("not stored: {}", key));
+
+        let mut stored: Vec<_> = cached_response.body.representations.keys().cloned().collect();
+        stored.sort_by_key(|encoding| encoding.to_string());
+        let mut expected = encodings.to_vec();
+        expected.sort_by_key(|encoding| encoding.to_string());
+
+        assert_eq!(stored, expected, "stored encodings for {}", key);
+    }
+
+    /// Assert that an entry is not stored.
+    #[track_caller]
+    pub fn assert_not_stored(&self, key: &CacheKeyT) {
+        assert!(self.peek(key).is_none(), "stored: {}", key);
+    }
+
+    fn count<FilterT>(&self, filter: FilterT) -> usize
+    where
+        FilterT: Fn(&CacheOperation<CacheKeyT>) -> bool,
+    {
+        self.operations.lock().expect("lock").iter().filter(|operation| filter(operation)).count()
+    }
+
+    fn record(&self, operation: CacheOperation<CacheKeyT>) {
+        self.operations.lock().expect("lock").push(operation);
+    }
+}
+
+impl<CacheKeyT> Default for RecordingCache<CacheKeyT>
+where
+    CacheKeyT: CacheKey,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<CacheKeyT> Cache<CacheKeyT> for RecordingCache<CacheKeyT>
+where
+    CacheKeyT: CacheKey,
+{
+    async fn get(&self, key: &CacheKeyT) -> Option<CachedResponseRef> {
+        let cached_response = self.peek(key);
+        self.record(CacheOperation::Get(key.clone(), cached_response.is_some()));
+        cached_response
+    }
+
+    async fn put(&self, key: CacheKeyT, cached_response: CachedResponseRef) {
+        self.record(CacheOperation::Put(key.clone()));
+        self.entries.pin().insert(key, cached_response);
+    }
+
+    async fn invalidate(&self, key: &CacheKeyT) {
+        self.record(CacheOperation::Invalidate(key.clone()));
+        self.entries.pin().remove(key);
+    }
+
+    async fn invalidate_all(&self) {
+        self.record(CacheOperation::InvalidateAll);
+        self.entries.pin().clear();
+    }
+}
+
+impl<CacheKeyT> EnumerableCache<CacheKeyT> for RecordingCache<CacheKeyT>
+where
+    CacheKeyT: CacheKey,
+{
+    async fn entries(&self) -> Vec<(CacheKeyT, CachedResponseRef)> {
+        self.entries.pin().iter().map(|(key, cached_response)| (key.clone(), cached_response.clone())).collect()
+    }
+}
+
+//
+// CacheOperation
+//
+
+/// Operation recorded by [RecordingCache].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CacheOperation<CacheKeyT = CommonCacheKey> {
+    /// Get. The bool is whether an entry was returned.
+    Get(CacheKeyT, bool),
+
+    /// Put.
+    Put(CacheKeyT),
+
+    /// Invalidate.
+    Invalidate(CacheKeyT),
+
+    /// Invalidate all.
+    InvalidateAll,
+}
@@ -0,0 +1,6 @@
+mod assertions;
+mod cache;
+mod upstream;
+
+#[allow(unused_imports)]
+pub use {assertions::*, cache::*, upstream::*};
@@ -0,0 +1,190 @@
+use super::super::super::super::super::std::{collections::*, immutable::*};
+
+use {
+    http::{request::*, response::*, *},
+    http_body::*,
+    std::{collections::*, convert::*, future, pin::*, result::Result, sync::*, task::*},
+    tower::*,
+};
+
+//
+// FakeUpstream
+//
+
+/// Scriptable fake upstream [Service].
+///
+/// Responses are scripted per path. If more than one response is scripted for a path then they
+/// will be returned in order, with the last one repeating. Unscripted paths return 404 (Not
+/// Found).
+///
+/// All requests are recorded (without their bodies).
+#[derive(Clone, Debug, Default)]
+pub struct FakeUpstream {
+    responses: Arc<Mutex<FastHashMap<String, VecDeque<FakeResponse>>>>,
+    requests: Arc<Mutex<Vec<Request<()>>>>,
+}
+
+impl FakeUpstream {
+    /// Script a response for a path.
+    ///
+    /// Can be called more than once for the same path in order to script a sequence.
+    pub fn with_response(self, path: &str, response: FakeResponse) -> Self {
+        self.responses.lock().expect("lock").entry(path.into()).or_default().push_back(response);
+        self
+    }
+
+    /// Recorded requests, in order.
+    pub fn requests(&self) -> Vec<Request<()>> {
+        self.requests
+            .lock()
+            .expect("lock")
+            .iter()
+            .map(|request| {
+                let mut clone = Request::new(());
+                *clone.method_mut() = request.method().clone();
+                *clone.uri_mut() = request.uri().clone();
+                *clone.version_mut() = request.version();
+                *clone.headers_mut() = request.headers().clone();
+                clone
+            })
+            .collect()
+    }
+
+    /// Number of recorded requests.
+    pub fn calls(&self) -> usize {
+        self.requests.lock().expect("lock").len()
+    }
+
+    /// Number of recorded requests for a path.
+    pub fn calls_for(&self, path: &str) -> usize {
+        self.requests.lock().expect("lock").iter().filter(|request| request.uri().path() == path).count()
+    }
+
+    /// Clear the recorded requests.
+    pub fn clear_requests(&self) {
+        self.requests.lock().expect("lock").clear();
+    }
+
+    fn next_response(&self, path: &str) -> Option<FakeResponse> {
+        let mut responses = self.responses.lock().expect("lock");
+        let responses = responses.get_mut(path)?;
+        if responses.len() > 1 { responses.pop_front() } else { responses.front().cloned() }
+    }
+}
+
+impl<RequestBodyT> Service<Request<RequestBodyT>> for FakeUpstream {
+    type Response = Response<FakeBody>;
+    type Error = Infallible;
+    type Future = future::Ready<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _context: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<RequestBodyT>) -> Self::Future {
+        let (parts, _body) = request.into_parts();
+        let response = self.next_response(parts.uri.path());
+        self.requests.lock().expect("lock").push(Request::from_parts(parts, ()));
+
+        future::ready(Ok(match response {
+            Some(response) => response.into(),
+            None => FakeResponse::default().with_status(StatusCode::NOT_FOUND).into(),
+        }))
+    }
+}
+
+//
+// FakeResponse
+//
+
+/// Scripted response for [FakeUpstream].
+#[derive(Clone, Debug, Default)]
+pub struct FakeResponse {
+    /// Status.
+    pub status: StatusCode,
+
+    /// Headers.
+    pub headers: HeaderMap,
+
+    /// Body.
+    pub body: Bytes,
+}
+
+impl FakeResponse {
+    /// Constructor.
+    ///
+    /// Status will be 200 (OK).
+    pub fn new<BodyT>(body: BodyT) -> Self
+    where
+        BodyT: Into<Bytes>,
+    {
+        Self { body: body.into(), ..Default::default() }
+    }
+
+    /// With status.
+    pub fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// With header.
+    ///
+    /// Panics if the value is invalid.
+    pub fn with_header(mut self, name: HeaderName, value: &str) -> Self {
+        self.headers.append(name, HeaderValue::from_str(value).expect("valid header value"));
+        self
+    }
+
+    /// With `Content-Length` header.
+    pub fn with_content_length(self) -> Self {
+        let length = self.body.len().to_string();
+        self.with_header(header::CONTENT_LENGTH, &length)
+    }
+}
+
+impl From<FakeResponse> for Response<FakeBody> {
+    fn from(fake_response: FakeResponse) -> Self {
+        let mut response = Response::new(fake_response.body.into());
+        *response.status_mut() = fake_response.status;
+        *response.headers_mut() = fake_response.headers;
+        response
+    }
+}
+
+//
+// FakeBody
+//
+
+/// [Body] for [FakeUpstream].
+///
+/// The bytes are sent as a single frame.
+#[derive(Clone, Debug, Default)]
+pub struct FakeBody {
+    bytes: Option<Bytes>,
+}
+
+impl Body for FakeBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        _context: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        Poll::Ready(self.bytes.take().filter(|bytes| !bytes.is_empty()).map(|bytes| Ok(Frame::data(bytes))))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.bytes.as_ref().is_none_or(|bytes| bytes.is_empty())
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::with_exact(self.bytes.as_ref().map(|bytes| bytes.len()).unwrap_or_default() as u64)
+    }
+}
+
+impl From<Bytes> for FakeBody {
+    fn from(bytes: Bytes) -> Self {
+        Self { bytes: Some(bytes) }
+    }
+}
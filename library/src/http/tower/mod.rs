@@ -1,2 +1,8 @@
+/// Authentication layer.
+pub mod authentication;
+
 /// HTTP response caching layer with integrated encoding.
 pub mod caching;
+
+/// Effective request origin layer.
+pub mod origin;
@@ -0,0 +1,43 @@
+use super::{super::super::origin::*, service::*};
+
+use tower::*;
+
+//
+// EffectiveOriginLayer
+//
+
+/// Layer that resolves the [EffectiveOrigin] of requests and stores it as a request extension.
+///
+/// The peer address is taken from the [SocketAddr](std::net::SocketAddr) request extension or,
+/// for axum, from its `ConnectInfo<SocketAddr>` request extension (see
+/// [into_make_service_with_connect_info](::axum::Router::into_make_service_with_connect_info)).
+/// Without a peer address we cannot trust any proxy.
+///
+/// The extension is consumed by the [CachingLayer](super::super::caching::CachingLayer) (for the
+/// cache key) and the [HostRouter](crate::http::axum::HostRouter), so this layer should be
+/// applied before them. It is also logged.
+#[derive(Clone, Debug, Default)]
+pub struct EffectiveOriginLayer {
+    trusted_proxies: TrustedProxies,
+}
+
+impl EffectiveOriginLayer {
+    /// Trusted proxies.
+    ///
+    /// The `Forwarded` and `X-Forwarded-*` request headers will only be used if they were set by
+    /// these proxies.
+    ///
+    /// The default trusts nothing.
+    pub fn trusted_proxies(mut self, trusted_proxies: TrustedProxies) -> Self {
+        self.trusted_proxies = trusted_proxies;
+        self
+    }
+}
+
+impl<InnerServiceT> Layer<InnerServiceT> for EffectiveOriginLayer {
+    type Service = EffectiveOriginService<InnerServiceT>;
+
+    fn layer(&self, inner_service: InnerServiceT) -> Self::Service {
+        EffectiveOriginService::new(inner_service, self.trusted_proxies.clone())
+    }
+}
@@ -0,0 +1,5 @@
+mod layer;
+mod service;
+
+#[allow(unused_imports)]
+pub use {layer::*, service::*};
@@ -0,0 +1,59 @@
+use super::super::super::origin::*;
+
+use {
+    http::request::*,
+    std::{net::*, task::*},
+    tower::*,
+};
+
+//
+// EffectiveOriginService
+//
+
+/// Service that resolves the [EffectiveOrigin] of requests.
+///
+/// See [EffectiveOriginLayer](super::layer::EffectiveOriginLayer).
+#[derive(Clone, Debug)]
+pub struct EffectiveOriginService<InnerServiceT> {
+    inner_service: InnerServiceT,
+    trusted_proxies: TrustedProxies,
+}
+
+impl<InnerServiceT> EffectiveOriginService<InnerServiceT> {
+    /// Constructor.
+    pub fn new(inner_service: InnerServiceT, trusted_proxies: TrustedProxies) -> Self {
+        Self { inner_service, trusted_proxies }
+    }
+}
+
+impl<InnerServiceT, RequestBodyT> Service<Request<RequestBodyT>> for EffectiveOriginService<InnerServiceT>
+where
+    InnerServiceT: Service<Request<RequestBodyT>>,
+{
+    type Response = InnerServiceT::Response;
+    type Error = InnerServiceT::Error;
+    type Future = InnerServiceT::Future;
+
+    fn poll_ready(&mut self, context: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner_service.poll_ready(context)
+    }
+
+    fn call(&mut self, mut request: Request<RequestBodyT>) -> Self::Future {
+        let origin =
+            EffectiveOrigin::resolve(peer_address(&request), request.uri(), request.headers(), &self.trusted_proxies);
+
+        tracing::debug!("effective origin: {}", origin);
+        request.extensions_mut().insert(origin);
+
+        self.inner_service.call(request)
+    }
+}
+
+fn peer_address<RequestBodyT>(request: &Request<RequestBodyT>) -> Option<IpAddr> {
+    #[cfg(feature = "axum")]
+    if let Some(connect_info) = request.extensions().get::<::axum::extract::ConnectInfo<SocketAddr>>() {
+        return Some(connect_info.0.ip());
+    }
+
+    request.extensions().get::<SocketAddr>().map(|address| address.ip())
+}
@@ -10,7 +10,7 @@ use {
     http::*,
     http_body::*,
     pin_project::*,
-    std::{collections::*, io, pin::*, result::Result, task::*},
+    std::{collections::*, io, mem::*, pin::*, result::Result, task::*},
     tokio_util::io::*,
 };
 
@@ -25,6 +25,9 @@ const BUFFER_INITIAL_CAPACITY: usize = 8 * 1_024; // 8 KiB
 /// Note that the resulting number (and of course sizes) of the data frames will not necessarily
 /// match those of the wrapped body.
 ///
+/// Trailers are emitted after the data frames: first those set via
+/// [with_trailers](Self::with_trailers) and then those of the wrapped body.
+///
 /// Relies on [TranscodingReader].
 #[pin_project]
 pub struct TranscodingBody<InnerBodyT>
@@ -35,6 +38,7 @@ where
     #[pin]
     reader: TranscodingReader<BodyReader<InnerBodyT>>,
     buffer: BytesMut,
+    first_trailers: Vec<HeaderMap>,
     trailers: Option<VecDeque<HeaderMap>>,
 }
 
@@ -45,7 +49,16 @@ where
 {
     /// Constructor.
     pub fn new(reader: TranscodingReader<BodyReader<InnerBodyT>>) -> Self {
-        Self { reader, buffer: BytesMut::with_capacity(0), trailers: None }
+        Self { reader, buffer: BytesMut::with_capacity(0), first_trailers: Default::default(), trailers: None }
+    }
+
+    /// With trailers to emit before those of the wrapped body.
+    ///
+    /// Useful for replaying stored trailers or trailers that have already been read from the
+    /// wrapped body.
+    pub fn with_trailers(mut self, trailers: Vec<HeaderMap>) -> Self {
+        self.first_trailers = trailers;
+        self
     }
 
     fn validate_buffer_capacity(&mut self) {
@@ -103,10 +116,9 @@ where
 
                 // Make sure we have the trailers
                 if self.trailers.is_none() {
-                    let trailers = &self.reader.inner().trailers;
-                    if !trailers.is_empty() {
-                        self.trailers = Some(trailers.clone().into());
-                    }
+                    let mut trailers: VecDeque<_> = take(&mut self.first_trailers).into();
+                    trailers.extend(self.reader.inner().trailers.iter().cloned());
+                    self.trailers = Some(trailers);
                 }
 
                 // Return the next trailer frame
@@ -145,6 +157,23 @@ where
     /// Into encoding [TranscodingBody].
     fn into_encoding_with_first_bytes(self, first_bytes: Option<Bytes>, encoding: &Encoding) -> TranscodingBody<BodyT>;
 
+    /// Into encoding [TranscodingBody] that flushes after every data frame of the wrapped body.
+    ///
+    /// For streaming bodies, e.g. server-sent events. Note that frequent flushing makes for worse
+    /// compression.
+    fn into_streaming_encoding(self, encoding: &Encoding) -> TranscodingBody<BodyT> {
+        self.into_streaming_encoding_with_first_bytes(None, encoding)
+    }
+
+    /// Into encoding [TranscodingBody] that flushes after every data frame of the wrapped body.
+    ///
+    /// See [into_streaming_encoding](Self::into_streaming_encoding).
+    fn into_streaming_encoding_with_first_bytes(
+        self,
+        first_bytes: Option<Bytes>,
+        encoding: &Encoding,
+    ) -> TranscodingBody<BodyT>;
+
     /// Into decoding [TranscodingBody].
     fn into_decoding(self, encoding: &Encoding) -> TranscodingBody<BodyT> {
         self.into_decoding_with_first_bytes(None, encoding)
@@ -169,6 +198,18 @@ where
         )
     }
 
+    fn into_streaming_encoding_with_first_bytes(
+        self,
+        first_bytes: Option<Bytes>,
+        encoding: &Encoding,
+    ) -> TranscodingBody<BodyT> {
+        TranscodingBody::new(
+            self.into_reader_with_first_bytes(first_bytes)
+                .with_yield_per_frame()
+                .into_encoding_reader(encoding, Level::Fastest),
+        )
+    }
+
     fn into_decoding_with_first_bytes(self, first_bytes: Option<Bytes>, encoding: &Encoding) -> TranscodingBody<BodyT> {
         TranscodingBody::new(self.into_reader_with_first_bytes(first_bytes).into_decoding_reader(encoding))
     }
@@ -52,6 +52,27 @@ where
         encoding: &Encoding,
         encodable_by_default: bool,
     ) -> Response<TranscodingBody<BodyT>>;
+
+    /// Into a [Response] with an encoding [TranscodingBody] that flushes after every data frame.
+    ///
+    /// For streaming responses. See [IntoTranscodingBody::into_streaming_encoding].
+    fn with_streaming_transcoding_body(
+        self,
+        encoding: &Encoding,
+        encodable_by_default: bool,
+    ) -> Response<TranscodingBody<BodyT>> {
+        self.with_streaming_transcoding_body_with_first_bytes(None, encoding, encodable_by_default)
+    }
+
+    /// Into a [Response] with an encoding [TranscodingBody] that flushes after every data frame.
+    ///
+    /// For streaming responses. See [IntoTranscodingBody::into_streaming_encoding].
+    fn with_streaming_transcoding_body_with_first_bytes(
+        self,
+        first_bytes: Option<Bytes>,
+        encoding: &Encoding,
+        encodable_by_default: bool,
+    ) -> Response<TranscodingBody<BodyT>>;
 }
 
 impl<BodyT> IntoTranscodingResponse<BodyT> for Response<BodyT>
@@ -66,6 +87,7 @@ where
         let (mut parts, body) = self.into_parts();
         parts.headers.remove(XX_ENCODE);
         parts.headers.remove(XX_CACHE);
+        parts.headers.remove(XX_CACHE_TAGS);
         Response::from_parts(parts, body.into_transcoding_passthrough_with_first_bytes(first_bytes))
     }
 
@@ -75,55 +97,90 @@ where
         encoding: &Encoding,
         encodable_by_default: bool,
     ) -> Response<TranscodingBody<BodyT>> {
-        if *encoding == Encoding::Identity {
-            return self.with_transcoding_body_passthrough_with_first_bytes(first_bytes);
-        }
+        with_transcoding_body(self, first_bytes, encoding, encodable_by_default, false)
+    }
 
-        let (mut parts, body) = self.into_parts();
+    fn with_streaming_transcoding_body_with_first_bytes(
+        self,
+        first_bytes: Option<Bytes>,
+        encoding: &Encoding,
+        encodable_by_default: bool,
+    ) -> Response<TranscodingBody<BodyT>> {
+        with_transcoding_body(self, first_bytes, encoding, encodable_by_default, true)
+    }
+}
 
-        let encode = parts.headers.xx_encode(encodable_by_default);
-        parts.headers.remove(XX_CACHE);
-        parts.headers.remove(XX_ENCODE);
+fn with_transcoding_body<BodyT>(
+    response: Response<BodyT>,
+    first_bytes: Option<Bytes>,
+    encoding: &Encoding,
+    encodable_by_default: bool,
+    streaming: bool,
+) -> Response<TranscodingBody<BodyT>>
+where
+    BodyT: Body,
+    BodyT::Error: Into<CapturedError>,
+{
+    if *encoding == Encoding::Identity {
+        return response.with_transcoding_body_passthrough_with_first_bytes(first_bytes);
+    }
+
+    if encoding.dictionary().is_some() {
+        tracing::debug!("not encoding to {} (unsupported for streaming)", encoding);
+        return response.with_transcoding_body_passthrough_with_first_bytes(first_bytes);
+    }
+
+    let (mut parts, body) = response.into_parts();
 
-        if !encode {
-            tracing::debug!("not encoding to {} ({}=false)", encoding, XX_ENCODE);
-            return Response::from_parts(parts, body.into_transcoding_passthrough_with_first_bytes(first_bytes));
-        }
+    let encode = parts.headers.xx_encode(encodable_by_default);
+    parts.headers.remove(XX_CACHE);
+    parts.headers.remove(XX_ENCODE);
+    parts.headers.remove(XX_CACHE_TAGS);
+
+    if !encode {
+        tracing::debug!("not encoding to {} ({}=false)", encoding, XX_ENCODE);
+        return Response::from_parts(parts, body.into_transcoding_passthrough_with_first_bytes(first_bytes));
+    }
 
-        let current_encoding = parts.headers.content_encoding().into();
+    let current_encoding = parts.headers.content_encoding().into();
 
-        if *encoding == current_encoding {
-            tracing::debug!("already encoded as {}", encoding);
-            return Response::from_parts(parts, body.into_transcoding_passthrough_with_first_bytes(first_bytes));
-        }
+    if *encoding == current_encoding {
+        tracing::debug!("already encoded as {}", encoding);
+        return Response::from_parts(parts, body.into_transcoding_passthrough_with_first_bytes(first_bytes));
+    }
 
-        if current_encoding != Encoding::Identity {
-            tracing::debug!("not reencoding from {} to {})", current_encoding, encoding);
-            return Response::from_parts(parts, body.into_transcoding_passthrough_with_first_bytes(first_bytes));
+    if current_encoding != Encoding::Identity {
+        tracing::debug!("not reencoding from {} to {})", current_encoding, encoding);
+        return Response::from_parts(parts, body.into_transcoding_passthrough_with_first_bytes(first_bytes));
+
+        // We intentionally don't reencode because it would be computationally wasteful!
+        // Also, it would be hard to program this in our current generics-based design.
+        // The wrapping would have to look something like this:
+        //
+        //   BodyReader ->
+        //     decoding TranscodingReader ->
+        //       encoding TranscodingReader
+        //
+        // Also note that we are *not* checking that the client can accept current_encoding.
+        // We just have to trust that the body was generated with respect to the request's
+        // `Accept-Encoding`.
+    }
 
-            // We intentionally don't reencode because it would be computationally wasteful!
-            // Also, it would be hard to program this in our current generics-based design.
-            // The wrapping would have to look something like this:
-            //
-            //   BodyReader ->
-            //     decoding TranscodingReader ->
-            //       encoding TranscodingReader
-            //
-            // Also note that we are *not* checking that the client can accept current_encoding.
-            // We just have to trust that the body was generated with respect to the request's
-            // `Accept-Encoding`.
-        }
+    parts.headers.set_into_header_value(CONTENT_ENCODING, encoding.clone());
 
-        parts.headers.set_into_header_value(CONTENT_ENCODING, encoding.clone());
+    // We don't know what the final content length will be
+    parts.headers.remove(CONTENT_LENGTH);
 
-        // We don't know what the final content length will be
-        parts.headers.remove(CONTENT_LENGTH);
+    // We don't know what the final digest will be
+    parts.headers.remove(CONTENT_DIGEST);
 
-        // We don't know what the final digest will be
-        parts.headers.remove(CONTENT_DIGEST);
+    let body = if streaming {
+        body.into_streaming_encoding_with_first_bytes(first_bytes, encoding)
+    } else {
+        body.into_encoding_with_first_bytes(first_bytes, encoding)
+    };
 
-        Response::from_parts(parts, body.into_encoding_with_first_bytes(first_bytes, encoding))
-    }
+    Response::from_parts(parts, body)
 }
 
 /// [Response] with an empty [TranscodingBody] and [StatusCode::INTERNAL_SERVER_ERROR].
@@ -147,3 +204,38 @@ where
     *response.status_mut() = StatusCode::NOT_MODIFIED;
     response
 }
+
+/// [Response] with an empty [TranscodingBody] and [StatusCode::PRECONDITION_FAILED].
+pub fn precondition_failed_transcoding_response<BodyT>() -> Response<TranscodingBody<BodyT>>
+where
+    BodyT: Body + From<Bytes>,
+    BodyT::Error: Into<CapturedError>,
+{
+    let mut response = Response::new(Bytes::default().into()).with_transcoding_body_passthrough_with_first_bytes(None);
+    *response.status_mut() = StatusCode::PRECONDITION_FAILED;
+    response
+}
+
+/// Replaces the [Response] body with an empty [TranscodingBody] while keeping the headers.
+///
+/// Intended for `HEAD` responses, which must have the same headers as the `GET` response
+/// (including `Content-Length`) but no body.
+pub fn head_transcoding_response<BodyT>(response: Response<TranscodingBody<BodyT>>) -> Response<TranscodingBody<BodyT>>
+where
+    BodyT: Body + From<Bytes>,
+    BodyT::Error: Into<CapturedError>,
+{
+    let (parts, _body) = response.into_parts();
+    Response::from_parts(parts, Bytes::default().into())
+}
+
+/// [Response] with an empty [TranscodingBody] and [StatusCode::NOT_ACCEPTABLE].
+pub fn not_acceptable_transcoding_response<BodyT>() -> Response<TranscodingBody<BodyT>>
+where
+    BodyT: Body + From<Bytes>,
+    BodyT::Error: Into<CapturedError>,
+{
+    let mut response = Response::new(Bytes::default().into()).with_transcoding_body_passthrough_with_first_bytes(None);
+    *response.status_mut() = StatusCode::NOT_ACCEPTABLE;
+    response
+}
@@ -1,13 +1,26 @@
-use super::{super::std::immutable::*, encoding::*, transcode::*};
+use super::{super::std::immutable::*, dictionary::*, encoding::*, transcode::*};
 
 use {
-    async_compression::tokio::{bufread, write},
-    std::io,
-    tokio::io::*,
+    async_compression::{
+        Level,
+        tokio::{bufread, write},
+    },
+    std::io::{self, Read as _},
+    tokio::{io::*, task::*},
 };
 
+// Window for Brotli with a dictionary (16 MiB), the maximum that clients are expected to support
+const DICTIONARY_BROTLI_WINDOW: i32 = 24;
+
+// Level for the encoders with a dictionary
+const DICTIONARY_LEVEL: Level = Level::Default;
+
 impl Transcode for Bytes {
-    async fn encode(&self, encoding: &Encoding) -> io::Result<Self> {
+    async fn encode_with_dictionaries(
+        &self,
+        encoding: &Encoding,
+        dictionaries: Option<&Dictionaries>,
+    ) -> io::Result<Self> {
         match encoding {
             Encoding::Identity => Ok(self.clone()),
 
@@ -38,10 +51,60 @@ impl Transcode for Bytes {
                 encoder.shutdown().await?;
                 Ok(encoder.into_inner().into())
             }
+
+            // The dictionary encoders are synchronous, so we run them on the blocking thread pool
+            Encoding::DictionaryBrotli(hash) => {
+                let dictionary = Dictionaries::get_or_error(dictionaries, hash)?;
+                let bytes = self.clone();
+                let mut buffer = dictionary_header(DICTIONARY_BROTLI_MAGIC, hash);
+
+                spawn_blocking(move || {
+                    let params = brotli::enc::BrotliEncoderParams {
+                        quality: brotli_quality(DICTIONARY_LEVEL),
+                        lgwin: DICTIONARY_BROTLI_WINDOW,
+                        ..Default::default()
+                    };
+
+                    brotli::BrotliCompressCustomIoCustomDict(
+                        &mut brotli::IoReaderWrapper(&mut bytes.as_ref()),
+                        &mut brotli::IoWriterWrapper(&mut buffer),
+                        &mut [0; 4096],
+                        &mut [0; 4096],
+                        &params,
+                        brotli::enc::StandardAlloc::default(),
+                        &mut |_, _, _, _| (),
+                        &dictionary.bytes,
+                        io::Error::from(io::ErrorKind::UnexpectedEof),
+                    )?;
+                    Ok(buffer.into())
+                })
+                .await?
+            }
+
+            Encoding::DictionaryZstandard(hash) => {
+                let dictionary = Dictionaries::get_or_error(dictionaries, hash)?;
+                let bytes = self.clone();
+                let mut buffer = dictionary_header(DICTIONARY_ZSTANDARD_MAGIC, hash);
+
+                spawn_blocking(move || {
+                    let mut encoder = zstd::stream::read::Encoder::with_dictionary(
+                        bytes.as_ref(),
+                        zstd_level(DICTIONARY_LEVEL),
+                        &dictionary.bytes,
+                    )?;
+                    encoder.read_to_end(&mut buffer)?;
+                    Ok(buffer.into())
+                })
+                .await?
+            }
         }
     }
 
-    async fn decode(&self, encoding: &Encoding) -> io::Result<Self> {
+    async fn decode_with_dictionaries(
+        &self,
+        encoding: &Encoding,
+        dictionaries: Option<&Dictionaries>,
+    ) -> io::Result<Self> {
         match encoding {
             Encoding::Identity => Ok(self.clone()),
 
@@ -72,6 +135,73 @@ impl Transcode for Bytes {
                 decoder.read_to_end(&mut buffer).await?;
                 Ok(buffer.into())
             }
+            Encoding::DictionaryBrotli(_) => {
+                let (dictionary, encoded) = read_dictionary_header(self, DICTIONARY_BROTLI_MAGIC, dictionaries)?;
+
+                let mut decoder =
+                    brotli::Decompressor::new_with_custom_dict(encoded, 4096, dictionary.bytes.to_vec().into());
+                let mut buffer = Vec::default();
+                decoder.read_to_end(&mut buffer)?;
+                Ok(buffer.into())
+            }
+
+            Encoding::DictionaryZstandard(_) => {
+                let (dictionary, encoded) = read_dictionary_header(self, DICTIONARY_ZSTANDARD_MAGIC, dictionaries)?;
+
+                let mut decoder = zstd::stream::read::Decoder::with_dictionary(encoded, &dictionary.bytes)?;
+                let mut buffer = Vec::default();
+                decoder.read_to_end(&mut buffer)?;
+                Ok(buffer.into())
+            }
         }
     }
 }
+
+// Brotli's own default (11) is far too slow for encoding on the request path
+fn brotli_quality(level: Level) -> i32 {
+    match level {
+        Level::Fastest => 0,
+        Level::Best => 11,
+        Level::Precise(quality) => quality.clamp(0, 11),
+        _ => 5,
+    }
+}
+
+fn zstd_level(level: Level) -> i32 {
+    let levels = zstd::compression_level_range();
+    match level {
+        Level::Fastest => 1,
+        Level::Best => *levels.end(),
+        Level::Precise(level) => level.clamp(*levels.start(), *levels.end()),
+        _ => zstd::DEFAULT_COMPRESSION_LEVEL,
+    }
+}
+
+// Magic followed by the hash
+fn dictionary_header(magic: &[u8], hash: &DictionaryHash) -> Vec<u8> {
+    let mut header = Vec::with_capacity(magic.len() + hash.0.len());
+    header.extend_from_slice(magic);
+    header.extend_from_slice(&hash.0);
+    header
+}
+
+// Returns the dictionary and the remaining encoded bytes
+fn read_dictionary_header<'own>(
+    bytes: &'own [u8],
+    magic: &[u8],
+    dictionaries: Option<&Dictionaries>,
+) -> io::Result<(Dictionary, &'own [u8])> {
+    let encoded = bytes
+        .strip_prefix(magic)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing dictionary header magic"))?;
+
+    if encoded.len() < 32 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "missing dictionary hash"));
+    }
+
+    let (hash, encoded) = encoded.split_at(32);
+    let hash = DictionaryHash(hash.try_into().expect("32 bytes"));
+    let dictionary = Dictionaries::get_or_error(dictionaries, &hash)?;
+
+    Ok((dictionary, encoded))
+}
@@ -0,0 +1,149 @@
+use super::super::std::immutable::*;
+
+use std::{collections::*, fmt, io, sync::*};
+
+/// Header magic for Brotli with a dictionary (`dcb`).
+pub const DICTIONARY_BROTLI_MAGIC: &[u8] = &[0xff, 0x44, 0x43, 0x42];
+
+/// Header magic for Zstandard with a dictionary (`dcz`).
+///
+/// This is a Zstandard skippable frame.
+pub const DICTIONARY_ZSTANDARD_MAGIC: &[u8] = &[0x5e, 0x2a, 0x4d, 0x18, 0x20, 0x00, 0x00, 0x00];
+
+/// Default capacity for [Dictionaries].
+pub const DEFAULT_DICTIONARIES_CAPACITY: usize = 16;
+
+//
+// DictionaryHash
+//
+
+/// SHA-256 hash of a [Dictionary].
+///
+/// Used to identify dictionaries, e.g. in the `Available-Dictionary` HTTP header.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct DictionaryHash(pub [u8; 32]);
+
+impl DictionaryHash {
+    /// Hash.
+    pub fn of(bytes: &[u8]) -> Self {
+        let digest = ring::digest::digest(&ring::digest::SHA256, bytes);
+        let mut hash = [0; 32];
+        hash.copy_from_slice(digest.as_ref());
+        Self(hash)
+    }
+
+    /// Whether this is the default (all zeros) hash, which we use when the dictionary is unknown.
+    pub fn is_unknown(&self) -> bool {
+        self.0 == [0; 32]
+    }
+}
+
+impl fmt::Display for DictionaryHash {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.0 {
+            write!(formatter, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+//
+// Dictionary
+//
+
+/// Compression dictionary.
+///
+/// Dictionaries are "raw", meaning that any content can be used as a dictionary. Usually it would
+/// be a previous version of the content being compressed.
+#[derive(Clone, Debug)]
+pub struct Dictionary {
+    /// Hash.
+    pub hash: DictionaryHash,
+
+    /// Content.
+    pub bytes: Bytes,
+}
+
+impl Dictionary {
+    /// Constructor.
+    pub fn new(bytes: Bytes) -> Self {
+        Self { hash: DictionaryHash::of(&bytes), bytes }
+    }
+}
+
+//
+// Dictionaries
+//
+
+/// Registry of [Dictionary] by [DictionaryHash].
+///
+/// When we reach capacity the oldest dictionaries are removed.
+///
+/// Cloning is cheap and clones share the same registry.
+#[derive(Clone, Debug)]
+pub struct Dictionaries {
+    capacity: usize,
+    dictionaries: Arc<Mutex<VecDeque<Dictionary>>>,
+}
+
+impl Dictionaries {
+    /// Constructor.
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, dictionaries: Default::default() }
+    }
+
+    /// Register a dictionary.
+    ///
+    /// Returns its hash.
+    pub fn register(&self, dictionary: Dictionary) -> DictionaryHash {
+        let hash = dictionary.hash;
+
+        if let Ok(mut dictionaries) = self.dictionaries.lock()
+            && !dictionaries.iter().any(|dictionary| dictionary.hash == hash)
+        {
+            tracing::debug!("registering dictionary: {}", hash);
+            dictionaries.push_back(dictionary);
+            while dictionaries.len() > self.capacity {
+                dictionaries.pop_front();
+            }
+        }
+
+        hash
+    }
+
+    /// Remove a dictionary.
+    pub fn remove(&self, hash: &DictionaryHash) {
+        if let Ok(mut dictionaries) = self.dictionaries.lock() {
+            dictionaries.retain(|dictionary| dictionary.hash != *hash);
+        }
+    }
+
+    /// Get a dictionary.
+    pub fn get(&self, hash: &DictionaryHash) -> Option<Dictionary> {
+        self.dictionaries
+            .lock()
+            .ok()
+            .and_then(|dictionaries| dictionaries.iter().find(|dictionary| dictionary.hash == *hash).cloned())
+    }
+
+    /// Whether we have a dictionary.
+    pub fn contains(&self, hash: &DictionaryHash) -> bool {
+        self.dictionaries
+            .lock()
+            .map(|dictionaries| dictionaries.iter().any(|dictionary| dictionary.hash == *hash))
+            .unwrap_or_default()
+    }
+
+    /// Get a dictionary or fail.
+    pub fn get_or_error(dictionaries: Option<&Self>, hash: &DictionaryHash) -> io::Result<Dictionary> {
+        dictionaries
+            .and_then(|dictionaries| dictionaries.get(hash))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("dictionary not found: {}", hash)))
+    }
+}
+
+impl Default for Dictionaries {
+    fn default() -> Self {
+        Self::new(DEFAULT_DICTIONARIES_CAPACITY)
+    }
+}
@@ -1,4 +1,6 @@
-use super::super::std::*;
+use super::dictionary::*;
+
+use std::fmt;
 
 /// Encodings in order from cheapest to decode to most expensive.
 pub const ENCODINGS_BY_DECODING_COST: &[Encoding] =
@@ -9,7 +11,7 @@ pub const ENCODINGS_BY_DECODING_COST: &[Encoding] =
 //
 
 /// HTTP encoding.
-#[derive(Clone, Copy, Debug, Default, Display, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
 pub enum Encoding {
     /// Identity.
     #[default]
@@ -26,4 +28,47 @@ pub enum Encoding {
 
     /// Zstandard.
     Zstandard,
+
+    /// Brotli with a dictionary.
+    ///
+    /// See [Compression Dictionary Transport](https://datatracker.ietf.org/doc/rfc9842/).
+    DictionaryBrotli(DictionaryHash),
+
+    /// Zstandard with a dictionary.
+    ///
+    /// See [Compression Dictionary Transport](https://datatracker.ietf.org/doc/rfc9842/).
+    DictionaryZstandard(DictionaryHash),
+}
+
+impl Encoding {
+    /// Dictionary hash, if we use a dictionary.
+    pub fn dictionary(&self) -> Option<&DictionaryHash> {
+        match self {
+            Self::DictionaryBrotli(hash) | Self::DictionaryZstandard(hash) => Some(hash),
+            _ => None,
+        }
+    }
+
+    /// The equivalent encoding without a dictionary.
+    pub fn without_dictionary(self) -> Self {
+        match self {
+            Self::DictionaryBrotli(_) => Self::Brotli,
+            Self::DictionaryZstandard(_) => Self::Zstandard,
+            _ => self,
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Identity => fmt::Display::fmt("Identity", formatter),
+            Self::Brotli => fmt::Display::fmt("Brotli", formatter),
+            Self::Deflate => fmt::Display::fmt("Deflate", formatter),
+            Self::GZip => fmt::Display::fmt("GZip", formatter),
+            Self::Zstandard => fmt::Display::fmt("Zstandard", formatter),
+            Self::DictionaryBrotli(hash) => write!(formatter, "DictionaryBrotli({})", hash),
+            Self::DictionaryZstandard(hash) => write!(formatter, "DictionaryZstandard({})", hash),
+        }
+    }
 }
@@ -1,4 +1,5 @@
 mod bytes;
+mod dictionary;
 mod encoding;
 
 /// Utilities for transcoding while reading.
@@ -8,4 +9,4 @@ pub mod reader;
 pub mod transcode;
 
 #[allow(unused_imports)]
-pub use encoding::*;
+pub use {dictionary::*, encoding::*};
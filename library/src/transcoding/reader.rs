@@ -77,9 +77,15 @@ where
     fn into_passthrough_reader(self) -> TranscodingReader<ReadT>;
 
     /// As encoding [TranscodingReader].
+    ///
+    /// Encodings with a dictionary are not supported and will result in passthrough. Use
+    /// [Transcode](super::transcode::Transcode) for those instead.
     fn into_encoding_reader(self, encoding: &Encoding, level: Level) -> TranscodingReader<ReadT>;
 
     /// As decoding [TranscodingReader].
+    ///
+    /// Encodings with a dictionary are not supported and will result in passthrough. Use
+    /// [Transcode](super::transcode::Transcode) for those instead.
     fn into_decoding_reader(self, encoding: &Encoding) -> TranscodingReader<ReadT>;
 }
 
@@ -134,6 +140,11 @@ where
             Encoding::Zstandard => {
                 TranscodingReader::EncodeZstandard(ZstdEncoder::with_quality(BufReader::new(self), level))
             }
+
+            Encoding::DictionaryBrotli(_) | Encoding::DictionaryZstandard(_) => {
+                tracing::warn!("not encoding to {} (unsupported for reading)", encoding);
+                self.into_passthrough_reader()
+            }
         }
     }
 
@@ -150,6 +161,11 @@ where
             Encoding::Deflate => TranscodingReader::DecodeDeflate(DeflateDecoder::new(BufReader::new(self))),
             Encoding::GZip => TranscodingReader::DecodeGZip(GzipDecoder::new(BufReader::new(self))),
             Encoding::Zstandard => TranscodingReader::DecodeZstandard(ZstdDecoder::new(BufReader::new(self))),
+
+            Encoding::DictionaryBrotli(_) | Encoding::DictionaryZstandard(_) => {
+                tracing::warn!("not decoding from {} (unsupported for reading)", encoding);
+                self.into_passthrough_reader()
+            }
         }
     }
 }
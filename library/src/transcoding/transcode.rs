@@ -1,4 +1,4 @@
-use super::encoding::*;
+use super::{dictionary::*, encoding::*};
 
 use std::io;
 
@@ -13,8 +13,37 @@ where
     Self: Sized,
 {
     /// Encode.
-    async fn encode(&self, encoding: &Encoding) -> io::Result<Self>;
+    ///
+    /// Encodings with a dictionary will fail. See
+    /// [encode_with_dictionaries](Self::encode_with_dictionaries).
+    async fn encode(&self, encoding: &Encoding) -> io::Result<Self> {
+        self.encode_with_dictionaries(encoding, None).await
+    }
 
     /// Decode.
-    async fn decode(&self, encoding: &Encoding) -> io::Result<Self>;
+    ///
+    /// Encodings with a dictionary will fail. See
+    /// [decode_with_dictionaries](Self::decode_with_dictionaries).
+    async fn decode(&self, encoding: &Encoding) -> io::Result<Self> {
+        self.decode_with_dictionaries(encoding, None).await
+    }
+
+    /// Encode.
+    ///
+    /// For encodings with a dictionary, the dictionary must be in `dictionaries`.
+    async fn encode_with_dictionaries(
+        &self,
+        encoding: &Encoding,
+        dictionaries: Option<&Dictionaries>,
+    ) -> io::Result<Self>;
+
+    /// Decode.
+    ///
+    /// For encodings with a dictionary, the dictionary (identified by the hash in the encoded
+    /// header) must be in `dictionaries`.
+    async fn decode_with_dictionaries(
+        &self,
+        encoding: &Encoding,
+        dictionaries: Option<&Dictionaries>,
+    ) -> io::Result<Self>;
 }
@@ -0,0 +1,51 @@
+use {
+    http::*,
+    kutil::{
+        http::{
+            ReadBodyIntoBytes,
+            cache::CommonCacheKey,
+            tower::caching::{testing::*, *},
+        },
+        std::immutable::*,
+    },
+    std::{fmt, future},
+    tower::{Layer, Service},
+};
+
+const BODY: &str = "Hello, world!";
+
+async fn get<ServiceT, BodyT>(service: &mut ServiceT, path: &str) -> Response<BodyT>
+where
+    ServiceT: Service<Request<FakeBody>, Response = Response<BodyT>>,
+    ServiceT::Error: fmt::Debug,
+{
+    future::poll_fn(|context| service.poll_ready(context)).await.expect("ready");
+    let request = Request::get(path).body(FakeBody::from(Bytes::new())).expect("request");
+    service.call(request).await.expect("response")
+}
+
+#[tokio::test]
+async fn second_request_is_a_hit() {
+    let upstream = FakeUpstream::default().with_response("/hello", FakeResponse::new(BODY).with_content_length());
+    let cache = RecordingCache::<CommonCacheKey>::new();
+
+    let mut service = CachingLayer::default()
+        .cache(cache.clone())
+        .min_cacheable_body_size(0)
+        .disable_encoding()
+        .x_cache(true)
+        .layer(upstream.clone());
+
+    let response = get(&mut service, "/hello").await;
+    assert_miss(&response);
+    let (body, _) = response.into_body().read_into_string(1024).await.expect("body");
+    assert_eq!(body, BODY);
+
+    let response = get(&mut service, "/hello").await;
+    assert_hit(&response);
+    let (body, _) = response.into_body().read_into_string(1024).await.expect("body");
+    assert_eq!(body, BODY);
+
+    assert_eq!(upstream.calls_for("/hello"), 1);
+    assert_eq!(cache.puts(), 1);
+}